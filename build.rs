@@ -0,0 +1,867 @@
+//! Turns the maps in `assets/maps` into data the game can use.
+//!
+//! Maps can be drawn as plain text, in a `.txt` file with one character per
+//! cell:
+//!
+//! - `#` a stone wall, `B` a brick wall, `M` a metal wall, `D` a door frame
+//! - `?`, `b` or `m` a secret stone, brick or metal wall
+//! - `R` or `Y` a door that needs the red or yellow key
+//! - `X` a switch on the wall that finishes the level when it's used
+//! - `W` a switch on the wall that works gates and bridges, `G` a gate, and
+//!   `=` a bridge over lava
+//! - `/` or `\` a wall cut from corner to corner, filling the north-west or
+//!   north-east half of its cell, and `L` or `J` one filling the south-west
+//!   or south-east half
+//! - `.` a floor under a ceiling, `,` a floor open to the sky
+//! - `S` where the player starts, facing east
+//! - `E` the exit
+//! - `^` a floor that kills the player
+//! - `~` a floor of lava, `%` a floor of acid, which both hurt the player
+//! - `0` to `9` a teleporter pad, which sends the player to the other pad
+//!   with the same digit
+//! - `C` a monitor on the wall, showing what the map's camera sees
+//! - `_` a low wall, which the player has to crouch to get under
+//! - `-` a hurdle, which the player has to jump to get over
+//! - `w` a floor under shallow water, which slows the player down
+//!
+//! After the cells and a blank line, a text map can list objects to place on
+//! it, one per line, as the kind of object and its x and y position in cells.
+//! The kind is `spawn` for where the player starts, or any of the kinds of
+//! entity that [`entity`] knows about. A spawn can be followed by which way
+//! the player faces, in degrees anticlockwise from east, instead of using an
+//! `S` cell:
+//!
+//! ```text
+//! spawn 1.5 1.5 270
+//! slime 8.5 4.5
+//! ```
+//!
+//! Any other object can be followed by which way it faces too, which picks
+//! which side of an enemy the player sees first:
+//!
+//! ```text
+//! slime 8.5 4.5 90
+//! ```
+//!
+//! A `camera` object is where the view on the map's monitors is seen from,
+//! and can be followed by which way it faces just like a spawn:
+//!
+//! ```text
+//! camera 12.5 1.5 180
+//! ```
+//!
+//! A `trigger` line sets off an event when the player first walks into a
+//! cell. It's followed by the cell's x and y, and then the event: `message`
+//! and some text to show, which is broken into lines to fit the message box,
+//! `spawn` and the kind of entity to place along with its x and y, or
+//! `close` and the x and y of a cell to close a gate across:
+//!
+//! ```text
+//! trigger 8 9 message The gate slams shut behind you!
+//! trigger 8 9 spawn slime 6.5 6.5
+//! trigger 8 9 close 8 10
+//! ```
+//!
+//! A `floor` line paints a pattern across a rectangle of the floor. It's
+//! followed by the x and y of the rectangle's top-left cell, its width and
+//! height in cells, and the pattern: `flagstones`, `planks` or `grate`. The
+//! pattern can be turned clockwise by a number of quarter turns, and
+//! flipped from left to right with `flip` before it's turned:
+//!
+//! ```text
+//! floor 5 5 7 5 planks 1 flip
+//! ```
+//!
+//! A `tag` object gives the cell it's in a tag number instead of a facing.
+//! Using a switch flips every cell sharing its tag: gates open and close,
+//! bridges rise out of the lava or sink back into it, and floors or lava
+//! with a tag become gates or bridges.
+//!
+//! Or they can be made in the [Tiled](https://www.mapeditor.org) editor and
+//! saved as a `.tmx` file. The tile layer has to be saved as CSV, and uses
+//! the tiles in `tiles.tsx`, which are the characters above in the order of
+//! [`TILESET`]. Objects are placed just like in text maps, with their class
+//! set to the kind of object, and a spawn rotated to face the way the player
+//! should. Teleporter pads are placed as objects too, with their class set to
+//! `teleporter` and their name set to the digit linking them with their
+//! partner. Tags are placed the same way with their class set to `tag` and
+//! their name set to the tag number, triggers with their class set to
+//! `trigger` and their name set to the event, and floor patterns as
+//! rectangles with their class set to `floor` and their name set to the
+//! pattern.
+//!
+//! Every map needs exactly one spawn, placed either as an `S` or as an
+//! object, and a way out: either one `E`, or at least one `X`. The spawn
+//! can't be inside a wall, a map with monitors needs a camera, the edge of
+//! the map has to be walled off all the way around, every colored door needs
+//! its key placed somewhere, and every tag needs both a switch and something
+//! for it to work. Each file becomes a `MapAsset` constant named after the
+//! file, so `level1.txt` becomes `LEVEL1`.
+//!
+//! Maps with more cells than fit in memory at once, up to 64x64, are cut
+//! into chunks of 8x8 cells, each packed on its own so the game can stream
+//! them in around the player. Chunks hanging off the edge of the map are
+//! padded out with stone.
+
+use std::{env, fmt::Write as _, fs, path::Path};
+
+const MAPS_DIR: &str = "assets/maps";
+
+/// The most cells a map can have before it has to be streamed in chunks, and
+/// the most a streamed map can have, matching the limits in `src/map.rs`.
+const MAX_CELLS: usize = 1024;
+const MAX_STREAMED_CELLS: usize = 64 * 64;
+
+/// How many cells wide and tall each chunk of a streamed map is.
+const CHUNK_SIZE: usize = 8;
+
+/// The most triggers a map can have, one for each bit the game keeps to
+/// remember which have gone off.
+const MAX_TRIGGERS: usize = 32;
+/// How many lines of how many characters fit in the message box.
+const MESSAGE_LINES: usize = 4;
+const MESSAGE_WIDTH: usize = 18;
+
+/// The character for each tile in `tiles.tsx`, in order.
+const TILESET: &str = ".,#BMDSE^?bmRY~%X/\\LJWG=C_-w";
+
+/// The bits of a Tiled tile ID that say how the tile is flipped, which don't
+/// matter here.
+const FLIP_FLAGS: u32 = 0xf000_0000;
+
+fn main() {
+    println!("cargo:rerun-if-changed={MAPS_DIR}");
+
+    let mut paths: Vec<_> = fs::read_dir(MAPS_DIR)
+        .expect("couldn't read the maps directory")
+        .map(|entry| entry.expect("couldn't read a map").path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "txt" || ext == "tmx")
+        })
+        .collect();
+    paths.sort();
+
+    let mut out = String::new();
+    for path in &paths {
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let source = fs::read_to_string(path).expect("couldn't read a map");
+        let name = path.file_stem().unwrap().to_string_lossy().to_uppercase();
+        let map = if path.extension().is_some_and(|ext| ext == "tmx") {
+            parse_tiled(&source)
+        } else {
+            parse_text(&source)
+        };
+        let map = map.unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+
+        writeln!(out, "/// Built from `{}`.", path.display()).unwrap();
+        writeln!(out, "pub const {name}: MapAsset = {map};").unwrap();
+    }
+
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("maps.rs");
+    fs::write(out_path, out).expect("couldn't write the maps");
+}
+
+/// Parse a plain-text map, returning the Rust expression for its `MapAsset`.
+fn parse_text(source: &str) -> Result<String, String> {
+    let mut lines = source.lines().skip_while(|line| line.is_empty());
+    let rows: Vec<&str> = lines.by_ref().take_while(|line| !line.is_empty()).collect();
+
+    let mut objects = Objects::default();
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        if let Some(trigger) = line.strip_prefix("trigger ") {
+            let mut parts = trigger.trim().splitn(3, ' ');
+            let mut cell = || parts.next().and_then(|word| word.parse::<usize>().ok());
+            let (Some(x), Some(y)) = (cell(), cell()) else {
+                return Err(format!("'{line}' needs the x and y of a cell"));
+            };
+            let event = parts.next().unwrap_or_default();
+            objects.triggers.push(((x, y), event_expr(event)?));
+            continue;
+        }
+
+        if let Some(floor) = line.strip_prefix("floor ") {
+            let mut parts = floor.trim().splitn(5, ' ');
+            let mut number = || parts.next().and_then(|word| word.parse::<i32>().ok());
+            let (Some(x), Some(y), Some(width), Some(height)) =
+                (number(), number(), number(), number())
+            else {
+                return Err(format!("'{line}' needs an x, a y, a width and a height"));
+            };
+            let pattern = parts.next().unwrap_or_default();
+            let area = floor_expr((x, y), (width, height), pattern)?;
+            write!(objects.floors, "{area}, ").unwrap();
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let kind = words.next().unwrap_or_default();
+        let numbers = words
+            .map(|word| {
+                word.parse::<f32>()
+                    .map_err(|_| format!("'{word}' isn't a number"))
+            })
+            .collect::<Result<Vec<f32>, String>>()?;
+
+        let (x, y, facing) = match numbers[..] {
+            [x, y] => (x, y, 0.0),
+            [x, y, facing] => (x, y, facing),
+            _ => return Err(format!("'{line}' needs a kind of object, an x and a y")),
+        };
+        if kind == "tag" {
+            objects.tags.push(((x as usize, y as usize), facing as u8));
+        } else {
+            place_object(kind, x, y, facing, &mut objects)?;
+        }
+    }
+
+    parse_cells(&rows, objects)
+}
+
+/// Parse a map saved by Tiled, returning the Rust expression for its `MapAsset`.
+fn parse_tiled(source: &str) -> Result<String, String> {
+    let map = tags(source, "map").next().ok_or("there's no <map>")?;
+    let number = |tag, name| {
+        attribute(tag, name)
+            .and_then(|value| value.parse::<u32>().ok())
+            .ok_or(format!("the map needs a {name}"))
+    };
+    let map_width = number(map, "width")? as usize;
+    let tile_width = number(map, "tilewidth")? as f32;
+    let tile_height = number(map, "tileheight")? as f32;
+
+    let tileset = tags(source, "tileset").next().ok_or("there's no tileset")?;
+    let first_gid = number(tileset, "firstgid")?;
+
+    let data = tags(source, "data").next().ok_or("there's no tile layer")?;
+    if attribute(data, "encoding") != Some("csv") {
+        return Err("the tile layer needs to be saved as CSV".into());
+    }
+
+    let csv = contents(source, "data").unwrap_or_default();
+    let mut cells = csv
+        .split(',')
+        .map(|gid| {
+            let gid = gid
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("'{}' isn't a tile", gid.trim()))?;
+            let idx = (gid & !FLIP_FLAGS)
+                .checked_sub(first_gid)
+                .unwrap_or(u32::MAX);
+            TILESET
+                .chars()
+                .nth(idx as usize)
+                .ok_or(format!("tile {gid} isn't in the tileset"))
+        })
+        .collect::<Result<Vec<char>, String>>()?;
+
+    let mut objects = Objects::default();
+    for object in tags(source, "object") {
+        // Tiled has called an object's class both of these over the years.
+        let kind = attribute(object, "type")
+            .or_else(|| attribute(object, "class"))
+            .ok_or("every object needs a class")?;
+
+        let number = |name| attribute(object, name).and_then(|value| value.parse::<f32>().ok());
+        let (Some(mut x), Some(mut y)) = (number("x"), number("y")) else {
+            return Err(format!("the {kind} needs a position"));
+        };
+
+        // place the entity in the middle of the object's box, if it has one.
+        let (width, height) = (
+            number("width").unwrap_or(0.0),
+            number("height").unwrap_or(0.0),
+        );
+        x += width / 2.0;
+        // tile objects are positioned by their bottom-left corner instead.
+        y += if attribute(object, "gid").is_some() {
+            -height / 2.0
+        } else {
+            height / 2.0
+        };
+
+        // teleporters are marked in the cells, just like in a plain-text map.
+        if kind == "teleporter" {
+            let link = attribute(object, "name")
+                .and_then(|name| name.parse::<char>().ok())
+                .filter(char::is_ascii_digit)
+                .ok_or("every teleporter needs a digit for its name")?;
+            let (cell_x, cell_y) = ((x / tile_width) as usize, (y / tile_height) as usize);
+            let cell = (cell_x < map_width)
+                .then(|| cells.get_mut(cell_y * map_width + cell_x))
+                .flatten()
+                .ok_or(format!("teleporter {link} is off the map"))?;
+            *cell = link;
+            continue;
+        }
+
+        if kind == "floor" {
+            let pattern = attribute(object, "name").unwrap_or_default();
+            let corner = (
+                (x - width / 2.0) / tile_width,
+                (y - height / 2.0) / tile_height,
+            );
+            let size = (width / tile_width, height / tile_height);
+            let area = floor_expr(
+                (corner.0.round() as i32, corner.1.round() as i32),
+                (size.0.round() as i32, size.1.round() as i32),
+                pattern,
+            )?;
+            write!(objects.floors, "{area}, ").unwrap();
+            continue;
+        }
+
+        if kind == "trigger" {
+            let event = attribute(object, "name").unwrap_or_default();
+            let cell = ((x / tile_width) as usize, (y / tile_height) as usize);
+            objects.triggers.push((cell, event_expr(event)?));
+            continue;
+        }
+
+        if kind == "tag" {
+            let tag = attribute(object, "name")
+                .and_then(|name| name.parse::<u8>().ok())
+                .ok_or("every tag needs a number for its name")?;
+            let cell = ((x / tile_width) as usize, (y / tile_height) as usize);
+            objects.tags.push((cell, tag));
+            continue;
+        }
+
+        // Tiled turns objects clockwise, rather than anticlockwise.
+        let facing = -number("rotation").unwrap_or(0.0);
+        place_object(kind, x / tile_width, y / tile_height, facing, &mut objects)?;
+    }
+
+    let rows: Vec<String> = cells
+        .chunks(map_width)
+        .map(|row| row.iter().collect())
+        .collect();
+    let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+
+    parse_cells(&rows, objects)
+}
+
+/// Everything placed on a map as an object, rather than as a cell.
+#[derive(Default)]
+struct Objects {
+    /// The Rust expressions for each entity, separated by commas.
+    entities: String,
+    /// Where the player starts, and which way they face, if it's been placed.
+    spawn: Option<(f32, f32, f32)>,
+    /// Where the view on the map's monitors is seen from, and which way it
+    /// faces, if it's been placed.
+    camera: Option<(f32, f32, f32)>,
+    /// The kinds of key placed, to check that every door can be opened.
+    keys: Vec<String>,
+    /// The cells given a tag number, linking switches with what they work.
+    tags: Vec<((usize, usize), u8)>,
+    /// The cells with triggers, and the Rust expression for each one's event.
+    triggers: Vec<((usize, usize), String)>,
+    /// The Rust expressions for the patterns painted across the floor,
+    /// separated by commas.
+    floors: String,
+}
+
+/// Place an object at (x, y) on a map facing `facing` degrees anticlockwise
+/// from east, either setting the player's spawn or the camera, or adding to
+/// its entities.
+fn place_object(
+    kind: &str,
+    x: f32,
+    y: f32,
+    facing: f32,
+    objects: &mut Objects,
+) -> Result<(), String> {
+    if kind == "spawn" {
+        if objects.spawn.is_some() {
+            return Err("there's more than one spawn".into());
+        }
+        objects.spawn = Some((x, y, facing.to_radians()));
+    } else if kind == "camera" {
+        if objects.camera.is_some() {
+            return Err("there's more than one camera".into());
+        }
+        objects.camera = Some((x, y, facing.to_radians()));
+    } else {
+        let entity =
+            entity(kind, x, y).ok_or(format!("there's no kind of entity called '{kind}'"))?;
+        if facing == 0.0 {
+            write!(objects.entities, "{entity}, ").unwrap();
+        } else {
+            let radians = facing.to_radians();
+            write!(objects.entities, "{entity}.facing({radians:?}), ").unwrap();
+        }
+        if kind.ends_with("_key") {
+            objects.keys.push(kind.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// The Rust expression for an entity of a kind that can be placed on a map.
+fn entity(kind: &str, x: f32, y: f32) -> Option<String> {
+    const ENTITY: &str = "crate::entities::Entity";
+    const SPRITE: &str = "crate::sprites::Graphic::Sprite";
+    const SLIME: &str = "crate::sprites::Graphic::Directional(&crate::sprites::SLIME_VIEWS)";
+    const ITEM: &str = "crate::items::Item";
+
+    Some(match kind {
+        "pillar" => format!("{ENTITY}::new({x:?}, {y:?}, {SPRITE}(&crate::sprites::PILLAR))"),
+        "crate" => format!("{ENTITY}::new({x:?}, {y:?}, {SPRITE}(&crate::sprites::CRATE))"),
+        "exit_door" => format!("{ENTITY}::new({x:?}, {y:?}, {SPRITE}(&crate::sprites::EXIT_DOOR))"),
+        "spikes" => format!("{ENTITY}::new({x:?}, {y:?}, {SPRITE}(&crate::sprites::SPIKES))"),
+        "statue" => format!(
+            "{ENTITY}::new({x:?}, {y:?}, crate::sprites::Graphic::Composite(&crate::sprites::STATUE))"
+        ),
+        "slime" => format!("{ENTITY}::enemy({x:?}, {y:?}, {SLIME}, crate::entities::SLIME_HEALTH)"),
+        "boss" => format!("{ENTITY}::boss({x:?}, {y:?}, {SLIME}, crate::entities::BOSS_HEALTH)"),
+        "red_key" => format!("{ENTITY}::item({x:?}, {y:?}, {ITEM}::Key(crate::keys::Key::Red))"),
+        "yellow_key" => {
+            format!("{ENTITY}::item({x:?}, {y:?}, {ITEM}::Key(crate::keys::Key::Yellow))")
+        }
+        "health" => {
+            format!("{ENTITY}::item({x:?}, {y:?}, {ITEM}::Health(crate::items::HEALTH_PACK_HEALS))")
+        }
+        "ammo" => {
+            format!("{ENTITY}::item({x:?}, {y:?}, {ITEM}::Ammo(crate::items::AMMO_BOX_ROUNDS))")
+        }
+        "treasure" => format!("{ENTITY}::item({x:?}, {y:?}, {ITEM}::Treasure)"),
+        _ => return None,
+    })
+}
+
+/// The Rust expression for a trigger's event, written as the kind of event
+/// followed by whatever it needs.
+fn event_expr(event: &str) -> Result<String, String> {
+    const EVENT: &str = "crate::triggers::Event";
+
+    let (kind, rest) = event.trim().split_once(' ').unwrap_or((event.trim(), ""));
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    let number = |word: &str| {
+        word.parse::<f32>()
+            .map_err(|_| format!("'{word}' isn't a number"))
+    };
+
+    match (kind, &words[..]) {
+        ("message", [_, ..]) => Ok(format!("{EVENT}::Message({:?})", wrap_message(rest)?)),
+        ("spawn", &[kind, x, y]) => {
+            let entity = entity(kind, number(x)?, number(y)?)
+                .ok_or(format!("there's no kind of entity called '{kind}'"))?;
+            Ok(format!("{EVENT}::Spawn({entity})"))
+        }
+        ("close", &[x, y]) => Ok(format!(
+            "{EVENT}::Close({}, {})",
+            number(x)? as i32,
+            number(y)? as i32
+        )),
+        _ => Err(format!("'{event}' isn't an event")),
+    }
+}
+
+/// The Rust expression for a pattern painted across a rectangle of the floor,
+/// from its top-left cell, its size in cells, and the pattern, written as its
+/// name and then optionally how many quarter turns it's turned and `flip`.
+fn floor_expr(corner: (i32, i32), size: (i32, i32), pattern: &str) -> Result<String, String> {
+    let mut words = pattern.split_whitespace();
+    let texture = match words.next().unwrap_or_default() {
+        "flagstones" => "Flagstones",
+        "planks" => "Planks",
+        "grate" => "Grate",
+        texture => return Err(format!("there's no floor pattern called '{texture}'")),
+    };
+
+    let mut turns = 0;
+    let mut flipped = false;
+    for word in words {
+        match word.parse::<u8>() {
+            Ok(quarters) => turns = quarters % 4,
+            Err(_) if word == "flip" => flipped = true,
+            Err(_) => return Err(format!("'{word}' isn't a way to turn a floor pattern")),
+        }
+    }
+
+    let ((x, y), (width, height)) = (corner, size);
+    if width <= 0 || height <= 0 {
+        return Err(format!("the floor pattern at ({x}, {y}) is empty"));
+    }
+
+    Ok(format!(
+        "crate::floors::FloorArea {{ \
+            x: {x}, y: {y}, width: {width}, height: {height}, \
+            texture: crate::floors::FloorTexture::{texture}, \
+            turns: {turns}, flipped: {flipped} \
+        }}"
+    ))
+}
+
+/// Break a message into lines that fit in the message box.
+///
+/// Messages are revealed a byte at a time, so anything outside ASCII, which
+/// would be cut in half partway through, is turned away.
+fn wrap_message(message: &str) -> Result<String, String> {
+    if let Some(c) = message.chars().find(|c| !c.is_ascii()) {
+        return Err(format!("'{c}' can't be shown in a message"));
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+
+    for word in message.split_whitespace() {
+        if word.len() > MESSAGE_WIDTH {
+            return Err(format!("'{word}' is too long to fit in a message"));
+        }
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= MESSAGE_WIDTH => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.into()),
+        }
+    }
+
+    if lines.len() > MESSAGE_LINES {
+        return Err(format!("'{message}' is too long to fit in a message"));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Find every start tag called `name`, returning the text inside each one.
+fn tags<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> {
+    xml.match_indices('<').filter_map(move |(start, _)| {
+        let rest = xml[start + 1..].strip_prefix(name)?;
+        if !rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+            return None;
+        }
+        Some(&rest[..rest.find('>')?])
+    })
+}
+
+/// Get the value of one of a tag's attributes.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{name}=\"");
+    let (start, _) = tag
+        .match_indices(&pattern)
+        .find(|&(idx, _)| tag[..idx].ends_with(char::is_whitespace))?;
+
+    let value = &tag[start + pattern.len()..];
+    Some(&value[..value.find('"')?])
+}
+
+/// Get everything between the first element called `name`'s start and end tags.
+fn contents<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{name}"))?;
+    let start = start + xml[start..].find('>')? + 1;
+    let end = start + xml[start..].find(&format!("</{name}>"))?;
+    Some(&xml[start..end])
+}
+
+/// Parse a map's cells, given as rows of the characters that plain-text maps
+/// use, returning the Rust expression for its `MapAsset` along with the
+/// `objects` placed on it. Each teleporter pad gets an entity marking it too.
+///
+/// Maps that would trap the player or let rays run off the edge are turned
+/// away here, so a broken map never makes it into the game.
+fn parse_cells(rows: &[&str], objects: Objects) -> Result<String, String> {
+    let height = rows.len();
+    let width = rows.first().map_or(0, |row| row.len());
+
+    if width == 0 || width > 255 || height > 255 {
+        return Err(format!("a map can't be {width}x{height}"));
+    }
+
+    let mut cells = Vec::with_capacity(width * height);
+    let mut spawn_cell = None;
+    let mut exit = None;
+    let mut has_switch = false;
+    let mut has_monitor = false;
+    let mut secrets = String::new();
+    let mut hazards = String::new();
+    let mut pads: [Vec<(usize, usize)>; 10] = Default::default();
+
+    for (y, row) in rows.iter().enumerate() {
+        if row.len() != width {
+            return Err(format!("row {y} isn't {width} cells wide"));
+        }
+
+        for (x, cell) in row.chars().enumerate() {
+            let tile = match cell {
+                '#' => "STONE",
+                'B' => "BRICK",
+                'M' => "METAL",
+                'D' => "DOOR_FRAME",
+                'R' => "RED_DOOR",
+                'Y' => "YELLOW_DOOR",
+                '/' => "DIAGONAL_NW",
+                '\\' => "DIAGONAL_NE",
+                'L' => "DIAGONAL_SW",
+                'J' => "DIAGONAL_SE",
+                'X' => {
+                    has_switch = true;
+                    "EXIT_SWITCH"
+                }
+                'W' => "SWITCH",
+                'G' => "GATE",
+                '=' => "BRIDGE",
+                '_' => "LOW_WALL",
+                '-' => "HURDLE",
+                'w' => "WATER",
+                'C' => {
+                    has_monitor = true;
+                    "MONITOR"
+                }
+                '?' | 'b' | 'm' => {
+                    write!(secrets, "({x}, {y}), ").unwrap();
+                    match cell {
+                        '?' => "STONE",
+                        'b' => "BRICK",
+                        _ => "METAL",
+                    }
+                }
+                '.' => "FLOOR",
+                ',' => "SKY",
+                '~' => "LAVA",
+                '%' => "ACID",
+                'S' if spawn_cell.is_none() => {
+                    spawn_cell = Some((x, y));
+                    "FLOOR"
+                }
+                'E' if exit.is_none() => {
+                    exit = Some((x, y));
+                    "FLOOR"
+                }
+                'S' | 'E' => return Err(format!("more than one '{cell}'")),
+                '^' => {
+                    write!(hazards, "({x}, {y}), ").unwrap();
+                    "FLOOR"
+                }
+                '0'..='9' => {
+                    pads[cell as usize - '0' as usize].push((x, y));
+                    "FLOOR"
+                }
+                _ => return Err(format!("unknown cell '{cell}' at ({x}, {y})")),
+            };
+
+            let on_edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            if on_edge && !is_wall(tile) {
+                return Err(format!(
+                    "there's a gap in the edge of the map at ({x}, {y})"
+                ));
+            }
+            cells.push(tile);
+        }
+    }
+
+    for (door, key) in [("RED_DOOR", "red_key"), ("YELLOW_DOOR", "yellow_key")] {
+        if cells.contains(&door) && !objects.keys.iter().any(|placed| placed == key) {
+            return Err(format!("there's a {door} but no {key} to open it"));
+        }
+    }
+
+    let mut teleporters = String::new();
+    let mut entities = objects.entities;
+    let floors = objects.floors;
+    for (link, pads) in pads.iter().enumerate() {
+        match pads[..] {
+            [] => {}
+            [(from_x, from_y), (to_x, to_y)] => {
+                write!(teleporters, "(({from_x}, {from_y}), ({to_x}, {to_y})), ").unwrap();
+                for (x, y) in [(from_x, from_y), (to_x, to_y)] {
+                    write!(
+                        entities,
+                        "crate::entities::Entity::new({x}.5, {y}.5, \
+                            crate::sprites::Graphic::Sprite(&crate::teleporters::PAD)), "
+                    )
+                    .unwrap();
+                }
+            }
+            _ => return Err(format!("teleporter {link} needs exactly two pads")),
+        }
+    }
+
+    let (spawn_x, spawn_y, spawn_facing) = match (objects.spawn, spawn_cell) {
+        (Some(spawn), None) => spawn,
+        (None, Some((x, y))) => (x as f32 + 0.5, y as f32 + 0.5, 0.0),
+        (Some(_), Some(_)) => return Err("there's more than one spawn".into()),
+        (None, None) => return Err("there's nowhere for the player to start".into()),
+    };
+    let tags = link_tags(&cells, width, height, &objects.tags)?;
+
+    if objects.triggers.len() > MAX_TRIGGERS {
+        return Err(format!("there are more than {MAX_TRIGGERS} triggers"));
+    }
+    let mut triggers = String::new();
+    for ((x, y), event) in &objects.triggers {
+        if *x >= width || *y >= height || is_wall(cells[y * width + x]) {
+            return Err(format!("the trigger at ({x}, {y}) can't be walked into"));
+        }
+        write!(
+            triggers,
+            "crate::triggers::Trigger {{ cell: ({x}, {y}), event: {event} }}, "
+        )
+        .unwrap();
+    }
+
+    let start_cell = (spawn_x >= 0.0 && spawn_y >= 0.0)
+        .then_some((spawn_x as usize, spawn_y as usize))
+        .filter(|&(x, y)| x < width && y < height);
+    match start_cell {
+        Some((x, y))
+            if !is_wall(cells[y * width + x]) && !cells[y * width + x].starts_with("DIAGONAL") => {}
+        _ => {
+            return Err(format!(
+                "the player would start inside a wall at ({spawn_x}, {spawn_y})"
+            ))
+        }
+    }
+
+    let camera = match objects.camera {
+        Some((x, y, facing)) => {
+            let cell = (x >= 0.0 && y >= 0.0)
+                .then_some((x as usize, y as usize))
+                .filter(|&(x, y)| x < width && y < height);
+            if !cell.is_some_and(|(x, y)| !is_wall(cells[y * width + x])) {
+                return Err(format!("the camera would be inside a wall at ({x}, {y})"));
+            }
+            format!("Some(({x:?}, {y:?}, {facing:?}))")
+        }
+        None if has_monitor => return Err("there are monitors but no camera".into()),
+        None => "None".into(),
+    };
+
+    let exit = match exit {
+        Some((x, y)) => format!("Some(({x}, {y}))"),
+        None if has_switch => "None".into(),
+        None => return Err("there's no exit".into()),
+    };
+
+    let (tiles, chunks) = if width * height <= MAX_CELLS {
+        (run_length_encode(&cells), String::new())
+    } else if width * height <= MAX_STREAMED_CELLS {
+        (String::new(), pack_chunks(&cells, width, height))
+    } else {
+        return Err(format!("a {width}x{height} map is too big to stream"));
+    };
+
+    Ok(format!(
+        "MapAsset {{ \
+            tiles: &[{width}, {height}, {tiles}], \
+            chunks: &[{chunks}], \
+            spawn: ({spawn_x:?}, {spawn_y:?}, {spawn_facing:?}), \
+            exit: {exit}, \
+            camera: {camera}, \
+            secrets: &[{secrets}], \
+            hazards: &[{hazards}], \
+            teleporters: &[{teleporters}], \
+            tags: &[{tags}], \
+            triggers: &[{triggers}], \
+            floors: &[{floors}], \
+            entities: &[{entities}] \
+        }}"
+    ))
+}
+
+/// Check that every tagged cell on a map can be worked by a switch, and that
+/// every tag has a switch and something for it to work, returning the Rust
+/// expressions for the tags.
+fn link_tags(
+    cells: &[&str],
+    width: usize,
+    height: usize,
+    tags: &[((usize, usize), u8)],
+) -> Result<String, String> {
+    let mut linked = String::new();
+    let mut switches = Vec::new();
+    let mut targets = Vec::new();
+
+    for &((x, y), tag) in tags {
+        if x >= width || y >= height {
+            return Err(format!("tag {tag} is off the map"));
+        }
+
+        match cells[y * width + x] {
+            "SWITCH" => switches.push(tag),
+            "GATE" | "BRIDGE" | "FLOOR" | "LAVA" => targets.push(tag),
+            tile => return Err(format!("a switch can't work the {tile} at ({x}, {y})")),
+        }
+        write!(linked, "(({x}, {y}), {tag}), ").unwrap();
+    }
+
+    for (idx, &tile) in cells.iter().enumerate() {
+        let (x, y) = (idx % width, idx / width);
+        if tile == "SWITCH" && !tags.iter().any(|&(cell, _)| cell == (x, y)) {
+            return Err(format!("the switch at ({x}, {y}) needs a tag"));
+        }
+    }
+    if let Some(tag) = switches.iter().find(|tag| !targets.contains(tag)) {
+        return Err(format!("tag {tag} has a switch but nothing for it to work"));
+    }
+    if let Some(tag) = targets.iter().find(|tag| !switches.contains(tag)) {
+        return Err(format!("tag {tag} has no switch to work it"));
+    }
+
+    Ok(linked)
+}
+
+/// Check if a tile, named as it is in `src/map.rs`, fills its whole cell.
+fn is_wall(tile: &str) -> bool {
+    matches!(
+        tile,
+        "STONE"
+            | "BRICK"
+            | "METAL"
+            | "DOOR_FRAME"
+            | "RED_DOOR"
+            | "YELLOW_DOOR"
+            | "EXIT_SWITCH"
+            | "SWITCH"
+            | "GATE"
+            | "MONITOR"
+    )
+}
+
+/// Pack a map's tiles into runs of the same tile, written as how long the run
+/// is and then the tile, in the format that `Map::load` takes.
+fn run_length_encode(cells: &[&str]) -> String {
+    let mut runs = String::new();
+
+    let mut idx = 0;
+    while idx < cells.len() {
+        let tile = cells[idx];
+        let length = cells[idx..]
+            .iter()
+            .take(255)
+            .take_while(|&&cell| cell == tile)
+            .count();
+        write!(runs, "{length}, {tile}, ").unwrap();
+        idx += length;
+    }
+
+    runs
+}
+
+/// Cut a map's tiles into chunks, row by row, packing each one into runs of
+/// the same tile, in the format that `Map::stream` takes.
+fn pack_chunks(cells: &[&str], width: usize, height: usize) -> String {
+    let mut chunks = String::new();
+
+    for chunk_y in (0..height).step_by(CHUNK_SIZE) {
+        for chunk_x in (0..width).step_by(CHUNK_SIZE) {
+            let mut chunk = Vec::with_capacity(CHUNK_SIZE * CHUNK_SIZE);
+            for y in chunk_y..chunk_y + CHUNK_SIZE {
+                for x in chunk_x..chunk_x + CHUNK_SIZE {
+                    let on_map = x < width && y < height;
+                    chunk.push(if on_map {
+                        cells[y * width + x]
+                    } else {
+                        "STONE"
+                    });
+                }
+            }
+            write!(chunks, "&[{}], ", run_length_encode(&chunk)).unwrap();
+        }
+    }
+
+    chunks
+}
@@ -0,0 +1,28 @@
+//! Angles kept between zero and a full turn.
+//!
+//! Turning the same way for long enough would otherwise wind the player's
+//! heading up without end, losing precision as it grows, so it's wrapped
+//! back around after every update. Working out which way a ray points, or
+//! how far around the skybox it is, can then go through [`normalize`]
+//! rather than each doing its own wrapping.
+
+use core::f32::consts::TAU;
+
+use crate::math::floorf;
+
+/// Wrap an angle around to the same direction in `[0, TAU)`.
+pub fn normalize(angle: f32) -> f32 {
+    let wrapped = angle - floorf(angle / TAU) * TAU;
+    // a tiny negative angle can round up to a whole turn.
+    if wrapped >= TAU {
+        0.0
+    } else {
+        wrapped
+    }
+}
+
+/// How far around a full turn an angle is, from 0.0 up to but not
+/// including 1.0.
+pub fn turns(angle: f32) -> f32 {
+    normalize(angle) / TAU
+}
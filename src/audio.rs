@@ -0,0 +1,108 @@
+//! Sounds that come from somewhere on the map, rather than from the player.
+//!
+//! They get quieter and a little lower the further away they are, and are
+//! muffled when there are walls in the way, so an enemy around the corner
+//! or behind a closed door sounds further off than one out in the open, and
+//! one deep inside the walls can't be heard at all. Working out how sound
+//! really bounces around would be far too slow, so it only counts the walls
+//! on a straight line between the sound and the player.
+
+use crate::{
+    map::{is_wall, GridWalk, WorldMap},
+    math::{distance, fabsf, floorf},
+    sfx::{Channel, Pan, TONE_CHANNEL},
+    State,
+};
+
+/// Sounds further away than this, in map units, can't be heard at all.
+const HEARING_DISTANCE: f32 = 12.0;
+/// How many walls a sound can pass through and still be heard clearly.
+const MAX_CLEAR_WALLS: u32 = 0;
+/// How many walls a sound can pass through and still be heard at all.
+const MAX_HEARD_WALLS: u32 = 2;
+/// How much lower a sound is at the edge of hearing than right next to the
+/// player, as a fraction of its pitch.
+const PITCH_DROP: f32 = 0.2;
+/// How much quieter a muffled sound is.
+const MUFFLED_VOLUME: f32 = 0.4;
+/// Sounds more than this far off to one side, for every unit they are in
+/// front of or behind the player, are panned to that side.
+const PAN_SLOPE: f32 = 0.5;
+
+/// Scale the pitch of a tone's `frequency` by `factor`, moving both ends of
+/// a sweep so that it still slides the same way.
+fn scale_frequency(frequency: u32, factor: f32) -> u32 {
+    let scale = |part: u32| (part as f32 * factor) as u32;
+    scale(frequency & 0xffff) | (scale(frequency >> 16) << 16)
+}
+
+/// Count the wall cells on the straight line between two points, not
+/// counting the cells the points are in.
+pub fn walls_between(map: &impl WorldMap, from: (f32, f32), to: (f32, f32)) -> u32 {
+    let (x, y) = (floorf(from.0) as i32, floorf(from.1) as i32);
+    let end = (floorf(to.0) as i32, floorf(to.1) as i32);
+    let steps = (end.0 - x).abs() + (end.1 - y).abs();
+
+    GridWalk::new(from, (to.0 - from.0, to.1 - from.1))
+        .take(steps as usize)
+        .take_while(|&(cell, _, _)| cell != end)
+        .filter(|&((x, y), _, _)| is_wall(map.tile_at(x, y)))
+        .count() as u32
+}
+
+impl State {
+    /// Work out how a tone coming from the point (x, y) on the map sounds to
+    /// the player, returning the `frequency`, `volume` and `flags` to play it
+    /// with, or `None` if it can't be heard at all.
+    ///
+    /// It's quieter and lower the further the point is from the player, and
+    /// if there are walls in the way, it's quieter still, an octave lower
+    /// and moved off the pulse channels onto the softer triangle channel.
+    /// Behind too many walls, it isn't played at all. Sounds well off to one
+    /// side only come out of the speaker on that side.
+    pub fn heard_from(
+        &self,
+        x: f32,
+        y: f32,
+        frequency: u32,
+        volume: u32,
+        flags: u32,
+    ) -> Option<(u32, u32, u32)> {
+        let away = distance(x - self.player_x, y - self.player_y);
+        if away >= HEARING_DISTANCE {
+            return None;
+        }
+
+        let walls = walls_between(&self.map, (x, y), (self.player_x, self.player_y));
+        if walls > MAX_HEARD_WALLS {
+            return None;
+        }
+
+        let falloff = away / HEARING_DISTANCE;
+        let mut volume = volume as f32 * (1.0 - falloff);
+        let mut frequency = scale_frequency(frequency, 1.0 - falloff * PITCH_DROP);
+        let mut flags = flags;
+
+        if walls > MAX_CLEAR_WALLS {
+            volume *= MUFFLED_VOLUME;
+            frequency = scale_frequency(frequency, 0.5);
+            // both pulse channels come before the triangle one.
+            if flags & TONE_CHANNEL <= Channel::Pulse2 as u32 {
+                flags = (flags & !TONE_CHANNEL) | Channel::Triangle as u32;
+            }
+        }
+
+        let (depth, mut lateral) = self.to_camera(x, y);
+        if self.mutators.mirror {
+            lateral = -lateral;
+        }
+        if lateral > fabsf(depth) * PAN_SLOPE {
+            flags |= Pan::Right as u32;
+        } else if lateral < -fabsf(depth) * PAN_SLOPE {
+            flags |= Pan::Left as u32;
+        }
+
+        // there's no point taking over a channel with something silent.
+        (volume >= 1.0).then_some((frequency, volume as u32, flags))
+    }
+}
@@ -0,0 +1,49 @@
+//! Walking forward without holding the button down, for long corridors or
+//! anyone who'd rather not hold up the whole time.
+//!
+//! Double-tapping forward turns it on, and the player then walks as if
+//! forward were held down, sliding along walls like usual. Tapping back,
+//! double-tapping forward again, or walking straight into a wall turns it
+//! back off.
+
+use crate::{double_tap::DoubleTap, State, BUTTON_DOWN, BUTTON_UP};
+
+/// Whether the player is walking forward on their own, and the taps on
+/// forward that turn it on and off.
+pub struct AutoWalk {
+    enabled: bool,
+    forward: DoubleTap,
+}
+
+impl AutoWalk {
+    pub const fn new() -> Self {
+        Self {
+            enabled: false,
+            forward: DoubleTap::new(),
+        }
+    }
+
+    /// Turn walking on its own on or off with the buttons just `pressed`,
+    /// returning whether forward should be held down for the player.
+    pub fn update(&mut self, pressed: u8) -> bool {
+        let double_tap = self.forward.update(pressed & BUTTON_UP != 0);
+
+        if pressed & BUTTON_DOWN != 0 {
+            self.enabled = false;
+        } else if double_tap {
+            self.enabled = !self.enabled;
+        }
+
+        self.enabled
+    }
+}
+
+impl State {
+    /// Stop walking on their own once the player has walked into a wall and
+    /// come to a dead stop.
+    pub fn update_auto_walk(&mut self) {
+        if self.player_velocity == (0.0, 0.0) {
+            self.auto_walk.enabled = false;
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! The automap, showing the walls the player has seen so far.
+
+use crate::{
+    map::{is_diagonal, is_solid, WorldMap},
+    math::floorf,
+    rect, State, DRAW_COLORS,
+};
+
+/// The most pixels wide and tall each map cell is drawn.
+const MAX_CELL_SIZE: i32 = 4;
+
+/// How many pixels the automap can cover along its longer side.
+const MAX_SIZE: i32 = 64;
+
+/// Where the automap's top-left corner goes on screen.
+const ORIGIN: (i32, i32) = (4, 4);
+
+impl State {
+    /// Remember that the player has seen the wall in a cell.
+    pub fn reveal(&mut self, x: i32, y: i32) {
+        if let Some(idx) = self.map.cell_number(x, y) {
+            self.seen[idx / 8] |= 0b1 << (idx % 8);
+        }
+    }
+
+    /// Check if the player has seen the wall in a cell.
+    fn has_seen(&self, x: i32, y: i32) -> bool {
+        match self.map.cell_number(x, y) {
+            Some(idx) => self.seen[idx / 8] & (0b1 << (idx % 8)) != 0,
+            None => false,
+        }
+    }
+
+    /// Draw every wall the player has seen, along with the player, over the
+    /// top-left corner of the screen.
+    pub fn draw_automap(&self) {
+        let (left, top) = ORIGIN;
+        let (width, height) = (self.map.width(), self.map.height());
+
+        // shrink bigger maps down so that they still fit.
+        let cell_size = (MAX_SIZE / width.max(height)).clamp(1, MAX_CELL_SIZE);
+
+        unsafe {
+            *DRAW_COLORS = 0x41;
+            rect(
+                left - 1,
+                top - 1,
+                (width * cell_size + 2) as u32,
+                (height * cell_size + 2) as u32,
+            );
+
+            *DRAW_COLORS = 0x33;
+        }
+        self.draw_walls(ORIGIN, cell_size, true);
+
+        unsafe {
+            *DRAW_COLORS = 0x44;
+            let player_x = floorf(self.player_x * cell_size as f32) as i32;
+            let player_y = floorf(self.player_y * cell_size as f32) as i32;
+            rect(left + player_x - 1, top + player_y - 1, 2, 2);
+        }
+    }
+
+    /// Draw every wall on the map from above in the current colors, each
+    /// cell `cell_size` pixels across, with the map's top-left corner at
+    /// `origin`. With `only_seen`, walls the player hasn't seen are left out.
+    pub fn draw_walls(&self, origin: (i32, i32), cell_size: i32, only_seen: bool) {
+        let (left, top) = origin;
+
+        for y in 0..self.map.height() {
+            for x in 0..self.map.width() {
+                if only_seen && !self.has_seen(x, y) {
+                    continue;
+                }
+
+                // a wall the player has seen might have slid away since.
+                let tile = self.map.tile_at(x, y);
+                let (cell_x, cell_y) = (left + x * cell_size, top + y * cell_size);
+                if self.cell_is_wall(x, y) {
+                    unsafe { rect(cell_x, cell_y, cell_size as u32, cell_size as u32) };
+                } else if is_diagonal(tile) {
+                    // only fill in the half of the cell that's solid.
+                    for v in 0..cell_size {
+                        for u in 0..cell_size {
+                            let center = |t: i32| (t as f32 + 0.5) / cell_size as f32;
+                            if is_solid(tile, center(u), center(v)) {
+                                unsafe { rect(cell_x + u, cell_y + v, 1, 1) };
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! The noises enemies make, so the player can keep track of them even when
+//! they're out of sight.
+//!
+//! Enemies gurgle to themselves every so often, and let out a bark the first
+//! time they spot the player. Each one has to wait a while between sounds,
+//! and only one enemy makes a sound on any update, so a room full of them
+//! doesn't turn into a racket.
+
+use crate::{chase::Ai, sfx, State};
+
+/// How many updates pass between each enemy's gurgles.
+const GURGLE_PERIOD: u32 = 150;
+/// How many updates an enemy stays quiet for after making a sound.
+const SOUND_COOLDOWN: u8 = 90;
+
+impl State {
+    /// Let each enemy make whatever sound it's due, if it's been quiet for
+    /// long enough.
+    pub fn update_enemy_sounds(&mut self) {
+        let mut sound = None;
+
+        for (idx, entity) in self.entities.indexed() {
+            if entity.health.is_none() || entity.sound_cooldown > 0 || sound.is_some() {
+                continue;
+            }
+
+            let spotted = !entity.alerted && !matches!(entity.ai, Ai::Idle);
+
+            // they take turns to gurgle, rather than all at once.
+            let gurgling = (self.stats.frames + idx as u32 * 37) % GURGLE_PERIOD == 0;
+
+            if spotted || gurgling {
+                sound = Some((idx, spotted));
+            }
+        }
+
+        for entity in self.entities.iter_mut() {
+            entity.sound_cooldown = entity.sound_cooldown.saturating_sub(1);
+        }
+
+        let Some((idx, spotted)) = sound else {
+            return;
+        };
+        let Some(entity) = self.entities.get_mut(idx) else {
+            return;
+        };
+        entity.sound_cooldown = SOUND_COOLDOWN;
+        entity.alerted |= spotted;
+
+        let sound = match (spotted, entity.boss) {
+            (true, false) => &sfx::BARK,
+            (true, true) => &sfx::BOSS_BARK,
+            (false, false) => &sfx::GURGLE,
+            (false, true) => &sfx::BOSS_GURGLE,
+        };
+        let (x, y) = (entity.x, entity.y);
+        self.play_sound_at(x, y, sound);
+    }
+}
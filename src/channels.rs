@@ -0,0 +1,68 @@
+//! Keeping track of what's still playing on each of the sound channels.
+//!
+//! Every sound the game makes goes through [`play_with_priority`], which
+//! works out from the tone's envelope how many updates it'll keep its
+//! channel busy for. Sounds that happen all the time and don't matter much,
+//! like footsteps, are skipped rather than cutting off something more
+//! important that's still playing. Sounds that matter more than most, like
+//! dying, can't be cut off by anything less important in turn.
+
+use crate::{sfx::TONE_CHANNEL, tone};
+
+/// How many channels there are to play tones on, each picked by its number
+/// in a tone's flags.
+const CHANNELS: usize = 4;
+
+/// How much a sound matters, which decides what it can cut off.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Background noise, only played on a channel that's free.
+    Ambient,
+    /// Most sounds, which cut off anything that isn't more important.
+    Normal,
+    /// Sounds the player mustn't miss, which only each other can cut off.
+    Important,
+}
+
+/// How many more updates each channel is busy playing something for.
+static mut BUSY_FRAMES: [u16; CHANNELS] = [0; CHANNELS];
+/// How much whatever each channel is busy playing matters.
+static mut PRIORITIES: [Priority; CHANNELS] = [Priority::Ambient; CHANNELS];
+
+/// Play a tone, taking the same arguments as [`tone`] does, and keep track
+/// of how long its channel is busy for. It isn't played if its channel is
+/// busy with something that matters more, or if `priority` is
+/// [`Priority::Ambient`] and the channel is busy at all.
+pub fn play_with_priority(
+    frequency: u32,
+    duration: u32,
+    volume: u32,
+    flags: u32,
+    priority: Priority,
+) {
+    let channel = (flags & TONE_CHANNEL) as usize;
+    unsafe {
+        let busy = BUSY_FRAMES[channel] > 0;
+        if busy && (priority == Priority::Ambient || priority < PRIORITIES[channel]) {
+            return;
+        }
+
+        // the attack, decay, release and sustain are each a byte of the duration.
+        BUSY_FRAMES[channel] = duration.to_le_bytes().iter().map(|&part| part as u16).sum();
+        PRIORITIES[channel] = priority;
+        tone(frequency, duration, volume, flags);
+    }
+}
+
+/// Count down how long each channel is busy for, once every update.
+pub fn update() {
+    unsafe { BUSY_FRAMES = BUSY_FRAMES.map(|frames| frames.saturating_sub(1)) };
+}
+
+/// Cut off whatever is still playing on every channel.
+pub fn silence() {
+    for channel in 0..CHANNELS as u32 {
+        unsafe { tone(0, 0, 0, channel) };
+    }
+    unsafe { BUSY_FRAMES = [0; CHANNELS] };
+}
@@ -0,0 +1,196 @@
+//! Enemies chasing the player down once they've spotted them.
+//!
+//! Each enemy goes through a handful of states: it stands around until it
+//! spots the player, takes a moment to react, then chases them, and swings
+//! at them once it's close enough. Enemies walk around the map with the
+//! same collision rules as the player. They head straight for the player
+//! while they can see them, and for wherever they last saw them otherwise,
+//! giving up once they get there and the player's nowhere to be seen.
+//! Bosses throw shots at the player as they come.
+
+use crate::{
+    audio::walls_between,
+    entities::{BODY_RADIUS, MAX_ENTITIES},
+    map::{Stature, WorldMap},
+    math::{atan2f, distance, floorf},
+    projectiles::Owner,
+    timestep::DT,
+    State,
+};
+
+/// How close the player has to be, with nothing in the way, for an enemy to
+/// spot them in broad daylight. After dark, they also have to be out of the
+/// fog.
+const SPOT_DISTANCE: f32 = 8.0;
+/// How many updates an enemy takes to react after spotting the player.
+const REACTION_FRAMES: u8 = 20;
+/// How fast enemies walk, and how fast bosses do, in map units per second.
+const CHASE_SPEED: f32 = 1.3;
+const BOSS_CHASE_SPEED: f32 = 0.9;
+/// How close an enemy has to get to the player to swing at them.
+const ATTACK_RANGE: f32 = 0.75;
+/// How many updates an enemy winds up for before its swing lands.
+const WIND_UP_FRAMES: u8 = 30;
+/// How much damage a swing does if it lands, and how much a boss's does.
+const ATTACK_DAMAGE: u8 = 10;
+const BOSS_ATTACK_DAMAGE: u8 = 20;
+/// How many updates pass between each shot a boss throws at the player
+/// while chasing them.
+const BOSS_SHOT_INTERVAL: u32 = 90;
+/// How close an enemy has to get to where it last saw the player before it
+/// gives up looking.
+const SEARCH_RADIUS: f32 = 0.1;
+
+/// Enemies are as tall as a wall, so they can't get under or over anything.
+const STATURE: Stature = Stature {
+    feet: 0.0,
+    head: 1.0,
+};
+
+/// What an enemy is up to.
+#[derive(Clone, Copy)]
+pub enum Ai {
+    /// Standing around, waiting to spot the player.
+    Idle,
+    /// Reacting to having just spotted the player, for a few more updates.
+    Alerted { frames: u8 },
+    /// Heading for where the player was last seen.
+    Chase { target: (f32, f32) },
+    /// Winding up a swing at the player, which lands after a few more
+    /// updates.
+    Attack { frames: u8 },
+}
+
+impl State {
+    /// Check if an enemy at (x, y) can see the player.
+    pub fn can_see_player(&self, x: f32, y: f32) -> bool {
+        let player = (self.player_x, self.player_y);
+        let spot_distance = SPOT_DISTANCE.min(self.fog_distance());
+        distance(x - player.0, y - player.1) < spot_distance
+            && walls_between(&self.map, (x, y), player) == 0
+    }
+
+    /// Move every enemy along to whatever it's doing next, chasing and
+    /// swinging at the player.
+    pub fn update_chase(&mut self) {
+        let player = (self.player_x, self.player_y);
+        let mut damage: Option<(u8, (i32, i32))> = None;
+        // with the rhythm mutator on, wound up swings wait for the beat.
+        let off_beat = self.off_beat();
+
+        for idx in 0..MAX_ENTITIES {
+            let Some(&entity) = self.entities.get(idx) else {
+                continue;
+            };
+            if entity.health.is_none() {
+                continue;
+            }
+
+            let sees_player = self.can_see_player(entity.x, entity.y);
+            let away = distance(player.0 - entity.x, player.1 - entity.y);
+            let (mut x, mut y) = (entity.x, entity.y);
+            // enemies turn to face the player while they're reacting to or
+            // swinging at them, and the way they're going while chasing.
+            let mut facing = entity.facing;
+
+            let ai = match entity.ai {
+                Ai::Idle if sees_player => Ai::Alerted {
+                    frames: REACTION_FRAMES,
+                },
+                Ai::Idle => Ai::Idle,
+                Ai::Alerted { frames: 0 } => Ai::Chase { target: player },
+                Ai::Alerted { frames } => {
+                    facing = atan2f(y - player.1, player.0 - x);
+                    Ai::Alerted { frames: frames - 1 }
+                }
+                Ai::Chase { .. } if sees_player && away < ATTACK_RANGE => Ai::Attack {
+                    frames: WIND_UP_FRAMES,
+                },
+                Ai::Chase { target } => {
+                    let target = if sees_player { player } else { target };
+                    let (dx, dy) = (target.0 - x, target.1 - y);
+                    let left = distance(dx, dy);
+                    if !sees_player && left < SEARCH_RADIUS {
+                        Ai::Idle
+                    } else {
+                        let speed = if entity.boss {
+                            BOSS_CHASE_SPEED
+                        } else {
+                            CHASE_SPEED
+                        };
+                        let step = (speed * self.mutators.enemy_speed() * DT).min(left);
+                        let slide = self.map.slide(
+                            (x, y),
+                            (dx / left * step, dy / left * step),
+                            BODY_RADIUS,
+                            STATURE,
+                        );
+                        (x, y) = (slide.x, slide.y);
+                        facing = atan2f(-dy, dx);
+                        Ai::Chase { target }
+                    }
+                }
+                Ai::Attack { frames: 0 } if off_beat => {
+                    facing = atan2f(y - player.1, player.0 - x);
+                    Ai::Attack { frames: 0 }
+                }
+                Ai::Attack { frames: 0 } => {
+                    if sees_player && away < ATTACK_RANGE {
+                        let hit = if entity.boss {
+                            BOSS_ATTACK_DAMAGE
+                        } else {
+                            ATTACK_DAMAGE
+                        };
+                        // when several land at once, the last one gets the blame.
+                        let total = damage.map_or(0, |(total, _)| total);
+                        let cell = (floorf(x) as i32, floorf(y) as i32);
+                        damage = Some((total.saturating_add(hit), cell));
+                    }
+                    Ai::Chase { target: player }
+                }
+                Ai::Attack { frames } => {
+                    facing = atan2f(y - player.1, player.0 - x);
+                    Ai::Attack { frames: frames - 1 }
+                }
+            };
+
+            // bosses throw shots at the player while chasing them down, each
+            // on its own beat so a pair of them don't throw in unison. With
+            // the rhythm mutator on, they throw on the music's beat instead.
+            let beat = (self.stats.frames + idx as u32 * 37) % BOSS_SHOT_INTERVAL;
+            let chasing = matches!(ai, Ai::Chase { .. });
+            if entity.boss && sees_player && chasing && beat == 0 && !self.mutators.rhythm {
+                let angle = atan2f(y - player.1, player.0 - x);
+                self.projectiles.launch(Owner::Enemy, (x, y), angle);
+            }
+
+            if let Some(entity) = self.entities.get_mut(idx) {
+                (entity.x, entity.y, entity.facing, entity.ai) = (x, y, facing, ai);
+            }
+        }
+
+        if let Some((hit, cell)) = damage {
+            self.hurt(hit, cell);
+        }
+    }
+    /// Have every boss chasing the player throw a shot at them, on the first
+    /// beat of every bar with the rhythm mutator on.
+    pub fn throw_boss_shots(&mut self, bar: bool) {
+        if !bar {
+            return;
+        }
+
+        let player = (self.player_x, self.player_y);
+        for idx in 0..MAX_ENTITIES {
+            let Some(&entity) = self.entities.get(idx) else {
+                continue;
+            };
+            let chasing = matches!(entity.ai, Ai::Chase { .. });
+            if entity.boss && chasing && self.can_see_player(entity.x, entity.y) {
+                let angle = atan2f(entity.y - player.1, player.0 - entity.x);
+                self.projectiles
+                    .launch(Owner::Enemy, (entity.x, entity.y), angle);
+            }
+        }
+    }
+}
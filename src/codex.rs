@@ -0,0 +1,214 @@
+//! The codex, describing every kind of enemy and item the player has come
+//! across so far.
+//!
+//! Entries unlock the first time the player gets a good look at one of them
+//! in a level, and are saved to the disk so they stay unlocked between
+//! plays.
+
+use crate::{
+    entities::{Entity, BOSS_HEALTH, SLIME_HEALTH},
+    framebuffer::SCREEN_SIZE,
+    items::{Item, AMMO_BOX, AMMO_BOX_ROUNDS, HEALTH_PACK, HEALTH_PACK_HEALS, TREASURE},
+    keys::{Key, KEY},
+    score::TREASURE_POINTS,
+    sprites::{Sprite, SLIME},
+    text::Line,
+    title, toasts,
+    ui::Ui,
+    Mode, State, BUTTON_LEFT, BUTTON_RIGHT,
+};
+
+/// How close something has to be before the player has seen it well enough
+/// to put it in the codex.
+const DISCOVER_DISTANCE: f32 = 5.0;
+
+const BOOK: Sprite = Sprite {
+    width: 8,
+    height: 8,
+    data: &[
+        0xff, 0xfc, 0xe5, 0x57, 0xe6, 0xa7, 0xe5, 0x57, 0xe6, 0xa7, 0xe5, 0x57, 0xe5, 0x57, 0xff,
+        0xfc,
+    ],
+};
+
+/// One page of the codex.
+struct Entry {
+    name: &'static str,
+    icon: &'static Sprite,
+    description: &'static str,
+    stats: Stats,
+}
+
+/// The line of numbers at the bottom of a page of the codex.
+enum Stats {
+    /// How much damage an enemy can take, before the difficulty changes it.
+    Health(u8),
+    /// What a pickup gives, before the difficulty changes it.
+    Gives(&'static str, u8),
+    /// How many points something is worth.
+    Points(u32),
+    /// Something with no numbers to it.
+    Text(&'static str),
+}
+
+/// Every page of the codex, in the order they're shown.
+const ENTRIES: [Entry; 7] = [
+    Entry {
+        name: "SLIME",
+        icon: &SLIME,
+        description: "Oozes around\nthe halls. Soft,\nbut not harmless.",
+        stats: Stats::Health(SLIME_HEALTH),
+    },
+    Entry {
+        name: "BIG SLIME",
+        icon: &SLIME,
+        description: "A slime that\nate too many\nother slimes.",
+        stats: Stats::Health(BOSS_HEALTH),
+    },
+    Entry {
+        name: "RED KEY",
+        icon: &KEY,
+        description: "Opens every\nred door in\nthe level.",
+        stats: Stats::Text("ONE PER LEVEL"),
+    },
+    Entry {
+        name: "YELLOW KEY",
+        icon: &KEY,
+        description: "Opens every\nyellow door in\nthe level.",
+        stats: Stats::Text("ONE PER LEVEL"),
+    },
+    Entry {
+        name: "HEALTH",
+        icon: &HEALTH_PACK,
+        description: "Patches up\nsome of the\ndamage taken.",
+        stats: Stats::Gives("HEALS ", HEALTH_PACK_HEALS),
+    },
+    Entry {
+        name: "AMMO",
+        icon: &AMMO_BOX,
+        description: "A box of rounds,\nfor when the\ngun runs dry.",
+        stats: Stats::Gives("ROUNDS ", AMMO_BOX_ROUNDS),
+    },
+    Entry {
+        name: "TREASURE",
+        icon: &TREASURE,
+        description: "No use at all,\nbut worth\ngrabbing.",
+        stats: Stats::Points(TREASURE_POINTS),
+    },
+];
+
+/// Which page of the codex describes an entity, if any do.
+fn entry_for(entity: &Entity) -> Option<usize> {
+    match entity.item {
+        Some(Item::Key(Key::Red)) => Some(2),
+        Some(Item::Key(Key::Yellow)) => Some(3),
+        Some(Item::Health(_)) => Some(4),
+        Some(Item::Ammo(_)) => Some(5),
+        Some(Item::Treasure) => Some(6),
+        None if entity.boss => Some(1),
+        None if entity.health.is_some() => Some(0),
+        None => None,
+    }
+}
+
+impl State {
+    /// Unlock the codex entry for everything the player can see up close.
+    pub fn discover_codex_entries(&mut self) {
+        let mut found = self.codex;
+
+        for (_, entity) in self.entities.indexed() {
+            let Some(entry) = entry_for(entity) else {
+                continue;
+            };
+
+            let (depth, lateral) = self.to_camera(entity.x, entity.y);
+            if depth <= 0.0 || depth > DISCOVER_DISTANCE {
+                continue;
+            }
+
+            // it has to be on screen, and not hidden behind a wall.
+            let x = self.screen_x(depth, lateral);
+            if !(0.0..SCREEN_SIZE as f32).contains(&x) {
+                continue;
+            }
+            if self.view[self.ray_at(x as usize)].distance < depth {
+                continue;
+            }
+
+            found |= 1 << entry;
+        }
+
+        if found != self.codex {
+            self.codex = found;
+            self.save_options();
+            toasts::push(&BOOK, "NEW CODEX ENTRY");
+        }
+    }
+
+    /// The stats on a page of the codex, as they are on this difficulty.
+    fn codex_stats(&self, stats: &Stats) -> Line {
+        let mut line = Line::new();
+        match *stats {
+            Stats::Health(health) => {
+                line.push_str("HEALTH ")
+                    .push_number(self.enemy_health(health) as u32);
+            }
+            Stats::Gives(label, amount) => {
+                line.push_str(label)
+                    .push_number(self.pickup_amount(amount) as u32);
+            }
+            Stats::Points(points) => {
+                line.push_number(points).push_str(" POINTS");
+            }
+            Stats::Text(text) => {
+                line.push_str(text);
+            }
+        }
+        line
+    }
+
+    /// Show one page of the codex, flipping between them with left and right
+    /// and going back to the title screen when the player is done.
+    pub fn update_codex(&mut self, pressed: u8) {
+        let Mode::Codex { mut page } = self.mode else {
+            return;
+        };
+
+        if pressed & BUTTON_LEFT != 0 {
+            page = page.checked_sub(1).unwrap_or(ENTRIES.len() - 1);
+        }
+        if pressed & BUTTON_RIGHT != 0 {
+            page = (page + 1) % ENTRIES.len();
+        }
+        self.mode = Mode::Codex { page };
+
+        let mut ui = Ui::new(24, 24, pressed);
+        ui.label("CODEX");
+        ui.hint(
+            Line::new()
+                .push_number(page as u32 + 1)
+                .push_str("/")
+                .push_number(ENTRIES.len() as u32)
+                .as_str(),
+        );
+        ui.space(8);
+
+        let entry = &ENTRIES[page];
+        if self.codex & (1 << page) != 0 {
+            ui.icon_label(entry.icon, entry.name);
+            ui.hint(entry.description);
+            ui.label(self.codex_stats(&entry.stats).as_str());
+        } else {
+            ui.label("???");
+            ui.hint("NOT FOUND YET");
+        }
+
+        let mut ui = Ui::new(24, 124, pressed);
+        let mut focus = 0;
+        if ui.menu(&["BACK"], &mut focus).is_some() {
+            self.mode = Mode::Title;
+            self.menu_focus = title::CODEX_ITEM;
+        }
+        ui.hint("\u{84}\u{85} PAGE  \u{80} PICK");
+    }
+}
@@ -0,0 +1,102 @@
+//! Attacking enemies.
+//!
+//! Shots are hitscan: a single ray is cast straight ahead of the player,
+//! and the nearest enemy in its way before the wall it hits takes the
+//! damage. Shots that don't hit anyone leave a hole in the wall instead.
+
+use crate::{
+    math::fabsf,
+    score::{BOSS_POINTS, KILL_POINTS},
+    sfx,
+    sprites::TEXELS_PER_UNIT,
+    State, NEAR_DISTANCE,
+};
+
+/// How much damage an attack does to an enemy one map unit away. Enemies
+/// further away take less.
+const DAMAGE_AT_ONE_UNIT: f32 = 30.0;
+/// The least and most damage a single attack can do.
+const MIN_DAMAGE: f32 = 5.0;
+const MAX_DAMAGE: f32 = 40.0;
+
+/// Hits doing at least this much damage briefly freeze the action.
+const HEAVY_DAMAGE: u8 = 25;
+
+impl State {
+    /// Fire a shot straight ahead, as long as the player has the ammo for
+    /// it.
+    pub fn fire(&mut self) {
+        if self.ammo == 0 {
+            sfx::play(&sfx::EMPTY_GUN);
+            return;
+        }
+
+        if !self.mutators.infinite_ammo {
+            self.ammo -= 1;
+        }
+        sfx::play(&sfx::SHOT);
+        self.attack();
+    }
+
+    /// Attack straight ahead, hurting the nearest enemy in the way, or
+    /// leaving a hole in the wall if there isn't one.
+    pub fn attack(&mut self) {
+        // nothing behind the wall straight ahead can be hit.
+        let wall = self.cast_ray(self.player_angle);
+        let mut nearest = wall.distance;
+        let mut target = None;
+
+        for (idx, entity) in self.entities.indexed() {
+            if entity.health.is_none() {
+                continue;
+            }
+
+            let (depth, lateral) = self.to_camera(entity.x, entity.y);
+            let radius = entity.graphic.width() as f32 / TEXELS_PER_UNIT / 2.0;
+            if depth > NEAR_DISTANCE && depth < nearest && fabsf(lateral) < radius {
+                nearest = depth;
+                target = Some(idx);
+            }
+        }
+
+        let Some(idx) = target else {
+            // shots fly straight out from the player's eyes.
+            self.decals.spawn(&wall, 1.0 - self.eye_height());
+            return;
+        };
+
+        let damage = (DAMAGE_AT_ONE_UNIT / nearest).clamp(MIN_DAMAGE, MAX_DAMAGE) as u8;
+        self.damage_entity(idx, damage);
+    }
+
+    /// Do `damage` to the entity in slot `idx`, killing it if that's more
+    /// than it has left.
+    pub fn damage_entity(&mut self, idx: usize, damage: u8) {
+        let Some(entity) = self.entities.get_mut(idx) else {
+            return;
+        };
+
+        entity.flash = true;
+        let (x, y) = (entity.x, entity.y);
+        let health = entity.health.unwrap_or(0).saturating_sub(damage);
+        entity.health = Some(health);
+
+        if self.show_damage_numbers {
+            self.damage_numbers.spawn(x, y, damage);
+        }
+
+        if health == 0 && entity.boss {
+            self.time.slow_motion();
+        } else if damage >= HEAVY_DAMAGE {
+            self.time.hit_stop();
+        }
+
+        if health == 0 {
+            let boss = entity.boss;
+            self.entities.remove(idx);
+            self.stats.kills += 1;
+            self.award(if boss { BOSS_POINTS } else { KILL_POINTS });
+        }
+        self.play_sound_at(x, y, &sfx::HIT);
+    }
+}
@@ -0,0 +1,196 @@
+//! Which buttons do what while playing, which the player can change from the
+//! options and are saved to the disk.
+//!
+//! The rest of the game reads the gamepad as if the controls were never
+//! changed, with [`Controls::map`] turning the buttons actually pressed into
+//! the ones they're bound in place of. Menus always use the normal buttons,
+//! so they can't be lost by rebinding.
+//!
+//! Every button already does something on its own while playing, so pausing
+//! is bound to pressing X and Z together to start with. While walking, that
+//! jumps instead.
+
+use crate::{
+    disk, options, text::Line, ui::Ui, Mode, State, BUTTON_1, BUTTON_2, BUTTON_DOWN, BUTTON_LEFT,
+    BUTTON_RIGHT, BUTTON_UP,
+};
+
+/// A byte saved in front of the bindings, so that a disk that's never been
+/// written isn't read as everything being bound to nothing.
+const SAVED: u8 = 0xc7;
+
+/// A bit the gamepad never sets, which pausing is mapped onto in place of a
+/// button of its own.
+pub const BUTTON_PAUSE: u8 = 4;
+
+/// The buttons pressed together to pause, until it's bound to another.
+const PAUSE_CHORD: u8 = BUTTON_1 | BUTTON_2;
+
+/// Everything the buttons can be bound to while playing, named on the
+/// controls screen, along with the buttons each uses normally.
+const ACTIONS: [(&str, u8); 7] = [
+    ("FORWARD", BUTTON_UP),
+    ("BACK", BUTTON_DOWN),
+    ("TURN LEFT", BUTTON_LEFT),
+    ("TURN RIGHT", BUTTON_RIGHT),
+    ("USE", BUTTON_1),
+    ("RUN/AIM/MAP", BUTTON_2),
+    ("PAUSE", PAUSE_CHORD),
+];
+
+/// How many bytes the bindings take up on the disk, with the byte in front.
+pub const SAVED_SIZE: usize = ACTIONS.len() + 1;
+
+/// The menu item for putting every binding back how it was.
+const RESET_ITEM: usize = ACTIONS.len();
+
+/// The character each button is drawn as in the built-in font.
+const fn glyph(button: u8) -> &'static str {
+    match button {
+        BUTTON_1 => "\u{80}",
+        BUTTON_2 => "\u{81}",
+        BUTTON_LEFT => "\u{84}",
+        BUTTON_RIGHT => "\u{85}",
+        BUTTON_UP => "\u{86}",
+        PAUSE_CHORD => "\u{80}\u{81}",
+        _ => "\u{87}",
+    }
+}
+
+/// The button bound to each action, in the order of [`ACTIONS`].
+pub struct Controls {
+    bindings: [u8; ACTIONS.len()],
+    /// The action waiting for a button to be pressed to bind it to, if any.
+    rebinding: Option<usize>,
+}
+
+impl Controls {
+    /// Every action bound to its normal button.
+    pub const fn new() -> Self {
+        let mut bindings = [0; ACTIONS.len()];
+        let mut idx = 0;
+        while idx < ACTIONS.len() {
+            bindings[idx] = ACTIONS[idx].1;
+            idx += 1;
+        }
+
+        Self {
+            bindings,
+            rebinding: None,
+        }
+    }
+
+    /// Turn the buttons held on the gamepad into the normal buttons for the
+    /// actions they're bound to. Pausing comes out as [`BUTTON_PAUSE`],
+    /// rather than the buttons it's normally bound to.
+    pub fn map(&self, buttons: u8) -> u8 {
+        self.bindings
+            .iter()
+            .zip(ACTIONS)
+            .filter(|&(&bound, _)| buttons & bound == bound)
+            .fold(0, |mapped, (_, (_, normal))| match normal {
+                PAUSE_CHORD => mapped | BUTTON_PAUSE,
+                normal => mapped | normal,
+            })
+    }
+
+    /// Bind an action to a button, swapping bindings with whichever action it
+    /// was bound to so that every action still has a button.
+    fn bind(&mut self, action: usize, button: u8) {
+        if let Some(other) = self.bindings.iter().position(|&bound| bound == button) {
+            self.bindings[other] = self.bindings[action];
+        }
+        self.bindings[action] = button;
+    }
+
+    /// Read the bindings saved on the disk, if there are any.
+    pub fn load(&mut self) {
+        let mut saved = [0; SAVED_SIZE];
+        disk::load(disk::CONTROLS, &mut saved);
+
+        let (marker, bindings) = saved.split_at(1);
+        // only bindings using every button once can be told apart.
+        let valid = ACTIONS
+            .iter()
+            .all(|&(_, button)| bindings.iter().filter(|&&bound| bound == button).count() == 1);
+        if marker[0] == SAVED && valid {
+            self.bindings.copy_from_slice(bindings);
+        }
+    }
+
+    /// Write the bindings to the disk.
+    fn save(&self) {
+        let mut saved = [SAVED; SAVED_SIZE];
+        saved[1..].copy_from_slice(&self.bindings);
+        disk::save(disk::CONTROLS, &saved);
+    }
+}
+
+impl State {
+    /// Show the menu for rebinding the controls, going back to the options
+    /// when the player is done.
+    pub fn update_controls(&mut self, pressed: u8) {
+        let mut ui = Ui::new(24, 8, pressed);
+        ui.label("CONTROLS");
+        ui.space(8);
+
+        // the next button pressed is bound to the action being changed.
+        if let Some(action) = self.controls.rebinding {
+            ui.label(ACTIONS[action].0);
+            ui.space(8);
+            ui.hint("PRESS A BUTTON");
+
+            if pressed != 0 {
+                // only one button is bound, if several are pressed at once.
+                let button = pressed & pressed.wrapping_neg();
+                self.controls.bind(action, button);
+                self.controls.save();
+                self.controls.rebinding = None;
+            }
+            return;
+        }
+
+        let mut lines = [
+            Line::new(),
+            Line::new(),
+            Line::new(),
+            Line::new(),
+            Line::new(),
+            Line::new(),
+            Line::new(),
+        ];
+        for (line, ((name, _), &bound)) in lines
+            .iter_mut()
+            .zip(ACTIONS.iter().zip(&self.controls.bindings))
+        {
+            line.push_str(name).push_str(" ").push_str(glyph(bound));
+        }
+
+        let items = [
+            lines[0].as_str(),
+            lines[1].as_str(),
+            lines[2].as_str(),
+            lines[3].as_str(),
+            lines[4].as_str(),
+            lines[5].as_str(),
+            lines[6].as_str(),
+            "RESET",
+            "BACK",
+        ];
+        match ui.menu(&items, &mut self.menu_focus) {
+            Some(RESET_ITEM) => {
+                self.controls = Controls::new();
+                self.controls.save();
+            }
+            Some(action) if action < ACTIONS.len() => self.controls.rebinding = Some(action),
+            Some(_) => {
+                self.mode = Mode::Options;
+                self.menu_focus = options::CONTROLS_ITEM;
+            }
+            None => {}
+        }
+
+        ui.space(8);
+        ui.hint("\u{86}\u{87} CHOOSE  \u{80} PICK");
+    }
+}
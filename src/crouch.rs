@@ -0,0 +1,86 @@
+//! Crouching, by double-tapping back, which lowers the player's view and
+//! lets them crawl under low walls.
+//!
+//! Crouching down and standing back up take a moment, with the view sliding
+//! between the two heights, and the player can't stand up while there's a
+//! low wall over their head.
+
+use crate::{
+    double_tap::DoubleTap,
+    entities::BODY_RADIUS,
+    map::{WorldMap, LOW_WALL},
+    math::floorf,
+    timestep::DT,
+    State,
+};
+
+/// How far off the floor the camera is while crouching, as a fraction of a
+/// wall's height. It has to be under the bottom of a low wall.
+pub const CROUCH_EYE_HEIGHT: f32 = 0.25;
+
+/// How far up from the floor the top of the player's head is while
+/// standing, and while crouching, as a fraction of a wall's height.
+/// Crouching has to bring it under the bottom of a low wall.
+pub const STANDING_HEAD: f32 = 0.6;
+pub const CROUCHING_HEAD: f32 = 0.35;
+
+/// How much slower than walking the player moves while crouching.
+pub const CROUCH_SPEED: f32 = 0.5;
+
+/// How many seconds it takes to crouch all the way down, or stand back up.
+const CROUCH_TIME: f32 = 0.15;
+
+/// Whether the player is crouching, and how far down they've got.
+pub struct Crouch {
+    pub down: bool,
+    /// How far between standing and crouching the player is, from 0.0 to 1.0.
+    progress: f32,
+    /// The taps on back that crouch down and stand back up.
+    pub taps: DoubleTap,
+}
+
+impl Crouch {
+    pub const fn new() -> Self {
+        Self {
+            down: false,
+            progress: 0.0,
+            taps: DoubleTap::new(),
+        }
+    }
+
+    /// How far between standing and crouching the player is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// Move the player's view a step towards where it's headed.
+    pub fn update(&mut self) {
+        let step = DT / CROUCH_TIME;
+        self.progress = if self.down {
+            (self.progress + step).min(1.0)
+        } else {
+            (self.progress - step).max(0.0)
+        };
+    }
+}
+
+impl State {
+    /// Crouch down, or stand back up if there's room to.
+    pub fn toggle_crouch(&mut self) {
+        if self.crouch.down && self.under_low_wall() {
+            return;
+        }
+        self.crouch.down = !self.crouch.down;
+    }
+
+    /// Check if any part of the player's body is under a low wall.
+    fn under_low_wall(&self) -> bool {
+        [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)]
+            .iter()
+            .any(|&(dir_x, dir_y)| {
+                let x = floorf(self.player_x + dir_x * BODY_RADIUS) as i32;
+                let y = floorf(self.player_y + dir_y * BODY_RADIUS) as i32;
+                self.map.tile_at(x, y) == LOW_WALL
+            })
+    }
+}
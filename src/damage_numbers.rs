@@ -0,0 +1,116 @@
+//! Numbers that float up from enemies when they're hurt.
+
+use crate::{
+    framebuffer::SCREEN_SIZE,
+    render::HORIZON,
+    text::{text, Line},
+    State, DRAW_COLORS, NEAR_DISTANCE,
+};
+
+/// The most damage numbers that can be on screen at once.
+const CAPACITY: usize = 8;
+
+/// How many updates a damage number floats for.
+const LIFETIME: u8 = 30;
+
+/// How high off the floor a damage number starts, and how far it rises, in
+/// map units.
+const START_HEIGHT: f32 = 1.0;
+const RISE: f32 = 0.5;
+
+/// How much damage was done, and where.
+#[derive(Clone, Copy)]
+struct DamageNumber {
+    x: f32,
+    y: f32,
+    amount: u8,
+    /// How many updates the number has been floating for.
+    frames: u8,
+}
+
+pub struct DamageNumbers {
+    numbers: [Option<DamageNumber>; CAPACITY],
+}
+
+impl DamageNumbers {
+    pub const fn new() -> Self {
+        Self {
+            numbers: [None; CAPACITY],
+        }
+    }
+
+    /// Show `amount` floating up from the point (x, y) on the map, replacing
+    /// the oldest number if there are too many.
+    pub fn spawn(&mut self, x: f32, y: f32, amount: u8) {
+        let age = |slot: &Option<DamageNumber>| slot.map_or(u8::MAX, |number| number.frames);
+        if let Some(slot) = self.numbers.iter_mut().max_by_key(|slot| age(slot)) {
+            *slot = Some(DamageNumber {
+                x,
+                y,
+                amount,
+                frames: 0,
+            });
+        }
+    }
+
+    /// How many numbers are floating, and how many there's room for.
+    pub fn usage(&self) -> (usize, usize) {
+        (self.numbers.iter().flatten().count(), CAPACITY)
+    }
+
+    /// Float every number along, dropping those that have run their course.
+    pub fn update(&mut self) {
+        for slot in &mut self.numbers {
+            if let Some(number) = slot {
+                number.frames += 1;
+                if number.frames == LIFETIME {
+                    *slot = None;
+                }
+            }
+        }
+    }
+}
+
+impl State {
+    /// Draw every damage number in front of the walls, fading as they rise.
+    pub fn draw_damage_numbers(&self) {
+        let eye_height = self.eye_height();
+        let fog_distance = self.fog_distance();
+
+        for number in self.damage_numbers.numbers.iter().flatten() {
+            let (depth, lateral) = self.to_camera(number.x, number.y);
+            if !(NEAR_DISTANCE..=fog_distance).contains(&depth) {
+                continue;
+            }
+
+            let x = self.screen_x(depth, lateral) as i32;
+            if !(0..SCREEN_SIZE as i32).contains(&x) {
+                continue;
+            }
+
+            // hide numbers behind walls, like any other sprite.
+            if depth > self.view[self.ray_at(x as usize)].distance {
+                continue;
+            }
+
+            let t = number.frames as f32 / LIFETIME as f32;
+            let height = START_HEIGHT + RISE * t;
+            let scale = self.wall_height() / depth;
+            let y = HORIZON + ((eye_height - height) * scale) as i32;
+
+            let mut line = Line::new();
+            line.push_number(number.amount as u32);
+            let label = line.as_str();
+
+            unsafe {
+                // fade from the darkest color to the lightest.
+                *DRAW_COLORS = match number.frames * 3 / LIFETIME {
+                    0 => 0x4,
+                    1 => 0x3,
+                    _ => 0x2,
+                };
+            }
+            text(label, x - label.len() as i32 * 4, y - 4);
+        }
+    }
+}
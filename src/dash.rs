@@ -0,0 +1,105 @@
+//! Dashing, by double-tapping Z while walking, which throws the player
+//! several cells forward or back in an instant.
+//!
+//! Pressing Z once while walking just starts a sprint, so a dash never
+//! catches the player out at the start of one. A dash takes over the
+//! player's walking for a few updates, and leaves them with all of its speed
+//! when it's done, so they slide to a stop or carry on walking from it like
+//! they would from a sprint. Holding Z on after the second tap sprints as
+//! usual. It takes a while to get another dash
+//! ready, which a meter at the bottom of the screen counts down.
+
+use crate::{
+    double_tap::DoubleTap,
+    framebuffer::SCREEN_SIZE,
+    math::{cosf, sinf},
+    rect, sfx, State, DRAW_COLORS,
+};
+
+/// How fast the player goes while they're dashing, in map units per second.
+const DASH_SPEED: f32 = 10.8;
+/// How many updates a dash lasts for, which along with its speed is how
+/// far it throws the player.
+const DASH_FRAMES: u8 = 15;
+/// How many updates it takes to get another dash ready, counting from the
+/// start of the last one.
+const COOLDOWN_FRAMES: u16 = 150;
+
+/// The size of the cooldown meter, and where it goes along the bottom of
+/// the screen, just under the stamina meter.
+const METER_WIDTH: u32 = 42;
+const METER_HEIGHT: u32 = 3;
+const METER_Y: i32 = 155;
+
+/// Whether the player is dashing, and how long until they can dash again.
+pub struct Dash {
+    /// Which way the player is going and how fast, for as long as the dash
+    /// lasts.
+    velocity: (f32, f32),
+    /// How many more updates the dash lasts for, or 0 if they're not dashing.
+    frames_left: u8,
+    /// How many more updates it is until the player can dash again.
+    cooldown: u16,
+    /// The taps on Z that set off a dash.
+    pub taps: DoubleTap,
+}
+
+impl Dash {
+    pub const fn new() -> Self {
+        Self {
+            velocity: (0.0, 0.0),
+            frames_left: 0,
+            cooldown: 0,
+            taps: DoubleTap::new(),
+        }
+    }
+
+    /// Count down the dash and its cooldown, returning how fast the player
+    /// should be going if they're still dashing.
+    pub fn update(&mut self) -> Option<(f32, f32)> {
+        self.cooldown = self.cooldown.saturating_sub(1);
+        if self.frames_left == 0 {
+            return None;
+        }
+
+        self.frames_left -= 1;
+        Some(self.velocity)
+    }
+}
+
+impl State {
+    /// Dash forward, or back if `backwards` is set, as long as the player has
+    /// a dash ready and isn't crouching.
+    pub fn dash(&mut self, backwards: bool) {
+        if self.dash.cooldown > 0 || self.crouch.down {
+            return;
+        }
+
+        let speed = if backwards { -DASH_SPEED } else { DASH_SPEED };
+        let angle = self.player_angle;
+        self.dash.velocity = (cosf(angle) * speed, -sinf(angle) * speed);
+        self.dash.frames_left = DASH_FRAMES;
+        self.dash.cooldown = COOLDOWN_FRAMES;
+        sfx::play(&sfx::DASH);
+    }
+
+    /// Draw how long it is until the player can dash again along the bottom
+    /// of the screen, unless they already can.
+    pub fn draw_dash_cooldown(&self) {
+        let cooldown = self.dash.cooldown;
+        if cooldown == 0 {
+            return;
+        }
+
+        let left = (SCREEN_SIZE as u32 - METER_WIDTH) as i32 / 2;
+        let ready = COOLDOWN_FRAMES - cooldown;
+        let filled = (METER_WIDTH - 2) * ready as u32 / COOLDOWN_FRAMES as u32;
+
+        unsafe {
+            *DRAW_COLORS = 0x41;
+            rect(left, METER_Y, METER_WIDTH, METER_HEIGHT);
+            *DRAW_COLORS = 0x33;
+            rect(left + 1, METER_Y + 1, filled, METER_HEIGHT - 2);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Levels where the light slowly fades from day to night, and back again.
+
+use core::f32::consts::TAU;
+
+use crate::{
+    math::{cosf, lerp},
+    State, FOG_DISTANCE,
+};
+
+/// How far away walls are lost in the fog in the dead of night, in map units.
+const NIGHT_FOG_DISTANCE: f32 = 5.0;
+
+/// How a level's light changes as it goes on.
+pub struct DayNight {
+    /// The colors the level is drawn in at night, from lightest to darkest.
+    pub palette: [u32; 4],
+    /// How many updates it takes to go from day to night and back again.
+    pub period: u32,
+    /// How many updates into the cycle the level starts, so that it can
+    /// start at dusk or in the middle of the night.
+    pub start: u32,
+}
+
+impl State {
+    /// How dark it's got, from 0.0 (broad daylight) to 1.0 (the dead of night).
+    pub fn darkness(&self) -> f32 {
+        let Some(day_night) = &self.level().day_night else {
+            return 0.0;
+        };
+
+        let frame = self.stats.frames.wrapping_add(day_night.start);
+        let phase = (frame % day_night.period) as f32 / day_night.period as f32;
+        (1.0 - cosf(phase * TAU)) / 2.0
+    }
+
+    /// How far away, in map units, walls and everything else are lost in the
+    /// fog, which closes in as it gets dark.
+    pub fn fog_distance(&self) -> f32 {
+        lerp(FOG_DISTANCE, NIGHT_FOG_DISTANCE, self.darkness())
+    }
+}
@@ -0,0 +1,89 @@
+//! Dying, and coming back afterwards.
+
+use crate::{
+    auto_walk::AutoWalk,
+    crouch::{Crouch, CROUCH_EYE_HEIGHT},
+    dash::Dash,
+    jump::Jump,
+    math::{ease_in_out, floorf, lerp},
+    sprint::Stamina,
+    Mode, State,
+};
+
+/// How many updates the camera takes to sink to the floor.
+const DYING_FRAMES: u16 = 90;
+
+/// How far off the floor the camera is while alive, as a fraction of a wall's height.
+const EYE_HEIGHT: f32 = 0.5;
+/// How far off the floor the camera ends up after dying.
+const DEAD_EYE_HEIGHT: f32 = 0.1;
+
+impl State {
+    /// Check if the player is standing somewhere that kills them.
+    pub fn on_hazard(&self) -> bool {
+        self.level()
+            .map
+            .hazards
+            .contains(&(floorf(self.player_x) as i32, floorf(self.player_y) as i32))
+    }
+
+    /// Sink the camera towards the floor, then fade back to the start of the
+    /// level if there's a time attack run going, or to the game over screen
+    /// otherwise.
+    pub fn update_dying(&mut self) {
+        if let Mode::Dying { frames } = &mut self.mode {
+            if *frames < DYING_FRAMES {
+                *frames += 1;
+            } else if self.out_of_continues() {
+                self.end_time_attack();
+            } else if self.time_attack.is_some() {
+                self.transition_to(Mode::Playing);
+            } else {
+                self.transition_to(Mode::GameOver);
+            }
+        }
+    }
+
+    /// Bring the player back to life, in the middle of a time attack run.
+    pub fn respawn(&mut self) {
+        // there aren't any checkpoints, so it's back to the start of the
+        // level, though everything the player did there stays done.
+        (self.player_x, self.player_y, self.player_angle) = self.level().map.spawn;
+        self.player_velocity = (0.0, 0.0);
+        self.player_turn_velocity = 0.0;
+        self.stamina = Stamina::new();
+        self.crouch = Crouch::new();
+        self.jump = Jump::new();
+        self.dash = Dash::new();
+        self.auto_walk = AutoWalk::new();
+        self.projectiles.clear();
+        self.charge = 0;
+        self.map.stream_around(self.player_x, self.player_y);
+        self.heal_fully();
+        self.use_continue();
+        self.replay.clear();
+    }
+
+    /// How far through dying the player is, from 0.0 (alive) to 1.0 (dead).
+    fn death_progress(&self) -> f32 {
+        match self.mode {
+            Mode::Dying { frames } => ease_in_out(frames as f32 / DYING_FRAMES as f32),
+            _ => 0.0,
+        }
+    }
+
+    /// How far off the floor the camera is, as a fraction of a wall's height.
+    pub fn eye_height(&self) -> f32 {
+        let alive = lerp(
+            EYE_HEIGHT,
+            CROUCH_EYE_HEIGHT,
+            ease_in_out(self.crouch.progress()),
+        ) + self.jump.height;
+        lerp(alive, DEAD_EYE_HEIGHT, self.death_progress())
+    }
+
+    /// How much the screen is washed in red, from 0.0 to 1.0.
+    pub fn redness(&self) -> f32 {
+        self.death_progress().max(self.hurt_redness())
+    }
+}
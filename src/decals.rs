@@ -0,0 +1,92 @@
+//! Bullet holes left in the walls by shots that miss.
+
+use crate::{RayHit, Side};
+
+/// The most bullet holes there can be at once. Once they run out, new ones
+/// replace the oldest.
+const CAPACITY: usize = 16;
+
+/// How many texels across and down a wall's pattern is, which bullet holes
+/// are placed on.
+const WALL_TEXELS: f32 = 16.0;
+
+/// The color a bullet hole is drawn in.
+const HOLE: u8 = 3;
+
+/// A hole in the face of a wall.
+#[derive(Clone, Copy)]
+struct Decal {
+    /// The kind of wall that was hit, so the hole goes away if the wall
+    /// opens or slides off somewhere else.
+    tile_id: u8,
+    /// The map cell containing the wall, and which face of it was hit.
+    cell_x: i32,
+    cell_y: i32,
+    side: Side,
+    /// Which texel of the wall's face the hole is in, across and down.
+    u: i32,
+    v: i32,
+}
+
+pub struct Decals {
+    decals: [Option<Decal>; CAPACITY],
+    /// The slot the next hole goes in.
+    next: usize,
+}
+
+impl Decals {
+    pub const fn new() -> Self {
+        Self {
+            decals: [None; CAPACITY],
+            next: 0,
+        }
+    }
+
+    /// Leave a hole where a ray hit a wall, `height` of the way down it.
+    pub fn spawn(&mut self, hit: &RayHit, height: f32) {
+        self.decals[self.next] = Some(Decal {
+            tile_id: hit.tile_id,
+            cell_x: hit.cell_x,
+            cell_y: hit.cell_y,
+            side: hit.side,
+            u: (hit.tex_u * WALL_TEXELS) as i32,
+            v: (height.clamp(0.0, 1.0) * (WALL_TEXELS - 1.0)) as i32,
+        });
+        self.next = (self.next + 1) % CAPACITY;
+    }
+
+    /// Patch up every hole.
+    pub fn clear(&mut self) {
+        self.decals = [None; CAPACITY];
+    }
+
+    /// How many holes there are, and how many there's room for.
+    pub fn usage(&self) -> (usize, usize) {
+        (self.decals.iter().flatten().count(), CAPACITY)
+    }
+
+    /// Draw any holes in the wall a ray hit over its screen column, with the
+    /// top of the wall at screen row `top`.
+    pub fn draw_column(&self, column: &mut [u8], hit: &RayHit, top: i32) {
+        if hit.height <= 0 {
+            return;
+        }
+
+        let u = (hit.tex_u * WALL_TEXELS) as i32;
+        let holes = self.decals.iter().flatten().filter(|decal| {
+            decal.u == u
+                && (decal.tile_id, decal.cell_x, decal.cell_y)
+                    == (hit.tile_id, hit.cell_x, hit.cell_y)
+                && decal.side == hit.side
+        });
+
+        for decal in holes {
+            let texel = hit.height as f32 / WALL_TEXELS;
+            let start = (top as f32 + decal.v as f32 * texel) as i32;
+            // holes stay at least a pixel tall, however far away they are.
+            let end = (top as f32 + (decal.v + 1) as f32 * texel).max(start as f32 + 1.0) as i32;
+            let rows = start.clamp(0, column.len() as i32)..end.clamp(0, column.len() as i32);
+            column[rows.start as usize..rows.end as usize].fill(HOLE);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! How hard the game is, picked from the title screen and kept with the
+//! run when it's saved.
+//!
+//! Everything a difficulty changes is in its row of [`TUNING`], rather than
+//! spread around the game, and is applied to each level's entities as the
+//! level is loaded, and to the player as they're hurt.
+
+use crate::{items::Item, State};
+
+/// How hard the game is.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+/// How a difficulty changes the game. Amounts are percentages of what they
+/// would be on [`Difficulty::Normal`].
+struct Tuning {
+    /// How much damage enemies can take.
+    enemy_health: u32,
+    /// How much damage enemies do to the player.
+    enemy_damage: u32,
+    /// How many of every four enemies are left in each level. Bosses are
+    /// always left in.
+    enemies_kept: usize,
+    /// How much health and ammo each pickup gives.
+    pickups: u32,
+}
+
+/// How each difficulty changes the game, in the order of [`Difficulty`].
+const TUNING: [Tuning; 3] = [
+    Tuning {
+        enemy_health: 75,
+        enemy_damage: 50,
+        enemies_kept: 3,
+        pickups: 150,
+    },
+    Tuning {
+        enemy_health: 100,
+        enemy_damage: 100,
+        enemies_kept: 4,
+        pickups: 100,
+    },
+    Tuning {
+        enemy_health: 150,
+        enemy_damage: 150,
+        enemies_kept: 4,
+        pickups: 50,
+    },
+];
+
+/// Scale `amount` by `percent`, keeping it between 1 and as much as fits.
+fn scale(amount: u8, percent: u32) -> u8 {
+    (amount as u32 * percent / 100).clamp(1, u8::MAX as u32) as u8
+}
+
+impl Difficulty {
+    /// The difficulty after this one, going back around to the first after
+    /// the last.
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Easy => Self::Normal,
+            Self::Normal => Self::Hard,
+            Self::Hard => Self::Easy,
+        }
+    }
+
+    /// The title screen's menu item for picking the difficulty.
+    pub const fn menu_item(self) -> &'static str {
+        match self {
+            Self::Easy => "SKILL: EASY",
+            Self::Normal => "SKILL: NORMAL",
+            Self::Hard => "SKILL: HARD",
+        }
+    }
+
+    /// The difficulty saved as `byte`, if it's one.
+    pub const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Easy),
+            1 => Some(Self::Normal),
+            2 => Some(Self::Hard),
+            _ => None,
+        }
+    }
+
+    /// How the difficulty changes the game.
+    const fn tuning(self) -> &'static Tuning {
+        &TUNING[self as usize]
+    }
+}
+
+impl State {
+    /// Change the level's entities to suit the difficulty, thinning out the
+    /// enemies and changing how tough they are and how much pickups give.
+    pub fn apply_difficulty(&mut self) {
+        let tuning = self.difficulty.tuning();
+
+        let mut enemies = 0;
+        for idx in 0..self.entities.usage().1 {
+            let Some(entity) = self.entities.get_mut(idx) else {
+                continue;
+            };
+
+            if let Some(health) = &mut entity.health {
+                *health = scale(*health, tuning.enemy_health);
+
+                let kept = enemies % 4 < tuning.enemies_kept;
+                enemies += 1;
+                if !kept && !entity.boss {
+                    self.entities.remove(idx);
+                }
+            } else if let Some(Item::Health(amount) | Item::Ammo(amount)) = &mut entity.item {
+                *amount = scale(*amount, tuning.pickups);
+            }
+        }
+    }
+
+    /// How much damage an enemy that would normally take `health` can take
+    /// on this difficulty.
+    pub fn enemy_health(&self, health: u8) -> u8 {
+        scale(health, self.difficulty.tuning().enemy_health)
+    }
+
+    /// How much a pickup that would normally give `amount` gives on this
+    /// difficulty.
+    pub fn pickup_amount(&self, amount: u8) -> u8 {
+        scale(amount, self.difficulty.tuning().pickups)
+    }
+
+    /// How much damage an enemy that would normally do `damage` does on
+    /// this difficulty.
+    pub fn enemy_damage(&self, damage: u8) -> u8 {
+        scale(damage, self.difficulty.tuning().enemy_damage)
+    }
+}
@@ -0,0 +1,56 @@
+//! The cartridge's save disk, which keeps a few bytes around between plays.
+//!
+//! WASM-4 only reads and writes the disk all at once from the start, so each
+//! thing saved has its own range of bytes, and saving one reads the rest
+//! back first to write them out again unchanged.
+
+use crate::{controls, save, splits};
+
+extern "C" {
+    fn diskr(dest: *mut u8, size: u32) -> u32;
+    fn diskw(src: *const u8, size: u32) -> u32;
+}
+
+/// How many bytes of the disk are used.
+const DISK_SIZE: usize = 64;
+
+/// Where the player's control bindings are saved.
+pub const CONTROLS: usize = 0;
+/// Where the best time on each level is saved.
+pub const SPLITS: usize = 16;
+/// Where the options and the player's progress are saved.
+pub const SAVE: usize = 48;
+
+// everything saved has to fit in its own range, without running into the
+// next one or off the end of the disk.
+const _: () = assert!(CONTROLS + controls::SAVED_SIZE <= SPLITS);
+const _: () = assert!(SPLITS + splits::SAVED_SIZE <= SAVE);
+const _: () = assert!(SAVE + save::SAVED_SIZE <= DISK_SIZE);
+
+/// Read the whole of the used part of the disk. Anything never written is
+/// left as zeroes.
+fn read_all() -> [u8; DISK_SIZE] {
+    let mut disk = [0; DISK_SIZE];
+    unsafe { diskr(disk.as_mut_ptr(), DISK_SIZE as u32) };
+    disk
+}
+
+/// Fill `bytes` from the disk, starting `offset` bytes in.
+pub fn load(offset: usize, bytes: &mut [u8]) {
+    bytes.copy_from_slice(&read_all()[offset..offset + bytes.len()]);
+}
+
+/// Write `bytes` to the disk, starting `offset` bytes in, keeping everything
+/// else on it as it was.
+///
+/// Kiosk builds never write to the disk, so one passer-by's records,
+/// bindings and progress aren't left behind for the next.
+pub fn save(offset: usize, bytes: &[u8]) {
+    if cfg!(feature = "kiosk") {
+        return;
+    }
+
+    let mut disk = read_all();
+    disk[offset..offset + bytes.len()].copy_from_slice(bytes);
+    unsafe { diskw(disk.as_ptr(), DISK_SIZE as u32) };
+}
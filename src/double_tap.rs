@@ -0,0 +1,30 @@
+//! Telling a double-tap on a button apart from two separate presses.
+
+/// How many updates apart two taps on a button can be and still count as a
+/// double-tap.
+const DOUBLE_TAP_FRAMES: u8 = 15;
+
+/// How long it's been since a button was last tapped.
+pub struct DoubleTap {
+    since_tap: u8,
+}
+
+impl DoubleTap {
+    pub const fn new() -> Self {
+        Self { since_tap: u8::MAX }
+    }
+
+    /// Keep track of taps on the button, given whether it was just
+    /// `pressed`, returning whether this one makes a double-tap.
+    pub fn update(&mut self, pressed: bool) -> bool {
+        self.since_tap = self.since_tap.saturating_add(1);
+        if !pressed {
+            return false;
+        }
+
+        let double = self.since_tap <= DOUBLE_TAP_FRAMES;
+        // a third tap starts counting towards a new double-tap.
+        self.since_tap = if double { u8::MAX } else { 0 };
+        double
+    }
+}
@@ -0,0 +1,208 @@
+//! Things in the world that aren't walls.
+
+use crate::{
+    chase::Ai,
+    items::Item,
+    keys::Key,
+    sprites::{Graphic, Tint},
+};
+
+/// How close the center of anything moving around the map, the player
+/// included, can get to a wall. Keeping the camera back from walls stops it
+/// seeing through corners, or getting close enough to pick out the seams.
+pub const BODY_RADIUS: f32 = 0.25;
+
+/// How much damage each kind of enemy can take on
+/// [`Difficulty::Normal`](crate::difficulty::Difficulty::Normal).
+pub const SLIME_HEALTH: u8 = 50;
+pub const BOSS_HEALTH: u8 = 150;
+
+/// Something placed in the world, drawn as a billboard.
+#[derive(Clone, Copy)]
+pub struct Entity {
+    pub x: f32,
+    pub y: f32,
+    pub graphic: Graphic,
+    /// Which way the entity faces, in radians anticlockwise from east, which
+    /// picks which side of a directional graphic the player sees.
+    pub facing: f32,
+    pub tint: Tint,
+    /// Whether the entity was hit this frame, and should flash.
+    pub flash: bool,
+    /// How much more damage the entity can take, if it can be hurt at all.
+    pub health: Option<u8>,
+    /// Whether killing the entity is a big enough deal to slow down time.
+    pub boss: bool,
+    /// What the player gets for picking the entity up, if it's an item.
+    pub item: Option<Item>,
+    /// What the entity is up to, if it's an enemy.
+    pub ai: Ai,
+    /// Whether the entity has let out its bark at spotting the player yet.
+    pub alerted: bool,
+    /// How many more updates until the entity can make another sound.
+    pub sound_cooldown: u8,
+}
+
+impl Entity {
+    /// Create an entity drawn in its graphic's own colors.
+    pub const fn new(x: f32, y: f32, graphic: Graphic) -> Self {
+        Self {
+            x,
+            y,
+            graphic,
+            facing: 0.0,
+            tint: Tint::Normal,
+            flash: false,
+            health: None,
+            boss: false,
+            item: None,
+            ai: Ai::Idle,
+            alerted: false,
+            sound_cooldown: 0,
+        }
+    }
+
+    /// Create an entity that can be hurt, and is killed once it has taken
+    /// `health` damage.
+    pub const fn enemy(x: f32, y: f32, graphic: Graphic, health: u8) -> Self {
+        Self {
+            health: Some(health),
+            ..Self::new(x, y, graphic)
+        }
+    }
+
+    /// Create a tougher enemy, picked out in different colors from the rest.
+    pub const fn boss(x: f32, y: f32, graphic: Graphic, health: u8) -> Self {
+        Self {
+            tint: Tint::Team(1),
+            boss: true,
+            ..Self::enemy(x, y, graphic, health)
+        }
+    }
+
+    /// Create an item for the player to pick up.
+    pub const fn item(x: f32, y: f32, item: Item) -> Self {
+        Self {
+            tint: item.tint(),
+            item: Some(item),
+            ..Self::new(x, y, Graphic::Sprite(item.sprite()))
+        }
+    }
+
+    /// Turn the entity to face `angle` radians anticlockwise from east.
+    pub const fn facing(self, angle: f32) -> Self {
+        Self {
+            facing: angle,
+            ..self
+        }
+    }
+
+    /// Check if the entity is something small lying around for the player
+    /// to pick up.
+    pub const fn is_pickup(&self) -> bool {
+        self.item.is_some()
+    }
+
+    /// The key the player gets for picking the entity up, if it's a key.
+    pub const fn key(&self) -> Option<Key> {
+        match self.item {
+            Some(Item::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// The tint to draw the entity with this frame.
+    pub fn current_tint(&self) -> Tint {
+        if self.flash {
+            Tint::Flash
+        } else {
+            self.tint
+        }
+    }
+}
+
+/// The most entities that can exist at once.
+pub const MAX_ENTITIES: usize = 32;
+
+/// A fixed-capacity pool of entities.
+pub struct Entities {
+    slots: [Option<Entity>; MAX_ENTITIES],
+}
+
+impl Entities {
+    /// Create a pool holding the given entities.
+    pub const fn new(initial: &[Entity]) -> Self {
+        let mut slots = [None; MAX_ENTITIES];
+
+        let mut idx = 0;
+        while idx < initial.len() {
+            slots[idx] = Some(initial[idx]);
+            idx += 1;
+        }
+
+        Self { slots }
+    }
+
+    /// Empty the pool and fill it with the given entities, in place rather
+    /// than building a whole new pool on the stack.
+    pub fn refill(&mut self, initial: &[Entity]) {
+        self.slots = [None; MAX_ENTITIES];
+        for (slot, &entity) in self.slots.iter_mut().zip(initial) {
+            *slot = Some(entity);
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Entity> {
+        self.slots.iter_mut().flatten()
+    }
+
+    /// How many slots are taken, and how many there are.
+    pub fn usage(&self) -> (usize, usize) {
+        (self.slots.iter().flatten().count(), MAX_ENTITIES)
+    }
+
+    /// Iterate over every entity along with the slot it's in.
+    pub fn indexed(&self) -> impl Iterator<Item = (usize, &Entity)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, slot)| Some((idx, slot.as_ref()?)))
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&Entity> {
+        self.slots.get(idx)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, idx: usize) -> Option<&mut Entity> {
+        self.slots.get_mut(idx)?.as_mut()
+    }
+
+    /// Put an entity in the first free slot, returning which one it went in,
+    /// or `None` if the pool is full.
+    pub fn insert(&mut self, entity: Entity) -> Option<usize> {
+        let idx = self.slots.iter().position(Option::is_none)?;
+        self.slots[idx] = Some(entity);
+        Some(idx)
+    }
+
+    pub fn remove(&mut self, idx: usize) {
+        self.slots[idx] = None;
+    }
+
+    /// Count the entities that can be hurt.
+    pub const fn enemy_count(&self) -> u8 {
+        let mut count = 0;
+
+        let mut idx = 0;
+        while idx < MAX_ENTITIES {
+            if let Some(entity) = &self.slots[idx] {
+                if entity.health.is_some() {
+                    count += 1;
+                }
+            }
+            idx += 1;
+        }
+
+        count
+    }
+}
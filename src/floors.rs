@@ -0,0 +1,83 @@
+//! Patterns painted across the floor, to break up big rooms.
+//!
+//! Each map can paint rectangles of its floor with a pattern, turned and
+//! flipped however the designer likes, so the same few patterns can cover a
+//! lot of ground without looking the same everywhere.
+
+use crate::math::floorf;
+
+/// How many texels across and down a floor's pattern is.
+const FLOOR_TEXELS: i32 = 8;
+
+/// The color of the lines making up a floor's pattern.
+const LINE: u8 = 1;
+
+/// A pattern that can be painted across the floor.
+#[derive(Clone, Copy)]
+pub enum FloorTexture {
+    /// Big square flagstones, offset row by row.
+    Flagstones,
+    /// Long boards running from west to east, before being turned.
+    Planks,
+    /// A metal grate, with a thicker bar along one side.
+    Grate,
+}
+
+impl FloorTexture {
+    /// Check if the texel at (u, v) is one of the pattern's lines.
+    fn is_line(self, u: i32, v: i32) -> bool {
+        match self {
+            Self::Flagstones => v % 4 == 0 || (u + v / 4 % 2 * 2) % 4 == 0,
+            Self::Planks => v % 3 == 0 || (u + v / 3 * 3) % 8 == 0,
+            Self::Grate => u % 2 == 0 || v == 0 || v == 1,
+        }
+    }
+}
+
+/// A rectangle of cells with a pattern painted across it.
+pub struct FloorArea {
+    /// The cell at the area's top-left corner.
+    pub x: i32,
+    pub y: i32,
+    /// How many cells wide and tall the area is.
+    pub width: i32,
+    pub height: i32,
+    pub texture: FloorTexture,
+    /// How many quarter turns clockwise the pattern is turned.
+    pub turns: u8,
+    /// Whether the pattern is flipped from left to right, before it's turned.
+    pub flipped: bool,
+}
+
+impl FloorArea {
+    /// Check if the area covers the point (x, y) on the map.
+    fn contains(&self, x: f32, y: f32) -> bool {
+        let (cell_x, cell_y) = (floorf(x) as i32, floorf(y) as i32);
+        (self.x..self.x + self.width).contains(&cell_x)
+            && (self.y..self.y + self.height).contains(&cell_y)
+    }
+
+    /// Check if the point (x, y) on the map lands on one of the pattern's
+    /// lines.
+    fn is_line(&self, x: f32, y: f32) -> bool {
+        let texel = |t: f32| ((t - floorf(t)) * FLOOR_TEXELS as f32) as i32;
+        let (mut u, mut v) = (texel(x), texel(y));
+
+        let last = FLOOR_TEXELS - 1;
+        if self.flipped {
+            u = last - u;
+        }
+        for _ in 0..self.turns % 4 {
+            (u, v) = (v, last - u);
+        }
+
+        self.texture.is_line(u, v)
+    }
+}
+
+/// The color of the floor at the point (x, y) on the map, if it's painted
+/// with a pattern. Later areas are painted over earlier ones.
+pub fn floor_color(areas: &[FloorArea], x: f32, y: f32) -> Option<u8> {
+    let area = areas.iter().rev().find(|area| area.contains(x, y))?;
+    area.is_line(x, y).then_some(LINE)
+}
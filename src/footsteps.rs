@@ -0,0 +1,67 @@
+//! Footsteps, which play every so often while the player walks around.
+//!
+//! A step is taken every time the player covers another stride, so they
+//! come quicker while sprinting and slower while wading or crouching. What
+//! they sound like depends on the floor being walked on. Footsteps are the
+//! least important sound there is, so they're skipped rather than cutting
+//! off anything else playing on the noise channel.
+
+use crate::{
+    map::{floor_attributes, WorldMap, WATER},
+    math::{distance, floorf},
+    sfx,
+    timestep::DT,
+    State,
+};
+
+/// How far the player walks between each footstep, in map units.
+const STRIDE: f32 = 0.8;
+
+/// How far the player has to go until their next footstep, and which foot
+/// it's on.
+pub struct Footsteps {
+    stride_left: f32,
+    left_foot: bool,
+}
+
+impl Footsteps {
+    pub const fn new() -> Self {
+        Self {
+            stride_left: STRIDE,
+            left_foot: false,
+        }
+    }
+}
+
+impl State {
+    /// Take a step every time the player covers another stride, as long as
+    /// they're on the ground.
+    pub fn update_footsteps(&mut self) {
+        if !self.on_ground() || self.noclip.enabled {
+            return;
+        }
+
+        let (dx, dy) = self.player_velocity;
+        let steps = &mut self.footsteps;
+        steps.stride_left -= distance(dx, dy) * DT;
+        if steps.stride_left > 0.0 {
+            return;
+        }
+        steps.stride_left = STRIDE;
+        steps.left_foot = !steps.left_foot;
+
+        // each foot lands with a slightly different pitch.
+        let foot = if steps.left_foot { 0 } else { 20 };
+        let tile = self
+            .map
+            .tile_at(floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        let sound = if tile == WATER {
+            sfx::STEP_WATER
+        } else if floor_attributes(tile).damage > 0 {
+            sfx::STEP_HOT
+        } else {
+            sfx::STEP
+        };
+        sfx::play(&sound.raised(foot));
+    }
+}
@@ -0,0 +1,117 @@
+//! Direct writes into WASM-4's packed 2bpp framebuffer.
+//!
+//! Every framebuffer byte holds four horizontally adjacent pixels, so drawing
+//! the screen one column at a time would read-modify-write each byte four
+//! times. Instead, columns are composed into a [`ColumnStage`] four at a time
+//! and then packed and written row by row, touching every byte exactly once.
+
+use core::ops::Range;
+
+const FRAMEBUFFER: *mut u8 = 0xa0 as *mut u8;
+
+/// Width and height of the screen, in pixels.
+pub const SCREEN_SIZE: usize = 160;
+
+/// How many pixels share a single framebuffer byte.
+pub const PIXELS_PER_BYTE: usize = 4;
+
+/// How many bytes make up one row of the framebuffer.
+pub const ROW_BYTES: usize = SCREEN_SIZE / PIXELS_PER_BYTE;
+
+/// Staging area for the four screen columns that share framebuffer bytes.
+///
+/// Each staged pixel is a palette index from 0 to 3. Columns that are a single
+/// color all the way down can be staged with [`ColumnStage::fill`], which skips
+/// the per-pixel work entirely.
+pub struct ColumnStage {
+    columns: [[u8; SCREEN_SIZE]; PIXELS_PER_BYTE],
+    /// The color of each column if it is filled with just one color.
+    fills: [Option<u8>; PIXELS_PER_BYTE],
+    /// Which of the staged rows are written to the framebuffer, and the
+    /// screen row the first of them lands on.
+    rows: Range<usize>,
+    to: usize,
+}
+
+impl ColumnStage {
+    pub const fn new() -> Self {
+        Self::band(0..SCREEN_SIZE, 0)
+    }
+
+    /// A stage that only writes out the staged `rows`, moved up or down so
+    /// that the first of them lands on screen row `to`.
+    pub const fn band(rows: Range<usize>, to: usize) -> Self {
+        Self {
+            columns: [[0; SCREEN_SIZE]; PIXELS_PER_BYTE],
+            fills: [None; PIXELS_PER_BYTE],
+            rows,
+            to,
+        }
+    }
+
+    /// Get the pixels of one of the four staged columns, from top to bottom.
+    pub fn column(&mut self, lane: usize) -> &mut [u8; SCREEN_SIZE] {
+        if let Some(color) = self.fills[lane].take() {
+            self.columns[lane].fill(color);
+        }
+
+        &mut self.columns[lane]
+    }
+
+    /// Stage one of the four columns as a single color from top to bottom.
+    pub fn fill(&mut self, lane: usize, color: u8) {
+        self.fills[lane] = Some(color & 0b11);
+    }
+
+    /// Pack the staged columns and write them to the framebuffer, with the
+    /// leftmost staged column landing on screen column `x`.
+    ///
+    /// `x` must be a multiple of [`PIXELS_PER_BYTE`].
+    pub fn blit(&mut self, x: usize) {
+        let mut offset = self.to * ROW_BYTES + x / PIXELS_PER_BYTE;
+
+        // if every column is a single color then every row packs to the same byte.
+        if let [Some(a), Some(b), Some(c), Some(d)] = self.fills {
+            let byte = a | (b << 2) | (c << 4) | (d << 6);
+
+            for _ in self.rows.clone() {
+                unsafe { FRAMEBUFFER.add(offset).write(byte) };
+                offset += ROW_BYTES;
+            }
+
+            self.fills = [None; PIXELS_PER_BYTE];
+            return;
+        }
+
+        for lane in 0..PIXELS_PER_BYTE {
+            self.column(lane);
+        }
+
+        let [a, b, c, d] = &self.columns;
+
+        for y in self.rows.clone() {
+            // the leftmost pixel lives in the least significant bits.
+            let byte =
+                (a[y] & 0b11) | ((b[y] & 0b11) << 2) | ((c[y] & 0b11) << 4) | ((d[y] & 0b11) << 6);
+
+            unsafe { FRAMEBUFFER.add(offset).write(byte) };
+            offset += ROW_BYTES;
+        }
+    }
+}
+
+/// Copy whole rows of the framebuffer, starting at row `y`, into `rows`.
+pub fn copy_rows(y: usize, rows: &mut [u8]) {
+    unsafe {
+        let start = FRAMEBUFFER.add(y * ROW_BYTES);
+        core::ptr::copy_nonoverlapping(start, rows.as_mut_ptr(), rows.len());
+    }
+}
+
+/// Write whole rows back into the framebuffer, starting at row `y`.
+pub fn paste_rows(y: usize, rows: &[u8]) {
+    unsafe {
+        let start = FRAMEBUFFER.add(y * ROW_BYTES);
+        core::ptr::copy_nonoverlapping(rows.as_ptr(), start, rows.len());
+    }
+}
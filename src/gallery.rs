@@ -0,0 +1,282 @@
+//! A shooting gallery, played as a bonus stage after the last level.
+//!
+//! The player stands at one end of a long room, turning to aim at targets
+//! that pop up further down it, and scores for every one they hit before
+//! time runs out.
+
+use core::f32::consts::FRAC_PI_4;
+
+use crate::{
+    entities::{Entities, Entity},
+    map::{Map, FLOOR, STONE},
+    rng::Rng,
+    sfx,
+    sprites::{Graphic, Sprite},
+    text::{text, Line},
+    timestep::{DT, STEPS_PER_SECOND},
+    toasts,
+    ui::Ui,
+    Mode, State, BUTTON_1, BUTTON_LEFT, BUTTON_RIGHT, DRAW_COLORS, TURN_SPEED,
+};
+
+/// How long the player has to hit as many targets as they can, in updates.
+const TIME_LIMIT: u32 = 30 * STEPS_PER_SECOND;
+
+/// How many updates a target stays up before dropping back down.
+const TARGET_FRAMES: u32 = 90;
+/// How many updates pass between targets popping up.
+const POP_INTERVAL: u32 = 35;
+
+/// How many points each target is worth.
+const POINTS_PER_TARGET: u32 = 100;
+
+/// How far the player can turn away from looking straight down the room.
+const MAX_AIM: f32 = FRAC_PI_4;
+
+/// The size of the room, in cells.
+const ROOM_WIDTH: i32 = 9;
+const ROOM_HEIGHT: i32 = 7;
+
+/// Where the targets can pop up.
+const SLOTS: [(f32, f32); 8] = [
+    (4.5, 1.5),
+    (4.5, 4.5),
+    (5.5, 2.5),
+    (6.5, 5.5),
+    (6.5, 3.5),
+    (7.5, 1.5),
+    (7.5, 5.5),
+    (7.5, 3.5),
+];
+
+const TARGET: Sprite = Sprite {
+    width: 16,
+    height: 16,
+    data: &[
+        0x00, 0x3f, 0xfc, 0x00, 0x03, 0xd5, 0x57, 0xc0, 0x0d, 0x7f, 0xfd, 0x70, 0x35, 0xf5, 0x5f,
+        0x5c, 0x37, 0xdf, 0xf7, 0xdc, 0xdf, 0x7e, 0xbd, 0xf7, 0xdf, 0x7e, 0xbd, 0xf7, 0x37, 0xdf,
+        0xf7, 0xdc, 0x35, 0xf5, 0x5f, 0x5c, 0x0d, 0x7f, 0xfd, 0x70, 0x03, 0xd5, 0x57, 0xc0, 0x00,
+        0x3f, 0xfc, 0x00, 0x00, 0x03, 0xc0, 0x00, 0x00, 0x03, 0xc0, 0x00, 0x00, 0x03, 0xc0, 0x00,
+        0x00, 0x3f, 0xfc, 0x00,
+    ],
+};
+
+/// A target that has popped up.
+#[derive(Clone, Copy)]
+struct Target {
+    /// The target's slot in [`State::entities`].
+    entity: usize,
+    /// How many more updates it stays up for.
+    frames_left: u32,
+}
+
+/// How the current trip to the shooting gallery is going.
+pub struct Gallery {
+    rng: Rng,
+    /// How many updates are left before time runs out.
+    frames_left: u32,
+    /// How many updates until the next target pops up.
+    pop_in: u32,
+    /// The target standing in each of the [`SLOTS`], if there is one.
+    targets: [Option<Target>; SLOTS.len()],
+    shots: u32,
+    hits: u32,
+    /// The best score anyone has got in the gallery.
+    best_score: u32,
+}
+
+impl Gallery {
+    pub const fn new() -> Self {
+        Self {
+            rng: Rng::new(0),
+            frames_left: 0,
+            pop_in: 0,
+            targets: [None; SLOTS.len()],
+            shots: 0,
+            hits: 0,
+            best_score: 0,
+        }
+    }
+
+    fn score(&self) -> u32 {
+        self.hits * POINTS_PER_TARGET
+    }
+}
+
+/// Build the long room the gallery is played in.
+fn room() -> Map {
+    let mut map = Map::filled(ROOM_WIDTH as usize, ROOM_HEIGHT as usize, STONE);
+
+    for y in 1..ROOM_HEIGHT - 1 {
+        for x in 1..ROOM_WIDTH - 1 {
+            map.set_tile(x, y, FLOOR);
+        }
+    }
+
+    map
+}
+
+impl State {
+    /// Set up the gallery, with the player at one end of the room.
+    pub fn start_gallery(&mut self) {
+        self.map = room();
+        self.entities = Entities::new(&[]);
+        (self.player_x, self.player_y, self.player_angle) = (1.5, ROOM_HEIGHT as f32 / 2.0, 0.0);
+
+        let gallery = &mut self.gallery;
+        gallery.rng = Rng::new(self.title_frames);
+        gallery.frames_left = TIME_LIMIT;
+        gallery.pop_in = POP_INTERVAL;
+        gallery.targets = [None; SLOTS.len()];
+        gallery.shots = 0;
+        gallery.hits = 0;
+    }
+
+    /// Aim, shoot and keep the targets coming until time runs out, then show
+    /// how it went.
+    pub fn update_gallery(&mut self, gamepad: u8, pressed: u8) {
+        if self.gallery.frames_left == 0 {
+            self.cast_view();
+            self.draw_view();
+            self.draw_gallery_results(pressed);
+            return;
+        }
+
+        self.gallery.frames_left -= 1;
+        if self.gallery.frames_left == 0 {
+            let score = self.gallery.score();
+            if score > self.gallery.best_score {
+                self.gallery.best_score = score;
+                toasts::push(&toasts::TROPHY, "NEW RECORD!");
+            }
+        }
+
+        // the player's feet are planted, so all they can do is turn.
+        let (mut left, mut right) = (gamepad & BUTTON_LEFT != 0, gamepad & BUTTON_RIGHT != 0);
+        if self.mutators.mirror {
+            (left, right) = (right, left);
+        }
+        if left {
+            self.player_angle += TURN_SPEED * DT;
+        }
+        if right {
+            self.player_angle -= TURN_SPEED * DT;
+        }
+        self.player_angle = self.player_angle.clamp(-MAX_AIM, MAX_AIM);
+
+        // the view has to be cast before shooting, to know what's in the way.
+        self.cast_view();
+
+        if pressed & BUTTON_1 != 0 {
+            let kills = self.stats.kills;
+            self.attack();
+            self.gallery.shots += 1;
+
+            if self.stats.kills > kills {
+                self.gallery.hits += 1;
+                sfx::play(&sfx::TARGET_HIT);
+            }
+        }
+
+        self.update_targets();
+
+        self.draw_view();
+        self.draw_gallery_hud();
+    }
+
+    /// Drop targets that were shot or have been up too long, and pop up new
+    /// ones.
+    fn update_targets(&mut self) {
+        let gallery = &mut self.gallery;
+
+        for slot in &mut gallery.targets {
+            let Some(target) = slot else {
+                continue;
+            };
+
+            target.frames_left = target.frames_left.saturating_sub(1);
+            if target.frames_left == 0 {
+                self.entities.remove(target.entity);
+            }
+            // shot targets are already gone from the world.
+            if self.entities.get_mut(target.entity).is_none() {
+                *slot = None;
+            }
+        }
+
+        gallery.pop_in -= 1;
+        if gallery.pop_in > 0 {
+            return;
+        }
+        gallery.pop_in = POP_INTERVAL;
+
+        let slot = gallery.rng.below(SLOTS.len());
+        if gallery.targets[slot].is_some() {
+            return;
+        }
+
+        let (x, y) = SLOTS[slot];
+        if let Some(entity) = self
+            .entities
+            .insert(Entity::enemy(x, y, Graphic::Sprite(&TARGET), 1))
+        {
+            gallery.targets[slot] = Some(Target {
+                entity,
+                frames_left: TARGET_FRAMES,
+            });
+        }
+    }
+
+    /// Draw the score and time left along the bottom of the screen.
+    fn draw_gallery_hud(&self) {
+        let seconds = self.gallery.frames_left.div_ceil(STEPS_PER_SECOND);
+
+        unsafe { *DRAW_COLORS = 0x4 };
+        text(
+            Line::new()
+                .push_str("SCORE ")
+                .push_number(self.gallery.score())
+                .as_str(),
+            4,
+            148,
+        );
+        text(
+            Line::new()
+                .push_str("TIME ")
+                .push_two_digits(seconds)
+                .as_str(),
+            108,
+            148,
+        );
+    }
+
+    /// Show the final score, moving on to the next level once the player is
+    /// ready.
+    fn draw_gallery_results(&mut self, pressed: u8) {
+        let gallery = &self.gallery;
+
+        let mut ui = Ui::new(16, 32, pressed);
+        let mut dialog = ui.dialog(128, 96);
+        dialog.label("TIME'S UP!");
+        dialog.space(4);
+        dialog.line(Line::new().push_str("SCORE ").push_number(gallery.score()));
+        dialog.line(
+            Line::new()
+                .push_str("HITS  ")
+                .push_number(gallery.hits)
+                .push_str("/")
+                .push_number(gallery.shots),
+        );
+        dialog.line(
+            Line::new()
+                .push_str("BEST  ")
+                .push_number(gallery.best_score),
+        );
+        dialog.space(4);
+
+        let mut focus = 0;
+        if dialog.menu(&["CONTINUE"], &mut focus).is_some() {
+            self.transition_to(Mode::Playing);
+        }
+    }
+}
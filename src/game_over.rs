@@ -0,0 +1,32 @@
+//! The screen shown after dying, with how far the player got through the
+//! level, and the choice of trying it again or giving up.
+
+use crate::{ui::Ui, Mode, State};
+
+impl State {
+    /// Show the stats for the level the player just died on, starting it
+    /// over or going back to the title screen once they've picked which.
+    pub fn update_game_over(&mut self, pressed: u8) {
+        let mut ui = Ui::new(16, 8, pressed);
+
+        ui.label("GAME OVER");
+        ui.space(8);
+
+        self.show_stats(&mut ui);
+        ui.space(4);
+
+        match ui.menu(&["RETRY", "TITLE"], &mut self.menu_focus) {
+            Some(0) => self.transition_to(Mode::Playing),
+            Some(_) => self.transition_to(Mode::Title),
+            None => {}
+        }
+    }
+
+    /// Start the level the player died on over from the beginning, with
+    /// everything in it back where it was and the points scored on it taken
+    /// back off.
+    pub fn retry_level(&mut self) {
+        self.score = self.score.saturating_sub(self.stats.points);
+        self.reset_level();
+    }
+}
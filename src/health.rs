@@ -0,0 +1,115 @@
+//! The player's health, what wears it down, and how much of it is left.
+//!
+//! Blows from enemies and the like leave the player unable to be hit again
+//! for a moment, so a crowd can't take them apart all in one go. Floors that
+//! hurt wear them down steadily instead, invulnerable or not.
+
+use crate::{
+    framebuffer::SCREEN_SIZE,
+    map::{floor_attributes, WorldMap},
+    math::floorf,
+    text::{text, Line},
+    State, DRAW_COLORS,
+};
+
+/// How much health the player starts each life with.
+pub const MAX_HEALTH: u8 = 100;
+/// How little health the player has left before it's shown as running low.
+const LOW_HEALTH: u8 = 25;
+
+/// How many updates pass between each bit of damage from the floor.
+const BURN_INTERVAL: u32 = 4;
+
+/// How many updates the screen stays washed in red after the player is hurt.
+const HURT_FRAMES: u8 = 10;
+/// How red the screen goes when the player is hurt, compared to dying.
+const HURT_REDNESS: f32 = 0.4;
+/// How many updates after being hit the player can't be hit again for.
+const INVULNERABLE_FRAMES: u8 = 45;
+
+impl State {
+    /// Wear down the player's health while they're standing on a floor that
+    /// hurts.
+    pub fn update_floor_damage(&mut self) {
+        self.hurt_frames = self.hurt_frames.saturating_sub(1);
+        self.invulnerable_frames = self.invulnerable_frames.saturating_sub(1);
+
+        let cell = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        let damage = floor_attributes(self.map.tile_at(cell.0, cell.1)).damage;
+
+        if damage > 0 && self.stats.frames % BURN_INTERVAL == 0 {
+            self.take_damage(damage, cell);
+        }
+    }
+
+    /// Take a hit of `damage` from the enemy in the cell `source`, as much
+    /// as the difficulty makes it, unless the player was hit too recently to
+    /// be hit again.
+    pub fn hurt(&mut self, damage: u8, source: (i32, i32)) {
+        if self.invulnerable_frames > 0 {
+            return;
+        }
+
+        self.invulnerable_frames = INVULNERABLE_FRAMES;
+        // with only one point of health, any hit at all is the last.
+        let damage = if self.mutators.one_hp {
+            self.health
+        } else {
+            self.enemy_damage(damage)
+        };
+        self.take_damage(damage, source);
+    }
+
+    /// Take `damage` from whatever is in the cell `source`, dying if it's
+    /// too much.
+    fn take_damage(&mut self, damage: u8, source: (i32, i32)) {
+        self.health = self.health.saturating_sub(damage);
+        self.hurt_frames = HURT_FRAMES;
+
+        if self.health == 0 {
+            self.start_kill_cam(source);
+        }
+    }
+
+    /// The most health the player can have.
+    pub fn max_health(&self) -> u8 {
+        if self.mutators.one_hp {
+            1
+        } else {
+            MAX_HEALTH
+        }
+    }
+
+    /// Put the player back to full health.
+    pub fn heal_fully(&mut self) {
+        self.health = self.max_health();
+        self.hurt_frames = 0;
+        self.invulnerable_frames = 0;
+    }
+
+    /// How much the screen is washed in red from being hurt, from 0.0 to 1.0.
+    pub fn hurt_redness(&self) -> f32 {
+        self.hurt_frames as f32 / HURT_FRAMES as f32 * HURT_REDNESS
+    }
+
+    /// Show how much health the player has left in the bottom-left corner of
+    /// the screen, blinking while they can't be hit and picked out once it's
+    /// running low.
+    pub fn draw_health(&self) {
+        if self.invulnerable_frames / 4 % 2 == 1 {
+            return;
+        }
+
+        unsafe {
+            *DRAW_COLORS = if self.health <= LOW_HEALTH { 0x2 } else { 0x4 };
+        }
+        text(
+            Line::new()
+                .push_str("+")
+                .push_number(self.health as u32)
+                .as_str(),
+            4,
+            SCREEN_SIZE as i32 - 12,
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! The layers the playing screen is drawn in, and what draws each of them.
+//!
+//! Everything drawn while playing is a hook on one of the [`Layer`]s, drawn
+//! from the world up to the overlays on top of everything else. A new visual
+//! feature draws itself by adding a hook to [`PLAYING`], rather than by
+//! editing the code that draws everything else. Hooks on the same layer are
+//! drawn in the order they were added.
+
+use crate::{messages, State};
+
+/// The most hooks the playing screen can have.
+const MAX_HOOKS: usize = 24;
+
+/// What part of the screen a hook draws, in the order the parts are drawn.
+#[derive(Clone, Copy)]
+pub enum Layer {
+    /// The walls, floors and ceilings, along with the sprites staged into
+    /// them column by column.
+    World,
+    /// Things drawn over the world as it's seen, like falling rain and
+    /// damage numbers.
+    Effects,
+    /// The heads-up display around the edges of the screen.
+    Hud,
+    /// Things covering up the view, like the map and messages.
+    Overlay,
+}
+
+/// Something that draws part of the playing screen, given the state of the
+/// gamepad.
+pub type Draw = fn(&mut State, u8);
+
+/// Every hook drawing the playing screen, kept in the order they're drawn.
+pub struct RenderHooks {
+    hooks: [Option<(Layer, Draw)>; MAX_HOOKS],
+    len: usize,
+}
+
+impl RenderHooks {
+    pub const fn new() -> Self {
+        Self {
+            hooks: [None; MAX_HOOKS],
+            len: 0,
+        }
+    }
+
+    /// Add a hook drawing on `layer`, after any already on it and before any
+    /// on the layers above it.
+    pub const fn with(mut self, layer: Layer, draw: Draw) -> Self {
+        assert!(self.len < MAX_HOOKS, "there are too many render hooks");
+
+        // insertion sort, since the hooks are only ever added up front.
+        let mut idx = self.len;
+        while idx > 0 {
+            match self.hooks[idx - 1] {
+                Some((above, _)) if above as u8 > layer as u8 => {
+                    self.hooks[idx] = self.hooks[idx - 1];
+                    idx -= 1;
+                }
+                _ => break,
+            }
+        }
+
+        self.hooks[idx] = Some((layer, draw));
+        self.len += 1;
+        self
+    }
+}
+
+impl State {
+    /// Draw the screen by running every one of `hooks`, from the bottom layer
+    /// up.
+    pub fn draw_hooks(&mut self, hooks: &RenderHooks, gamepad: u8) {
+        for (_, draw) in hooks.hooks[..hooks.len].iter().flatten() {
+            draw(self, gamepad);
+        }
+    }
+}
+
+/// Everything drawn on the playing screen.
+pub const PLAYING: RenderHooks = RenderHooks::new()
+    .with(Layer::World, |state, _| {
+        state.cast_view();
+        state.draw_view();
+    })
+    .with(Layer::Effects, |state, _| state.draw_weather())
+    .with(Layer::Effects, |state, _| state.draw_damage_numbers())
+    .with(Layer::Hud, |state, _| state.draw_health())
+    .with(Layer::Hud, |state, _| state.draw_ammo())
+    .with(Layer::Hud, |state, _| state.draw_score())
+    .with(Layer::Hud, |state, _| state.draw_keys())
+    .with(Layer::Hud, |state, _| state.draw_stamina())
+    .with(Layer::Hud, |state, _| state.draw_dash_cooldown())
+    .with(Layer::Hud, |state, _| state.draw_time_attack())
+    .with(Layer::Hud, |state, _| state.draw_timer())
+    .with(Layer::Hud, |state, _| state.draw_noclip())
+    .with(Layer::Overlay, |state, _| state.draw_pool_report())
+    .with(Layer::Overlay, |state, _| {
+        if state.show_automap {
+            state.draw_automap();
+        }
+    })
+    .with(Layer::Overlay, |_, _| messages::draw())
+    .with(Layer::Overlay, |state, gamepad| {
+        state.draw_input_display(gamepad)
+    });
@@ -0,0 +1,51 @@
+//! An overlay showing which buttons are held down on every update, for
+//! recording videos and checking that the controls do what they should.
+
+use crate::{
+    framebuffer::SCREEN_SIZE, rect, text::text, State, BUTTON_1, BUTTON_2, BUTTON_DOWN,
+    BUTTON_LEFT, BUTTON_RIGHT, BUTTON_UP, DRAW_COLORS,
+};
+
+/// The width and height of a character in the built-in font.
+const CHAR_SIZE: i32 = 8;
+
+/// Each button, the character it's shown as, and where that goes in the
+/// overlay, counted in characters.
+const BUTTONS: [(u8, &str, i32, i32); 6] = [
+    (BUTTON_UP, "\u{86}", 1, 0),
+    (BUTTON_LEFT, "\u{84}", 0, 1),
+    (BUTTON_DOWN, "\u{87}", 1, 1),
+    (BUTTON_RIGHT, "\u{85}", 2, 1),
+    (BUTTON_1, "\u{80}", 4, 1),
+    (BUTTON_2, "\u{81}", 5, 1),
+];
+
+/// Where the overlay's top-left corner goes on screen.
+const ORIGIN: (i32, i32) = (4, SCREEN_SIZE as i32 - 4 - 2 * CHAR_SIZE);
+
+impl State {
+    /// Draw the buttons in the bottom-left corner of the screen, lighting up
+    /// the ones held down in `gamepad`, if the overlay is switched on.
+    pub fn draw_input_display(&self, gamepad: u8) {
+        if !self.show_inputs {
+            return;
+        }
+
+        let (left, top) = ORIGIN;
+
+        unsafe {
+            *DRAW_COLORS = 0x11;
+            rect(
+                left - 1,
+                top - 1,
+                6 * CHAR_SIZE as u32 + 2,
+                2 * CHAR_SIZE as u32 + 2,
+            );
+
+            for (button, glyph, column, row) in BUTTONS {
+                *DRAW_COLORS = if gamepad & button != 0 { 0x4 } else { 0x2 };
+                text(glyph, left + column * CHAR_SIZE, top + row * CHAR_SIZE);
+            }
+        }
+    }
+}
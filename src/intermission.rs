@@ -0,0 +1,114 @@
+//! The stats screen shown between levels.
+
+use crate::{
+    items::Item, levels::LEVELS, text::Line, timestep::STEPS_PER_SECOND, ui::Ui, Mode, State,
+};
+
+/// How wide the tally bars are, in pixels.
+const BAR_WIDTH: u32 = 128;
+
+impl State {
+    /// Show the stats for the level that was just finished, moving on to the
+    /// next level once the player is ready.
+    pub fn update_intermission(&mut self, pressed: u8) {
+        // there are a lot of stats to fit on the screen above the menu.
+        let mut ui = Ui::new(16, 8, pressed);
+
+        if self.dungeon.is_some() {
+            ui.label("DUNGEON COMPLETE!");
+        } else {
+            ui.line(
+                Line::new()
+                    .push_str("LEVEL ")
+                    .push_number(self.level as u32 + 1)
+                    .push_str(" COMPLETE!"),
+            );
+        }
+        ui.space(8);
+
+        self.show_stats(&mut ui);
+        ui.space(4);
+
+        // there's a bonus stage after the last level, before starting over,
+        // unless it's the end of a time attack run.
+        let last = self.dungeon.is_none() && self.level == LEVELS.len() - 1;
+        let item = match (last, self.time_attack.is_some()) {
+            (true, true) => "FINAL RANKING",
+            (true, false) => "BONUS STAGE",
+            (false, _) => "NEXT LEVEL",
+        };
+
+        let mut focus = 0;
+        if ui.menu(&[item], &mut focus).is_some() {
+            match (last, self.time_attack.is_some()) {
+                (true, true) => self.end_time_attack(),
+                (true, false) => self.transition_to(Mode::Gallery),
+                (false, _) => self.transition_to(Mode::Playing),
+            }
+        }
+    }
+    /// List how the level went so far: the time taken, and how much of
+    /// everything there was to find has been found.
+    pub fn show_stats(&self, ui: &mut Ui) {
+        ui.line(push_time(
+            Line::new().push_str("TIME    "),
+            self.stats.frames,
+        ));
+        ui.line(push_time(
+            Line::new().push_str("PAR     "),
+            self.level().par_frames,
+        ));
+        ui.space(4);
+
+        let (kills, kills_total) = (self.stats.kills as u32, self.stats.kills_total as u32);
+        ui.line(
+            Line::new()
+                .push_str("KILLS   ")
+                .push_number(kills)
+                .push_str("/")
+                .push_number(kills_total),
+        );
+        ui.bar(kills, kills_total, BAR_WIDTH);
+
+        let (secrets, secrets_total) = (
+            self.stats.secrets_found as u32,
+            self.level().map.secrets.len() as u32,
+        );
+        ui.line(
+            Line::new()
+                .push_str("SECRETS ")
+                .push_number(secrets)
+                .push_str("/")
+                .push_number(secrets_total),
+        );
+        ui.bar(secrets, secrets_total, BAR_WIDTH);
+
+        let treasure_total = (self.level().map.entities.iter())
+            .filter(|entity| entity.item == Some(Item::Treasure))
+            .count() as u32;
+        ui.line(
+            Line::new()
+                .push_str("TREASURE ")
+                .push_number(self.stats.treasure as u32)
+                .push_str("/")
+                .push_number(treasure_total),
+        );
+        ui.bar(self.stats.treasure as u32, treasure_total, BAR_WIDTH);
+
+        ui.line(
+            Line::new()
+                .push_str("SCORE   ")
+                .push_number(self.score)
+                .push_str(" +")
+                .push_number(self.stats.points),
+        );
+    }
+}
+
+/// Write out a number of updates as minutes and seconds.
+pub fn push_time(line: &mut Line, frames: u32) -> &mut Line {
+    let seconds = frames / STEPS_PER_SECOND;
+    line.push_number(seconds / 60)
+        .push_str(":")
+        .push_two_digits(seconds % 60)
+}
@@ -0,0 +1,170 @@
+//! Things lying around the map for the player to pick up by walking over
+//! them: keys, health, ammo and treasure.
+
+use crate::{
+    entities::Entity,
+    framebuffer::SCREEN_SIZE,
+    keys::{Key, KEY},
+    math::distance,
+    score::TREASURE_POINTS,
+    sfx::{self, Sound},
+    sprites::{Sprite, Tint},
+    text::{text, Line},
+    toasts, State, DRAW_COLORS,
+};
+
+/// How close the player has to get to an item to pick it up.
+const PICK_UP_DISTANCE: f32 = 0.5;
+
+/// How much ammo the player starts each level with, and the most they can
+/// carry.
+pub const STARTING_AMMO: u8 = 20;
+const MAX_AMMO: u8 = 99;
+
+/// How much each pickup gives on
+/// [`Difficulty::Normal`](crate::difficulty::Difficulty::Normal).
+pub const HEALTH_PACK_HEALS: u8 = 25;
+pub const AMMO_BOX_ROUNDS: u8 = 10;
+
+pub const HEALTH_PACK: Sprite = Sprite {
+    width: 8,
+    height: 8,
+    data: &[
+        0x00, 0x00, 0x3f, 0xfc, 0x36, 0x9c, 0x3a, 0xac, 0x3a, 0xac, 0x36, 0x9c, 0x3f, 0xfc, 0x00,
+        0x00,
+    ],
+};
+
+pub const AMMO_BOX: Sprite = Sprite {
+    width: 8,
+    height: 8,
+    data: &[
+        0x00, 0x00, 0x22, 0x20, 0x22, 0x20, 0x11, 0x10, 0x11, 0x10, 0xff, 0xfc, 0xd5, 0x5c, 0xff,
+        0xfc,
+    ],
+};
+
+pub const TREASURE: Sprite = Sprite {
+    width: 8,
+    height: 8,
+    data: &[
+        0x0f, 0xf0, 0x3a, 0xac, 0xe9, 0x6b, 0xe6, 0xab, 0xe6, 0xab, 0xea, 0xab, 0x3a, 0xac, 0x0f,
+        0xf0,
+    ],
+};
+
+/// Something the player can pick up.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    /// A key, which opens the doors of its color.
+    Key(Key),
+    /// A health pack, which heals the player by this much.
+    Health(u8),
+    /// A box of this many rounds of ammo.
+    Ammo(u8),
+    /// Treasure, which is only worth having.
+    Treasure,
+}
+
+impl Item {
+    /// What the item looks like lying on the floor, and beside its toast.
+    pub const fn sprite(self) -> &'static Sprite {
+        match self {
+            Self::Key(_) => &KEY,
+            Self::Health(_) => &HEALTH_PACK,
+            Self::Ammo(_) => &AMMO_BOX,
+            Self::Treasure => &TREASURE,
+        }
+    }
+
+    /// The tint the item is drawn with.
+    pub const fn tint(self) -> Tint {
+        match self {
+            Self::Key(key) => key.tint(),
+            _ => Tint::Normal,
+        }
+    }
+
+    /// What the player is told when they pick the item up.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Key(key) => key.name(),
+            Self::Health(_) => "HEALTH",
+            Self::Ammo(_) => "AMMO",
+            Self::Treasure => "TREASURE",
+        }
+    }
+
+    /// The sound of picking the item up.
+    const fn sound(self) -> &'static Sound {
+        match self {
+            Self::Key(_) => &sfx::PICKUP_KEY,
+            Self::Health(_) => &sfx::PICKUP_HEALTH,
+            Self::Ammo(_) => &sfx::PICKUP_AMMO,
+            Self::Treasure => &sfx::PICKUP_TREASURE,
+        }
+    }
+}
+
+impl State {
+    /// Pick up every item the player is standing close enough to, leaving
+    /// anything they've got no room for where it is.
+    pub fn pick_up_items(&mut self) {
+        let (x, y) = (self.player_x, self.player_y);
+        let in_reach = |entity: &Entity| distance(entity.x - x, entity.y - y) < PICK_UP_DISTANCE;
+
+        let mut skipped = 0u32;
+        loop {
+            let found = self.entities.indexed().find_map(|(idx, entity)| {
+                let item = entity.item.filter(|_| in_reach(entity))?;
+                (skipped & (1 << idx) == 0).then_some((idx, item))
+            });
+            let Some((idx, item)) = found else {
+                break;
+            };
+
+            if !self.take(item) {
+                skipped |= 1 << idx;
+                continue;
+            }
+
+            self.entities.remove(idx);
+            sfx::play(item.sound());
+            toasts::push(item.sprite(), item.name());
+        }
+    }
+
+    /// Add an item to what the player is carrying, unless they've already
+    /// got as much of it as they can use.
+    fn take(&mut self, item: Item) -> bool {
+        match item {
+            Item::Key(key) => self.keys |= key.bit(),
+            Item::Health(_) if self.health >= self.max_health() => return false,
+            Item::Health(amount) => {
+                self.health = self.health.saturating_add(amount).min(self.max_health())
+            }
+            Item::Ammo(_) if self.ammo >= MAX_AMMO => return false,
+            Item::Ammo(amount) => self.ammo = self.ammo.saturating_add(amount).min(MAX_AMMO),
+            Item::Treasure => {
+                self.stats.treasure += 1;
+                self.award(TREASURE_POINTS);
+            }
+        }
+
+        true
+    }
+
+    /// Show how much ammo the player has left along the bottom of the
+    /// screen, beside their health.
+    pub fn draw_ammo(&self) {
+        unsafe { *DRAW_COLORS = if self.ammo == 0 { 0x2 } else { 0x4 } };
+        text(
+            Line::new()
+                .push_str("*")
+                .push_number(self.ammo as u32)
+                .as_str(),
+            48,
+            SCREEN_SIZE as i32 - 12,
+        );
+    }
+}
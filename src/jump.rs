@@ -0,0 +1,86 @@
+//! Jumping, by pressing X and Z together while walking, which lifts the
+//! camera off the floor and lets the player get over hurdles.
+//!
+//! Only the camera and the player's feet leave the floor: everything else
+//! about moving around works the same in the air. Hurdles are low enough to
+//! land on top of, and walking off the end of one drops the player back
+//! down to the floor.
+
+use crate::{
+    map::{WorldMap, HURDLE, HURDLE_TOP},
+    math::floorf,
+    timestep::DT,
+    State,
+};
+
+/// How fast the player leaves the floor when they jump, in wall heights per
+/// second.
+const JUMP_SPEED: f32 = 2.2;
+/// How fast the player's upward speed falls away, in wall heights per second
+/// per second.
+const GRAVITY: f32 = 9.0;
+
+/// How high off the floor the player's feet are, and how fast they're
+/// going up or down.
+pub struct Jump {
+    /// How far the player's feet are off the floor, as a fraction of a
+    /// wall's height.
+    pub height: f32,
+    /// How fast the player is going up, in wall heights per second, which is
+    /// negative while they're falling.
+    velocity: f32,
+}
+
+impl Jump {
+    pub const fn new() -> Self {
+        Self {
+            height: 0.0,
+            velocity: 0.0,
+        }
+    }
+}
+
+impl State {
+    /// Jump, as long as the player is standing on something.
+    pub fn jump(&mut self) {
+        if self.on_ground() && !self.crouch.down {
+            self.jump.velocity = JUMP_SPEED;
+        }
+    }
+
+    /// Move the player's feet up or down, landing on whatever's under them.
+    pub fn update_jump(&mut self) {
+        let ground = self.ground_height();
+        let jump = &mut self.jump;
+        if jump.height <= ground && jump.velocity <= 0.0 {
+            (jump.height, jump.velocity) = (ground, 0.0);
+            return;
+        }
+
+        jump.velocity -= GRAVITY * DT;
+        jump.height += jump.velocity * DT;
+        if jump.height <= ground {
+            (jump.height, jump.velocity) = (ground, 0.0);
+        }
+    }
+
+    /// Check if the player is standing on something, rather than in the air.
+    pub fn on_ground(&self) -> bool {
+        self.jump.height == self.ground_height()
+    }
+
+    /// How high off the floor the top of whatever the player is standing
+    /// over is, which is the top of a hurdle if they're over one.
+    fn ground_height(&self) -> f32 {
+        if self.noclip.enabled {
+            return 0.0;
+        }
+
+        let (x, y) = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        if self.map.tile_at(x, y) == HURDLE {
+            HURDLE_TOP
+        } else {
+            0.0
+        }
+    }
+}
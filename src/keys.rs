@@ -0,0 +1,115 @@
+//! Keys lying around the map, and the locked doors they open.
+
+use crate::{
+    blit,
+    framebuffer::SCREEN_SIZE,
+    map::{WorldMap, FLOOR, RED_DOOR, YELLOW_DOOR},
+    messages, sfx,
+    sprites::{Sprite, Tint},
+    State, DRAW_COLORS,
+};
+
+/// The draw flag telling `blit` that a sprite has two bits per pixel.
+const BLIT_2BPP: u32 = 1;
+
+pub const KEY: Sprite = Sprite {
+    width: 8,
+    height: 8,
+    data: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3c, 0x00, 0xd7, 0x00, 0xd7, 0xff, 0xd7, 0x33, 0x3c,
+        0x33,
+    ],
+};
+
+/// A key, which opens the doors of the same color.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Red,
+    Yellow,
+}
+
+impl Key {
+    /// Every kind of key, in the order they're shown in the corner of the screen.
+    const ALL: [Self; 2] = [Self::Red, Self::Yellow];
+
+    /// The key's bit in [`State::keys`].
+    pub const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// The tint that keys of this color are drawn with.
+    pub const fn tint(self) -> Tint {
+        match self {
+            Self::Red => Tint::Team(1),
+            Self::Yellow => Tint::Normal,
+        }
+    }
+
+    /// The key that opens a door tile, if the tile is a locked door.
+    pub const fn for_door(tile: u8) -> Option<Self> {
+        match tile {
+            RED_DOOR => Some(Self::Red),
+            YELLOW_DOOR => Some(Self::Yellow),
+            _ => None,
+        }
+    }
+
+    /// What the player is told when they pick up this key.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Red => "RED KEY",
+            Self::Yellow => "YELLOW KEY",
+        }
+    }
+
+    /// What the player is told when they try a door without this key.
+    const fn needed(self) -> &'static str {
+        match self {
+            Self::Red => "You need the\nred key.",
+            Self::Yellow => "You need the\nyellow key.",
+        }
+    }
+}
+
+impl State {
+    /// Try to unlock the door in a cell, opening it if the player has the key.
+    ///
+    /// Returns `true` if there's a locked door there, whether or not it opened.
+    pub fn unlock_door(&mut self, x: i32, y: i32) -> bool {
+        let Some(key) = Key::for_door(self.map.tile_at(x, y)) else {
+            return false;
+        };
+
+        if self.keys & key.bit() != 0 && self.off_beat() {
+            self.rhythm.hold((x, y));
+        } else if self.keys & key.bit() != 0 {
+            self.map.set_tile(x, y, FLOOR);
+            self.play_sound_at(x as f32 + 0.5, y as f32 + 0.5, &sfx::DOOR);
+        } else {
+            messages::push(key.needed());
+        }
+
+        true
+    }
+
+    /// Draw every key the player is holding in the top-right corner.
+    pub fn draw_keys(&self) {
+        let held = Key::ALL.iter().filter(|key| self.keys & key.bit() != 0);
+
+        for (idx, key) in held.enumerate() {
+            let x = SCREEN_SIZE as i32 - (idx as i32 + 1) * (KEY.width as i32 + 2);
+
+            unsafe {
+                *DRAW_COLORS = key.tint().draw_colors();
+                blit(
+                    KEY.data.as_ptr(),
+                    x,
+                    2,
+                    KEY.width as u32,
+                    KEY.height as u32,
+                    BLIT_2BPP,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Replaying the player's last moments from whatever killed them, so they can
+//! see what went wrong.
+
+use crate::{
+    entities::Entity,
+    math::atan2f,
+    sfx,
+    sprites::{Graphic, PLAYER},
+    text::text,
+    Mode, State, DRAW_COLORS,
+};
+
+impl State {
+    /// Start replaying the player's last moments from the cell `killer`.
+    pub fn start_kill_cam(&mut self, killer: (i32, i32)) {
+        sfx::play(&sfx::DEATH);
+        self.mode = Mode::KillCam { frame: 0, killer };
+    }
+
+    /// Step through the replay, then move on to dying once it runs out or the
+    /// player skips it.
+    pub fn update_kill_cam(&mut self, skip: bool) {
+        if let Mode::KillCam { frame, .. } = &mut self.mode {
+            *frame += 1;
+
+            if skip || *frame as usize >= self.replay.len() {
+                self.mode = Mode::Dying { frames: 0 };
+            }
+        }
+    }
+
+    /// Draw the view from the killer, turned to watch the player.
+    pub fn draw_kill_cam(&mut self) {
+        let Mode::KillCam { frame, killer } = self.mode else {
+            return;
+        };
+        let Some(snapshot) = self.replay.get(frame as usize) else {
+            return;
+        };
+
+        // borrow the player's camera, putting it back once the view is drawn.
+        let player = (self.player_x, self.player_y, self.player_angle);
+        self.player_x = killer.0 as f32 + 0.5;
+        self.player_y = killer.1 as f32 + 0.5;
+        self.player_angle = atan2f(self.player_y - snapshot.y, snapshot.x - self.player_x);
+
+        // the player isn't in the world normally, so they need a stand-in.
+        let stand_in = self.entities.insert(Entity::new(
+            snapshot.x,
+            snapshot.y,
+            Graphic::Sprite(&PLAYER),
+        ));
+
+        self.cast_view();
+        self.draw_view();
+
+        if let Some(idx) = stand_in {
+            self.entities.remove(idx);
+        }
+        (self.player_x, self.player_y, self.player_angle) = player;
+
+        unsafe { *DRAW_COLORS = 0x4 };
+        text("KILL CAM", 4, 4);
+    }
+}
@@ -0,0 +1,177 @@
+//! Kiosk mode, for leaving the game running unattended at shows and meetups.
+//!
+//! When it's built with the `kiosk` feature, a title screen that nobody
+//! touches moves on to a screen explaining the controls, and then to a demo
+//! of the first level, before going back to the title screen to start over.
+//! A game that's been left alone partway through goes back to the title
+//! screen by itself, ready for the next person.
+
+use crate::{
+    timestep::STEPS_PER_SECOND, ui::Ui, Mode, State, BUTTON_1, BUTTON_LEFT, BUTTON_RIGHT, BUTTON_UP,
+};
+
+/// Whether the game was built for a kiosk.
+const ENABLED: bool = cfg!(feature = "kiosk");
+
+/// How long the title screen waits for someone to press something before
+/// showing how to play.
+const TITLE_IDLE_FRAMES: u32 = 10 * STEPS_PER_SECOND;
+/// How long the controls are shown for before the demo starts.
+const HOW_TO_PLAY_FRAMES: u32 = 8 * STEPS_PER_SECOND;
+/// How long a game can go without anyone pressing anything before it's
+/// given up on.
+const GAME_IDLE_FRAMES: u32 = 45 * STEPS_PER_SECOND;
+
+/// The buttons held down during the demo, and for how many updates.
+///
+/// It turns to walk down from the start of the first level, through the
+/// teleporter, fights the slime waiting at the other end and looks out at the
+/// sky.
+const DEMO: &[(u8, u16)] = &[
+    (0, 30),
+    (BUTTON_RIGHT, 36),
+    (BUTTON_UP, 45),
+    (BUTTON_LEFT, 35),
+    (BUTTON_UP, 40),
+    (0, 30),
+    (BUTTON_LEFT, 70),
+    (BUTTON_1, 1),
+    (0, 15),
+    (BUTTON_1, 1),
+    (0, 15),
+    (BUTTON_1, 1),
+    (0, 15),
+    (BUTTON_1, 1),
+    (0, 40),
+    (BUTTON_RIGHT, 35),
+    (BUTTON_UP, 30),
+    (0, 90),
+];
+
+/// How far the demo has got, as the step it's on and how many updates have
+/// been spent on it.
+#[derive(Clone, Copy)]
+struct DemoPosition {
+    step: usize,
+    frames: u16,
+}
+
+/// What the kiosk is keeping track of while nobody's playing.
+pub struct Kiosk {
+    /// How many updates it's been since any button was held down.
+    idle_frames: u32,
+    /// How far the demo has got, if it's playing.
+    demo: Option<DemoPosition>,
+}
+
+impl Kiosk {
+    pub const fn new() -> Self {
+        Self {
+            idle_frames: 0,
+            demo: None,
+        }
+    }
+}
+
+impl State {
+    /// Whether a game has been left alone for so long that it should be
+    /// [reset](crate::reset::soft_reset) for the next person.
+    pub fn kiosk_abandoned(&self) -> bool {
+        ENABLED
+            && self.kiosk.demo.is_none()
+            && !matches!(self.mode, Mode::Title | Mode::HowToPlay)
+            && self.kiosk.idle_frames >= GAME_IDLE_FRAMES
+    }
+
+    /// Keep the game moving along while nobody's at the controls, swapping
+    /// the demo's buttons in for the player's while it's playing.
+    pub fn update_kiosk(&mut self, gamepad: &mut u8, pressed: &mut u8) {
+        if !ENABLED {
+            return;
+        }
+
+        self.kiosk.idle_frames = if *gamepad == 0 {
+            self.kiosk.idle_frames.saturating_add(1)
+        } else {
+            0
+        };
+
+        if let Some(position) = self.kiosk.demo {
+            // anyone pressing anything takes over from the demo.
+            if *pressed != 0 {
+                self.end_demo();
+                (*gamepad, *pressed) = (0, 0);
+                return;
+            }
+            if self.in_transition() {
+                return;
+            }
+
+            let Some(&(buttons, frames)) = DEMO.get(position.step) else {
+                self.end_demo();
+                return;
+            };
+
+            // buttons are pressed on the first update of the step they're
+            // held down for.
+            *gamepad = buttons;
+            *pressed = if position.frames == 0 { buttons } else { 0 };
+
+            self.kiosk.demo = Some(if position.frames + 1 < frames {
+                DemoPosition {
+                    frames: position.frames + 1,
+                    ..position
+                }
+            } else {
+                DemoPosition {
+                    step: position.step + 1,
+                    frames: 0,
+                }
+            });
+            return;
+        }
+
+        if matches!(self.mode, Mode::Title) && self.kiosk.idle_frames >= TITLE_IDLE_FRAMES {
+            self.kiosk.idle_frames = 0;
+            self.transition_to(Mode::HowToPlay);
+        }
+    }
+
+    /// Explain the controls, starting the demo once they've been up for a
+    /// while, or going back to the title screen if anything is pressed.
+    pub fn update_how_to_play(&mut self, pressed: u8) {
+        let mut ui = Ui::new(24, 8, pressed);
+        ui.label("HOW TO PLAY");
+        ui.space(4);
+        ui.label("\u{86}\u{87} WALK  \u{84}\u{85} TURN");
+        ui.label("\u{80}  USE OR ATTACK");
+        ui.label("\u{81}  MAP");
+        ui.label("\u{81}+\u{86} RUN");
+        ui.label("\u{81}\u{81}+\u{86} DASH");
+        ui.label("\u{81}+\u{84}\u{85} AIM");
+        ui.label("\u{80}+\u{81}+\u{86} JUMP");
+        ui.label("\u{87}\u{87} CROUCH");
+        ui.label("\u{80}+\u{81} PAUSE");
+        ui.space(4);
+        ui.label("FIND THE EXIT!");
+        ui.space(4);
+        ui.hint("PRESS ANY BUTTON");
+
+        if pressed != 0 {
+            self.mode = Mode::Title;
+        } else if self.kiosk.idle_frames >= HOW_TO_PLAY_FRAMES && !self.in_transition() {
+            self.time_attack = None;
+            self.score = 0;
+            self.load_level(0);
+            self.kiosk.demo = Some(DemoPosition { step: 0, frames: 0 });
+            self.transition_to(Mode::Playing);
+        }
+    }
+
+    /// Stop the demo and go back to the title screen.
+    fn end_demo(&mut self) {
+        self.kiosk.demo = None;
+        self.kiosk.idle_frames = 0;
+        self.transition_to(Mode::Title);
+    }
+}
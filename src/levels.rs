@@ -0,0 +1,201 @@
+//! The levels making up the campaign, played one after another.
+//!
+//! Each level's map is drawn in `assets/maps`, either as a text file or in
+//! the Tiled editor, and the build script turns it into a [`MapAsset`].
+
+use crate::{
+    day_night::DayNight,
+    entities::{Entities, Entity},
+    floors::FloorArea,
+    map::Map,
+    maze,
+    palette::Cycle,
+    sprites::{Graphic, EXIT_DOOR},
+    switches::Tag,
+    timestep::STEPS_PER_SECOND,
+    triggers::Trigger,
+    weather::Weather,
+    WALL_HEIGHT,
+};
+
+/// Two cells linked to each other.
+type Link = ((i32, i32), (i32, i32));
+
+/// A map built from one of the files in `assets/maps`.
+pub struct MapAsset {
+    /// The map's tiles, in the format that [`Map::load`] takes, or just its
+    /// width and height if it's streamed.
+    pub tiles: &'static [u8],
+    /// The map's chunks, in the format that [`Map::stream`] takes, if it's
+    /// too big to fit in memory all at once.
+    pub chunks: &'static [&'static [u8]],
+    /// Where the player starts, and which way they're facing.
+    pub spawn: (f32, f32, f32),
+    /// The cell the player needs to reach to finish the level, if it isn't
+    /// finished by using an [`EXIT_SWITCH`](crate::map::EXIT_SWITCH) instead.
+    pub exit: Option<(i32, i32)>,
+    /// Where the view on the map's [`MONITOR`](crate::map::MONITOR)s is seen
+    /// from, and which way it faces, if the map has any.
+    pub camera: Option<(f32, f32, f32)>,
+    /// The cells holding secret walls.
+    pub secrets: &'static [(i32, i32)],
+    /// Cells with floors that kill the player.
+    pub hazards: &'static [(i32, i32)],
+    /// Pairs of cells with teleporter pads, each sending the player to the other.
+    pub teleporters: &'static [Link],
+    /// Switches, and the cells they work, each with the tag linking them.
+    pub tags: &'static [Tag],
+    /// Cells that set off scripted events when the player walks into them.
+    pub triggers: &'static [Trigger],
+    /// Patterns painted across the floor.
+    pub floors: &'static [FloorArea],
+    /// Everything placed around the map when the level starts.
+    pub entities: &'static [Entity],
+}
+
+impl MapAsset {
+    /// Load the map into memory, or for a streamed map, the part of it
+    /// around where the player starts.
+    pub fn load(&self) -> Map {
+        if self.chunks.is_empty() {
+            Map::load(self.tiles)
+        } else {
+            let (x, y, _) = self.spawn;
+            Map::stream(
+                self.tiles[0] as usize,
+                self.tiles[1] as usize,
+                self.chunks,
+                x,
+                y,
+            )
+        }
+    }
+}
+
+// angles in the maps are written out in degrees, and some of them come out
+// close enough to a constant like PI in radians to set off clippy.
+#[allow(clippy::approx_constant)]
+mod assets {
+    use super::MapAsset;
+    use crate::map::*;
+
+    include!(concat!(env!("OUT_DIR"), "/maps.rs"));
+}
+
+/// Everything needed to set up a level.
+pub struct Level {
+    /// The map, along with where the player starts and the cells that do
+    /// something special.
+    pub map: MapAsset,
+    /// The colors the level is drawn in, from lightest to darkest.
+    pub palette: [u32; 4],
+    /// Colors that shift around as the level goes on.
+    pub cycles: &'static [Cycle],
+    /// What falls from the sky over the open parts of the level.
+    pub weather: Weather,
+    /// How the light changes as the level goes on, if it does at all.
+    pub day_night: Option<DayNight>,
+    /// How many updates a good player takes to finish the level.
+    pub par_frames: u32,
+    /// The height, in pixels, that a wall appears as when it is one unit
+    /// away. Taller walls close the level in around the player, and shorter
+    /// ones open it up.
+    pub wall_height: f32,
+}
+
+impl Level {
+    /// Create everything placed around the level when it starts.
+    pub const fn spawn_entities(&self) -> Entities {
+        Entities::new(self.map.entities)
+    }
+}
+
+pub const LEVELS: [Level; 4] = [
+    Level {
+        map: assets::LEVEL1,
+        palette: [0xe0f8cf, 0x86c06c, 0x306850, 0x071821],
+        cycles: &[],
+        weather: Weather::Rain { thunder: true },
+        day_night: Some(DayNight {
+            palette: [0x5878a0, 0x34507a, 0x1c2c50, 0x060a1c],
+            period: 4 * 60 * STEPS_PER_SECOND,
+            // starts in the late afternoon, with night falling fast.
+            start: 30 * STEPS_PER_SECOND,
+        }),
+        par_frames: 30 * STEPS_PER_SECOND,
+        wall_height: WALL_HEIGHT,
+    },
+    Level {
+        map: assets::LEVEL2,
+        palette: [0xe0f0f8, 0x88b0d0, 0x385880, 0x0a1428],
+        // the walls shimmer, as if lit from water somewhere nearby.
+        cycles: &[Cycle {
+            entry: 1,
+            color: 0x78c8c0,
+            period: 180,
+        }],
+        weather: Weather::Clear,
+        day_night: None,
+        par_frames: 60 * STEPS_PER_SECOND,
+        // the tunnels are low and narrow, so the walls loom over the player.
+        wall_height: 120.0,
+    },
+    Level {
+        map: assets::LEVEL3,
+        palette: [0xf8e8c8, 0xd09858, 0x804830, 0x201010],
+        // the sky slowly glows with the setting sun.
+        cycles: &[Cycle {
+            entry: 0,
+            color: 0xf8c890,
+            period: 300,
+        }],
+        weather: Weather::Snow,
+        day_night: None,
+        par_frames: 45 * STEPS_PER_SECOND,
+        wall_height: WALL_HEIGHT,
+    },
+    Level {
+        // too big to fit in memory, so it's streamed in around the player.
+        map: assets::LEVEL4,
+        palette: [0xd8f0f8, 0x88b8a0, 0x486850, 0x142018],
+        cycles: &[],
+        weather: Weather::Clear,
+        day_night: None,
+        par_frames: 60 * STEPS_PER_SECOND,
+        wall_height: WALL_HEIGHT,
+    },
+];
+
+/// The level used for random dungeons, which have a map made by [`maze`]
+/// instead of one from `assets/maps`.
+pub const DUNGEON: Level = Level {
+    map: MapAsset {
+        tiles: &[],
+        chunks: &[],
+        spawn: (1.5, 1.5, 0.0),
+        exit: Some((maze::SIZE - 2, maze::SIZE - 2)),
+        camera: None,
+        secrets: &[],
+        hazards: &[],
+        teleporters: &[],
+        tags: &[],
+        triggers: &[],
+        floors: &[],
+        entities: &[Entity::new(
+            maze::SIZE as f32 - 1.5,
+            maze::SIZE as f32 - 1.5,
+            Graphic::Sprite(&EXIT_DOOR),
+        )],
+    },
+    palette: [0xe8e8e8, 0xa0a0a0, 0x505050, 0x101010],
+    // torchlight flickering on the walls.
+    cycles: &[Cycle {
+        entry: 1,
+        color: 0xb8a888,
+        period: 40,
+    }],
+    weather: Weather::Clear,
+    day_night: None,
+    par_frames: 90 * STEPS_PER_SECOND,
+    wall_height: WALL_HEIGHT,
+};
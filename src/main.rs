@@ -9,292 +9,1375 @@ use core::{
     panic::PanicInfo,
 };
 
+mod angle;
+mod audio;
+mod auto_walk;
+mod automap;
+mod barks;
+mod channels;
+mod chase;
+mod codex;
+mod combat;
+mod controls;
+mod crouch;
+mod damage_numbers;
+mod dash;
+mod day_night;
+mod death;
+mod decals;
+mod difficulty;
+mod disk;
+mod double_tap;
+mod entities;
+mod floors;
+mod footsteps;
+mod framebuffer;
+mod gallery;
+mod game_over;
+mod health;
+mod hooks;
+mod input_display;
+mod intermission;
+mod items;
+mod jump;
+mod keys;
+mod kill_cam;
+mod kiosk;
+mod levels;
+mod map;
+mod math;
+mod maze;
+mod messages;
+mod monitors;
+mod mouse;
+mod mutators;
+mod noclip;
+mod options;
+mod palette;
+mod partial_walls;
+mod pause;
+mod pools;
+mod projectiles;
+mod render;
+mod replay;
+mod reset;
+mod rhythm;
+mod rng;
+mod save;
+mod score;
+mod scoreboard;
+mod secrets;
+mod sfx;
+mod shuffle;
+mod skylight;
+mod splits;
+mod sprint;
+mod sprites;
+mod switches;
+mod teleporters;
+mod text;
+mod texture;
+mod time;
+mod time_attack;
+mod timestep;
+mod title;
+mod toasts;
+mod transition;
+mod triggers;
+mod ui;
+mod versus;
+mod water;
+mod weather;
+
+use auto_walk::AutoWalk;
+use controls::{Controls, BUTTON_PAUSE};
+use crouch::{Crouch, CROUCHING_HEAD, CROUCH_SPEED, STANDING_HEAD};
+use damage_numbers::DamageNumbers;
+use dash::Dash;
+use decals::Decals;
+use difficulty::Difficulty;
+use entities::{Entities, BODY_RADIUS};
+use footsteps::Footsteps;
+use framebuffer::SCREEN_SIZE;
+use gallery::Gallery;
+use health::MAX_HEALTH;
+use items::STARTING_AMMO;
+use jump::Jump;
+use kiosk::Kiosk;
+use levels::{Level, DUNGEON, LEVELS};
+use map::{
+    floor_attributes, is_diagonal, is_solid, is_wall, Map, Stature, WorldMap, DIAGONAL_NE,
+    DIAGONAL_SW, EXIT_SWITCH, FLOOR, MAX_STREAMED_CELLS, SKY,
+};
+use math::{atan2f, atanf, ceilf, cosf, distance, fabsf, floorf, sinf, tanf};
+use mouse::MouseInput;
+use mutators::Mutators;
+use noclip::Noclip;
+use pause::Pause;
+use pools::PoolReport;
+use projectiles::{Projectiles, FIREBALL};
+use replay::{Replay, Snapshot};
+use rhythm::Rhythm;
+use secrets::PushWall;
+use sprint::{Stamina, SPRINT_SPEED};
+use sprites::{Graphic, Projected, SpriteList, TEXELS_PER_UNIT};
+use teleporters::Teleporter;
+use texture::Textures;
+use time::TimeScale;
+use time_attack::TimeAttack;
+use timestep::DT;
+use transition::Transition;
+use versus::Versus;
+use weather::Precipitation;
+
 const DRAW_COLORS: *mut u16 = 0x14 as *mut u16;
 const GAMEPAD1: *const u8 = 0x16 as *const u8;
 
+const BUTTON_1: u8 = 1; // 00000001
+const BUTTON_2: u8 = 2; // 00000010
 const BUTTON_LEFT: u8 = 16; // 00010000
 const BUTTON_RIGHT: u8 = 32; // 00100000
 const BUTTON_UP: u8 = 64; // 01000000
 const BUTTON_DOWN: u8 = 128; // 10000000
 
-/// How far the player moves per update.
-const STEP_SIZE: f32 = 0.045;
+extern "C" {
+    fn blit(sprite: *const u8, x: i32, y: i32, width: u32, height: u32, flags: u32);
+    #[link_name = "blitSub"]
+    fn blit_sub(
+        sprite: *const u8,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        src_x: u32,
+        src_y: u32,
+        stride: u32,
+        flags: u32,
+    );
+    fn rect(x: i32, y: i32, width: u32, height: u32);
+    fn tone(frequency: u32, duration: u32, volume: u32, flags: u32);
+}
+
+/// How fast the player walks, in map units per second.
+const WALK_SPEED: f32 = 2.7;
+/// How fast the player turns, in radians per second.
+const TURN_SPEED: f32 = 2.7;
+/// How fast the player turns while aiming carefully, in radians per second.
+const FINE_TURN_SPEED: f32 = 0.9;
+/// How quickly the player's turning gets up to speed, in radians per second
+/// per second, so that tapping a direction only nudges them around.
+const TURN_ACCELERATION: f32 = 40.5;
+/// How quickly the player gets up to speed while walking, in map units per
+/// second per second.
+const ACCELERATION: f32 = 43.2;
+/// How quickly the player slides to a stop after they stop walking, in map
+/// units per second per second.
+const DECELERATION: f32 = 21.6;
+/// The player stops dead once they're slower than this, in map units per
+/// second.
+const MIN_SPEED: f32 = 0.06;
 
-const FIVE_PI_SQUARED: f32 = 5.0 * (PI * PI);
+/// How far in front of the player they can reach to use something, in map
+/// units.
+const USE_RANGE: f32 = 1.5;
 
 /// The player's field of view.
 const FOV: f32 = PI / 2.7;
 /// Half the player's field of view.
 const HALF_FOV: f32 = FOV * 0.5;
-/// The angle between each ray used in raycasting.
-const ANGLE_STEP: f32 = FOV / 160.0;
-/// The height, in pixels, that a wall will appear as when it is one unit away.
+/// The height, in pixels, that a wall will appear as when it is one unit away,
+/// unless the level says otherwise.
 const WALL_HEIGHT: f32 = 100.0;
+/// Walls further away than this, in map units, are lost in the fog in daylight.
+const FOG_DISTANCE: f32 = 10.0;
+/// Entities closer to the camera plane than this, in map units, aren't drawn.
+const NEAR_DISTANCE: f32 = 0.1;
+/// Pickups are drawn lying flat on the floor while the camera looks down on
+/// them at least this steeply, in map units down for every map unit away.
+/// Further off, or with the camera low down, they stand up like everything
+/// else so they're still easy to spot.
+const SPLAT_SLOPE: f32 = 0.3;
 
-extern "C" {
-    fn vline(x: i32, y: i32, len: u32);
+/// How many rays are cast across the player's field of view each frame.
+#[derive(Clone, Copy)]
+enum Resolution {
+    /// One ray per screen column.
+    Full,
+    /// One ray per two screen columns, with each column drawn two pixels wide.
+    Half,
 }
 
-#[panic_handler]
-fn phandler(_: &PanicInfo<'_>) -> ! {
-    wasm32::unreachable()
-}
-
-#[no_mangle]
-unsafe fn update() {
-    STATE.update(
-        *GAMEPAD1 & BUTTON_UP != 0,
-        *GAMEPAD1 & BUTTON_DOWN != 0,
-        *GAMEPAD1 & BUTTON_LEFT != 0,
-        *GAMEPAD1 & BUTTON_RIGHT != 0,
-    );
-
-    // go through each column on screen and draw walls in the center.
-    for (x, wall) in STATE.get_view().iter().enumerate() {
-        let (height, shadow) = wall;
+impl Resolution {
+    /// The resolution used when the game starts.
+    const DEFAULT: Self = if cfg!(feature = "half-res") {
+        Self::Half
+    } else {
+        Self::Full
+    };
 
-        if *shadow {
-            *DRAW_COLORS = 0x2;
-        } else {
-            *DRAW_COLORS = 0x3;
+    /// The number of rays cast each frame.
+    const fn rays(self) -> usize {
+        match self {
+            Self::Full => SCREEN_SIZE,
+            Self::Half => SCREEN_SIZE / 2,
         }
+    }
 
-        vline(x as i32, 80 - (height / 2), *height as u32);
+    /// How many pixels wide each ray's column is drawn.
+    const fn column_width(self) -> usize {
+        SCREEN_SIZE / self.rays()
     }
-}
 
-const MAP: [u16; 8] = [
-    0b1111111111111111,
-    0b1000001010000101,
-    0b1011100000110101,
-    0b1000111010010001,
-    0b1010001011110111,
-    0b1011101001100001,
-    0b1000100000001101,
-    0b1111111111111111,
-];
-
-/// Check if the map contains a wall at a point.
-fn point_in_wall(x: f32, y: f32) -> bool {
-    match MAP.get(y as usize) {
-        Some(line) => (line & (0b1 << x as usize)) != 0,
-        None => true,
+    /// The angle between each ray used in raycasting.
+    fn angle_step(self) -> f32 {
+        FOV / self.rays() as f32
     }
 }
 
-fn sinf(mut x: f32) -> f32 {
-    let y = x / TAU;
-    let z = y - floorf(y);
-    x = z * TAU;
-
-    let sinf_imp = |x: f32| -> f32 {
-        // these magic numbers were discovered 1400 years ago!
-        (16.0 * x * (PI - x)) / (FIVE_PI_SQUARED - (4.0 * x * (PI - x)))
-    };
+/// Which kind of grid line a ray hit a wall on.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// A line running along the x axis.
+    Horizontal,
+    /// A line running along the y axis.
+    Vertical,
+    /// The slanted face of a diagonal wall.
+    Diagonal,
+}
 
-    if x > PI {
-        -sinf_imp(x - PI)
-    } else {
-        sinf_imp(x)
-    }
+/// Everything known about where a ray hit a wall.
+#[derive(Clone, Copy)]
+struct RayHit {
+    /// The distance from the camera plane to the wall, in map units.
+    distance: f32,
+    /// The height, in pixels, that the wall appears as on screen.
+    height: i32,
+    /// Which way the face of the wall that was hit runs.
+    side: Side,
+    /// The kind of wall that was hit.
+    tile_id: u8,
+    /// How far along the face of the wall the ray hit, from 0.0 to 1.0.
+    tex_u: f32,
+    /// The map cell containing the wall.
+    cell_x: i32,
+    cell_y: i32,
+    /// Whether the face of the wall that was hit is open to the sky.
+    outdoor: bool,
 }
 
-fn cosf(x: f32) -> f32 {
-    sinf(x + FRAC_PI_2)
+impl RayHit {
+    const NONE: Self = Self {
+        distance: 0.0,
+        height: 0,
+        side: Side::Horizontal,
+        tile_id: 0,
+        tex_u: 0.0,
+        cell_x: 0,
+        cell_y: 0,
+        outdoor: false,
+    };
 }
 
-fn tanf(x: f32) -> f32 {
-    sinf(x) / cosf(x)
+/// Which screen the game is showing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// The title screen, shown when the game starts.
+    Title,
+    /// The controls, shown in kiosk mode when nobody's playing.
+    HowToPlay,
+    /// Changing how the game looks.
+    Options,
+    /// Changing which buttons do what while playing.
+    Controls,
+    /// Switching mutators on and off, before starting a run.
+    Mutators,
+    /// Reading about the enemies and items found so far, on the given page
+    /// of the codex.
+    Codex { page: usize },
+    /// Walking around the level.
+    Playing,
+    /// Replaying the player's last moments from whatever killed them, which
+    /// is standing in the cell `killer`. Shows the replay's `frame`th update.
+    KillCam { frame: u16, killer: (i32, i32) },
+    /// Sinking to the floor after dying, for some number of updates.
+    Dying { frames: u16 },
+    /// Stopped in the middle of a level, with the pause menu up.
+    Paused,
+    /// The stats screen shown after dying, to try the level again from.
+    GameOver,
+    /// The stats screen shown after reaching the exit.
+    Intermission,
+    /// The shooting gallery bonus stage.
+    Gallery,
+    /// How a time attack run went, shown once it's over.
+    Ranking,
+    /// Split-screen deathmatch between two players.
+    Versus,
 }
 
-fn sqrtf(x: f32) -> f32 {
-    unsafe { core::intrinsics::sqrtf32(x) }
+#[panic_handler]
+fn phandler(_: &PanicInfo<'_>) -> ! {
+    wasm32::unreachable()
 }
 
-fn floorf(x: f32) -> f32 {
-    unsafe { core::intrinsics::floorf32(x) }
+/// The gamepad's state on the previous update, to tell when buttons are first pressed.
+static mut PREVIOUS_GAMEPAD: u8 = 0;
+
+#[no_mangle]
+unsafe fn start() {
+    STATE.controls.load();
+    STATE.load_best_frames();
+    STATE.load_options();
 }
 
-fn ceilf(x: f32) -> f32 {
-    unsafe { core::intrinsics::ceilf32(x) }
+#[no_mangle]
+unsafe fn update() {
+    let mut gamepad = *GAMEPAD1;
+    let mut pressed = gamepad & !PREVIOUS_GAMEPAD;
+    PREVIOUS_GAMEPAD = gamepad;
+    if reset::update(gamepad) {
+        pressed = 0;
+    }
+    let mut mouse = mouse::read();
+    if !STATE.mouse_look {
+        mouse = MouseInput {
+            turn: 0.0,
+            clicked: false,
+        };
+    }
+
+    // the player has to wait for the screen to finish changing.
+    if STATE.in_transition() {
+        (gamepad, pressed) = (0, 0);
+        mouse = MouseInput {
+            turn: 0.0,
+            clicked: false,
+        };
+    }
+    // the kiosk's demos are recorded with the normal controls, so the
+    // player's own bindings are applied first.
+    if matches!(STATE.mode, Mode::Playing | Mode::Gallery) {
+        gamepad = STATE.controls.map(gamepad);
+        pressed = STATE.controls.map(pressed);
+    }
+    if STATE.kiosk_abandoned() {
+        reset::soft_reset();
+    }
+    STATE.update_kiosk(&mut gamepad, &mut pressed);
+
+    match STATE.mode {
+        Mode::Playing => {
+            if STATE.auto_walk.update(pressed) {
+                gamepad |= BUTTON_UP;
+            }
+
+            // pressing X and Z together jumps while walking and pauses
+            // otherwise, instead of doing what either does on its own.
+            let both = BUTTON_1 | BUTTON_2;
+            let chord = gamepad & both == both && pressed & both != 0;
+            let walking = gamepad & (BUTTON_UP | BUTTON_DOWN) != 0;
+
+            // while a message is up, the X button belongs to the message box.
+            let activate = (pressed & BUTTON_1 != 0 && !chord) || mouse.clicked;
+            let activate = !messages::update(activate) && activate;
+
+            // turning has to be mirrored along with the view to still feel right.
+            let (mut left, mut right) = (gamepad & BUTTON_LEFT != 0, gamepad & BUTTON_RIGHT != 0);
+            let mut turn = mouse.turn;
+            if STATE.mutators.mirror {
+                (left, right) = (right, left);
+                turn = -turn;
+            }
+
+            if STATE.time.tick() {
+                STATE.update(
+                    gamepad & BUTTON_UP != 0,
+                    gamepad & BUTTON_DOWN != 0,
+                    left,
+                    right,
+                    turn,
+                    gamepad & BUTTON_2 != 0,
+                    activate,
+                );
+                STATE.charge_fireball(gamepad & BUTTON_1 != 0 && !chord);
+                STATE.damage_numbers.update();
+                STATE.update_weather();
+                STATE.update_pool_report();
+            }
+
+            STATE.noclip.enter(pressed);
+            let dash_tap = STATE.dash.taps.update(pressed & BUTTON_2 != 0);
+            let crouch_tap = STATE.crouch.taps.update(pressed & BUTTON_DOWN != 0);
+            if chord && walking {
+                STATE.jump();
+            } else if crouch_tap {
+                STATE.toggle_crouch();
+            } else if dash_tap && walking {
+                STATE.dash(gamepad & BUTTON_UP == 0);
+            }
+
+            // holding Z while walking sprints and while turning aims, so it
+            // only brings up the map when pressed on its own.
+            let steering = BUTTON_UP | BUTTON_DOWN | BUTTON_LEFT | BUTTON_RIGHT;
+            if pressed & BUTTON_2 != 0 && gamepad & steering == 0 && !chord {
+                STATE.show_automap = !STATE.show_automap;
+            }
+            // X and Z held down while walking jump rather than pause, when
+            // they're what pauses.
+            if walking && gamepad & both == both {
+                gamepad &= !BUTTON_PAUSE;
+            }
+            STATE.update_pause_button(gamepad);
+
+            STATE.draw_hooks(&hooks::PLAYING, gamepad);
+
+            // hit flashes only last for the frame they were drawn on.
+            for entity in STATE.entities.iter_mut() {
+                entity.flash = false;
+            }
+        }
+        Mode::KillCam { .. } => {
+            STATE.draw_kill_cam();
+            STATE.update_kill_cam(pressed & BUTTON_1 != 0);
+        }
+        Mode::Dying { .. } => {
+            STATE.update_dying();
+            STATE.cast_view();
+            STATE.draw_view();
+        }
+        Mode::Paused => STATE.update_paused(pressed),
+        Mode::Title => STATE.update_title(pressed),
+        Mode::HowToPlay => STATE.update_how_to_play(pressed),
+        Mode::Options => STATE.update_options(pressed),
+        Mode::Controls => STATE.update_controls(pressed),
+        Mode::Mutators => STATE.update_mutators(pressed),
+        Mode::Codex { .. } => STATE.update_codex(pressed),
+        Mode::Intermission => STATE.update_intermission(pressed),
+        Mode::GameOver => STATE.update_game_over(pressed),
+        Mode::Gallery => {
+            STATE.update_gallery(gamepad, pressed);
+            STATE.draw_input_display(gamepad);
+        }
+        Mode::Ranking => STATE.update_ranking(pressed),
+        Mode::Versus => STATE.update_versus(gamepad, pressed),
+    }
+
+    // toasts wait while the game is paused, rather than leaving a trail
+    // across the frozen frame as they slide.
+    if STATE.mode != Mode::Paused {
+        toasts::update();
+    }
+    channels::update();
+
+    STATE.update_transition();
+    STATE.preserve_paused_frame();
+    let level = STATE.level();
+    let mut colors = if STATE.reduce_motion {
+        level.palette
+    } else {
+        palette::cycle(&level.palette, level.cycles, STATE.stats.frames)
+    };
+    if let Some(day_night) = &level.day_night {
+        colors = palette::mix(&colors, &day_night.palette, STATE.darkness());
+    }
+    colors = palette::mix(&colors, &water::TINT, STATE.water_tint());
+    let fade = STATE
+        .fade()
+        .max(STATE.teleport_flash())
+        .max(STATE.lightning());
+    palette::set(&colors, STATE.redness(), fade);
 }
 
-fn fabsf(x: f32) -> f32 {
-    unsafe { core::intrinsics::fabsf32(x) }
+/// Tallies kept over the course of a level, for the end-of-level stats screen.
+struct Stats {
+    /// How many updates the level has been played for.
+    frames: u32,
+    kills: u8,
+    /// How many enemies there were to kill.
+    kills_total: u8,
+    secrets_found: u8,
+    treasure: u8,
+    /// How many points were scored on the level.
+    points: u32,
 }
 
-/// Get the distance from (0.0, 0.0) to (x, y).
-fn distance(a: f32, b: f32) -> f32 {
-    sqrtf((a * a) + (b * b))
+impl Stats {
+    const fn new(kills_total: u8) -> Self {
+        Self {
+            frames: 0,
+            kills: 0,
+            kills_total,
+            secrets_found: 0,
+            treasure: 0,
+            points: 0,
+        }
+    }
 }
 
 struct State {
+    mode: Mode,
+    /// The change of screen in progress, if there is one.
+    transition: Option<Transition>,
+    /// Which item is focused on the title screen's menu.
+    menu_focus: usize,
+    /// Which of [`LEVELS`] is being played.
+    level: usize,
+    /// The seed of the random dungeon being played instead, if there is one.
+    dungeon: Option<u32>,
+    /// How many updates the title screen has been up for, which is as good
+    /// a seed for random dungeons as any.
+    title_frames: u32,
     player_x: f32,
     player_y: f32,
     player_angle: f32,
+    /// How fast the player is moving east and south, in map units per
+    /// second.
+    player_velocity: (f32, f32),
+    /// How fast the player is turning anticlockwise, in radians per second.
+    player_turn_velocity: f32,
+    /// How much longer the player can keep sprinting.
+    stamina: Stamina,
+    crouch: Crouch,
+    jump: Jump,
+    dash: Dash,
+    auto_walk: AutoWalk,
+    footsteps: Footsteps,
+    health: u8,
+    ammo: u8,
+    /// How many points the player has scored so far this run.
+    score: u32,
+    /// How many more updates the player flinches for after being hurt.
+    hurt_frames: u8,
+    /// How many more updates the player can't be hit again for.
+    invulnerable_frames: u8,
+    /// Whether the player was standing in water on the last update.
+    wading: bool,
+    /// The map, which secret walls move around in.
+    map: Map,
+    /// One bit for each [`Key`](keys::Key) the player is holding.
+    keys: u8,
+    /// One bit for each of the level's triggers that has gone off.
+    triggered: u32,
+    /// The secret wall that is currently sliding, if there is one.
+    push_wall: Option<PushWall>,
+    teleporter: Teleporter,
+    /// The rain or snow falling on screen.
+    precipitation: Precipitation,
+    /// One bit for each cell on the map, set once a ray has hit a wall there.
+    seen: [u8; MAX_STREAMED_CELLS / 8],
+    /// Whether the automap is drawn over the view.
+    show_automap: bool,
+    /// Whether the player can walk through walls, for debugging levels.
+    noclip: Noclip,
+    stats: Stats,
+    /// The fewest updates anyone has taken to finish each level, first
+    /// without any mutators and then with some.
+    best_frames: [[Option<u32>; LEVELS.len()]; 2],
+    resolution: Resolution,
+    entities: Entities,
+    damage_numbers: DamageNumbers,
+    /// Bullet holes in the walls from shots that missed.
+    decals: Decals,
+    projectiles: Projectiles,
+    /// How many updates X has been held down for, charging up a fireball.
+    charge: u8,
+    /// One bit for each page of the codex the player has unlocked.
+    codex: u8,
+    /// Whether hurting an enemy shows how much damage was done.
+    show_damage_numbers: bool,
+    /// Whether the buttons being held down are shown in the corner.
+    show_inputs: bool,
+    /// Whether the speedrun timer is shown in the corner.
+    show_timer: bool,
+    /// How full each of the fixed-size pools has got, if it's being reported.
+    pool_report: PoolReport,
+    /// Whether the mouse turns the player and clicking attacks.
+    mouse_look: bool,
+    /// Which buttons do what while playing.
+    controls: Controls,
+    /// Whether to hold back on effects that flash or cycle colors.
+    reduce_motion: bool,
+    mutators: Mutators,
+    difficulty: Difficulty,
+    /// The mutators the player is allowed to switch on.
+    unlocked: Mutators,
+    /// What items and enemies are shuffled with when [`Mutators::shuffle`] is
+    /// on, picked fresh for every run.
+    shuffle_seed: u32,
+    /// Hit-stop and slow motion, which skip updates of the game.
+    time: TimeScale,
+    /// Where the player has been lately, for the kill-cam.
+    replay: Replay,
+    gallery: Gallery,
+    /// The split-screen match being played, if there is one.
+    versus: Versus,
+    /// Textures drawn at runtime, for walls and sprites that change.
+    textures: Textures,
+    /// The time attack run being played, if there is one.
+    time_attack: Option<TimeAttack>,
+    /// The fewest updates anyone has taken to finish a time attack run.
+    best_time_attack: Option<u32>,
+    kiosk: Kiosk,
+    pause: Pause,
+    /// The beat the rhythm mutator keeps time to.
+    rhythm: Rhythm,
+    /// What each ray hit on the last call to [`State::cast_view`].
+    view: [RayHit; SCREEN_SIZE],
 }
 
-static mut STATE: State = State {
-    player_x: 1.5,
-    player_y: 1.5,
-    player_angle: 0.0,
-};
+static mut STATE: State = State::new();
 
 impl State {
-    /// Move the character.
-    pub fn update(&mut self, up: bool, down: bool, left: bool, right: bool) {
-        let prev_pos = (self.player_x, self.player_y);
+    /// Everything as it is when the cartridge starts.
+    const fn new() -> Self {
+        Self {
+            mode: Mode::Title,
+            transition: None,
+            menu_focus: 0,
+            level: 0,
+            dungeon: None,
+            title_frames: 0,
+            player_x: LEVELS[0].map.spawn.0,
+            player_y: LEVELS[0].map.spawn.1,
+            player_angle: LEVELS[0].map.spawn.2,
+            player_velocity: (0.0, 0.0),
+            player_turn_velocity: 0.0,
+            stamina: Stamina::new(),
+            crouch: Crouch::new(),
+            jump: Jump::new(),
+            dash: Dash::new(),
+            auto_walk: AutoWalk::new(),
+            footsteps: Footsteps::new(),
+            map: Map::load(LEVELS[0].map.tiles),
+            keys: 0,
+            triggered: 0,
+            health: MAX_HEALTH,
+            ammo: STARTING_AMMO,
+            score: 0,
+            hurt_frames: 0,
+            invulnerable_frames: 0,
+            wading: false,
+            push_wall: None,
+            teleporter: Teleporter::new(),
+            precipitation: Precipitation::new(),
+            seen: [0; MAX_STREAMED_CELLS / 8],
+            show_automap: false,
+            noclip: Noclip::new(),
+            stats: Stats::new(LEVELS[0].spawn_entities().enemy_count()),
+            best_frames: [[None; LEVELS.len()]; 2],
+            resolution: Resolution::DEFAULT,
+            entities: LEVELS[0].spawn_entities(),
+            damage_numbers: DamageNumbers::new(),
+            decals: Decals::new(),
+            projectiles: Projectiles::new(),
+            charge: 0,
+            codex: 0,
+            show_damage_numbers: false,
+            show_inputs: false,
+            show_timer: false,
+            pool_report: PoolReport::new(),
+            mouse_look: false,
+            controls: Controls::new(),
+            reduce_motion: false,
+            mutators: Mutators::NONE,
+            difficulty: Difficulty::Normal,
+            unlocked: Mutators::UNLOCKED_AT_START,
+            shuffle_seed: 0,
+            time: TimeScale::new(),
+            replay: Replay::new(),
+            gallery: Gallery::new(),
+            versus: Versus::new(),
+            textures: Textures::new(),
+            time_attack: None,
+            best_time_attack: None,
+            kiosk: Kiosk::new(),
+            pause: Pause::new(),
+            rhythm: Rhythm::new(),
+            view: [RayHit::NONE; SCREEN_SIZE],
+        }
+    }
 
-        if up {
-            self.player_x += cosf(self.player_angle) * STEP_SIZE;
-            self.player_y += -sinf(self.player_angle) * STEP_SIZE;
+    /// Move the character, and push on whatever is in front of them if `activate` is set.
+    ///
+    /// As well as turning with `left` and `right`, they turn `turn` radians
+    /// anticlockwise, for turning with the mouse. They sprint while `sprint`
+    /// is held, for as long as their stamina lasts.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        &mut self,
+        up: bool,
+        down: bool,
+        left: bool,
+        right: bool,
+        turn: f32,
+        sprint: bool,
+        activate: bool,
+    ) {
+        self.update_rhythm();
+
+        // holding Z while walking sprints, and while standing still aims.
+        let sprinting = self.stamina.sprint(sprint && up != down);
+        let fine_aim = sprint && up == down;
+        self.steer(up, down, left, right, turn, sprinting, fine_aim);
+        self.update_auto_walk();
+
+        // with nothing to push on, the player attacks instead.
+        if activate && !self.activate() {
+            self.fire();
         }
 
-        if down {
-            self.player_x -= cosf(self.player_angle) * STEP_SIZE;
-            self.player_y -= -sinf(self.player_angle) * STEP_SIZE;
+        self.crouch.update();
+        self.update_jump();
+        self.update_water();
+        self.update_footsteps();
+        self.update_push_wall();
+        self.discover_codex_entries();
+        self.update_chase();
+        self.update_projectiles();
+        self.update_enemy_sounds();
+        self.pick_up_items();
+        self.update_teleporter();
+        self.update_triggers();
+        self.update_monitors();
+        self.map.stream_around(self.player_x, self.player_y);
+        self.stats.frames += 1;
+        self.replay.record(Snapshot {
+            x: self.player_x,
+            y: self.player_y,
+        });
+
+        let cell = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        if Some(cell) == self.level().map.exit {
+            self.finish_level();
+        } else if self.on_hazard() {
+            self.start_kill_cam(cell);
+        } else {
+            self.update_floor_damage();
         }
+    }
+
+    /// Speed up, slow down and turn the player, then move them along.
+    ///
+    /// As well as turning with `left` and `right`, they turn `turn` radians
+    /// anticlockwise, for turning with the mouse. Sprinting only makes them
+    /// walk faster, not turn faster, and aiming with `fine_aim` turns them
+    /// slower.
+    #[allow(clippy::too_many_arguments)]
+    fn steer(
+        &mut self,
+        up: bool,
+        down: bool,
+        left: bool,
+        right: bool,
+        turn: f32,
+        sprinting: bool,
+        fine_aim: bool,
+    ) {
+        let (forward_x, forward_y) = (cosf(self.player_angle), -sinf(self.player_angle));
+        let (mut dx, mut dy) = self.player_velocity;
+        let top_speed = if sprinting {
+            WALK_SPEED * SPRINT_SPEED
+        } else {
+            WALK_SPEED
+        } * self.speed_multiplier();
+
+        // speed up while walking, or slide to a stop after letting go.
+        if up != down {
+            let push = if up {
+                ACCELERATION * DT
+            } else {
+                -ACCELERATION * DT
+            };
+            (dx, dy) = (dx + forward_x * push, dy + forward_y * push);
 
-        if right {
-            self.player_angle -= STEP_SIZE;
+            let speed = distance(dx, dy);
+            if speed > top_speed {
+                (dx, dy) = (dx / speed * top_speed, dy / speed * top_speed);
+            }
+        } else {
+            let speed = distance(dx, dy);
+            let slower = speed - DECELERATION * DT;
+            if slower < MIN_SPEED {
+                (dx, dy) = (0.0, 0.0);
+            } else {
+                (dx, dy) = (dx / speed * slower, dy / speed * slower);
+            }
         }
 
-        if left {
-            self.player_angle += STEP_SIZE;
+        // turning gets up to speed over the first few steps of holding a
+        // direction, and stops as soon as it's let go or reversed.
+        let top_turn_speed = if fine_aim {
+            FINE_TURN_SPEED
+        } else {
+            TURN_SPEED
+        };
+        let turning = (left as i8 - right as i8) as f32;
+        if turning == 0.0 || self.player_turn_velocity * turning < 0.0 {
+            self.player_turn_velocity = 0.0;
         }
+        self.player_turn_velocity = (self.player_turn_velocity + turning * TURN_ACCELERATION * DT)
+            .clamp(-top_turn_speed, top_turn_speed);
+
+        self.player_angle =
+            angle::normalize(self.player_angle + self.player_turn_velocity * DT + turn);
 
-        // if moving us on this frame put us into a wall just revert it
-        if point_in_wall(self.player_x, self.player_y) {
-            (self.player_x, self.player_y) = prev_pos;
+        // a dash carries the player along whichever way they're walking.
+        if let Some(velocity) = self.dash.update() {
+            (dx, dy) = velocity;
         }
+
+        self.player_velocity = (dx, dy);
+        self.move_player();
     }
 
-    /// Returns the nearest wall the ray intersects with on a **horizontal** grid line.
-    fn horizontal_intersection(&self, angle: f32) -> f32 {
-        // Figure out if the angle is "facing up" on the map.
-        let up = fabsf(floorf(angle / PI) % 2.0) != 0.0;
+    /// Move the player along by their velocity, sliding along whatever walls
+    /// they run into. Whatever they run into stops them in that direction,
+    /// unless they're in noclip, and partial walls only stop them if they
+    /// don't fit under or over them.
+    fn move_player(&mut self) {
+        let (dx, dy) = (self.player_velocity.0 * DT, self.player_velocity.1 * DT);
+
+        // even in noclip, the player can't leave the map.
+        if self.noclip.enabled {
+            let width = self.map.width() as f32 - BODY_RADIUS;
+            let height = self.map.height() as f32 - BODY_RADIUS;
+            self.player_x = (self.player_x + dx).clamp(BODY_RADIUS, width);
+            self.player_y = (self.player_y + dy).clamp(BODY_RADIUS, height);
+            return;
+        }
 
-        // first_y and first_x are the first grid intersections that the ray intersects with.
-        let first_y = if up {
-            ceilf(self.player_y) - self.player_y
+        let slide = self.map.slide(
+            (self.player_x, self.player_y),
+            (dx, dy),
+            BODY_RADIUS,
+            self.stature(),
+        );
+        (self.player_x, self.player_y) = (slide.x, slide.y);
+        if slide.stopped_x {
+            self.player_velocity.0 = 0.0;
+        }
+        if slide.stopped_y {
+            self.player_velocity.1 = 0.0;
+        }
+    }
+
+    /// How far up from the floor the player reaches, crouching or not and
+    /// jumping or not.
+    fn stature(&self) -> Stature {
+        let head = if self.crouch.down {
+            CROUCHING_HEAD
         } else {
-            floorf(self.player_y) - self.player_y
+            STANDING_HEAD
         };
-        let first_x = -first_y / tanf(angle);
+        Stature {
+            feet: self.jump.height,
+            head: self.jump.height + head,
+        }
+    }
 
-        // The vertical and horizontal ray extensions.
-        let dy = if up { 1.0 } else { -1.0 };
-        let dx = -dy / tanf(angle);
+    /// How fast the player can walk compared to usual, slowed down by
+    /// crouching and by whatever floor they're standing on.
+    fn speed_multiplier(&self) -> f32 {
+        let crouching = if self.crouch.down { CROUCH_SPEED } else { 1.0 };
+        let floor = if self.jump.height <= 0.0 {
+            let (x, y) = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+            floor_attributes(self.map.tile_at(x, y)).speed
+        } else {
+            1.0
+        };
+        crouching * floor
+    }
 
-        // next_x and next_y keep track of how far away the ray is from the player.
-        // Note that these are relative coordinates.
-        let mut next_x = first_x;
-        let mut next_y = first_y;
+    /// Move on to the stats screen, keeping track of the best time.
+    fn finish_level(&mut self) {
+        // the player is still standing on the exit while the screen fades out.
+        if self.in_transition() {
+            return;
+        }
 
-        // Our draw distance is 256 ray extensions.
-        for _ in 0..256 {
-            // current_x and current_y are absolute coordinate for where the ray
-            // currently is on the map.
-            let current_x = next_x + self.player_x;
-            let current_y = if up {
-                next_y + self.player_y
-            } else {
-                next_y + self.player_y - 1.0
-            };
+        if self.stats.frames <= self.level().par_frames {
+            self.unlock_mutator(|unlocked| &mut unlocked.party_colors);
+        }
+        self.finish_time_attack_level();
+        self.trace_pool_report();
+        self.record_split();
 
-            // Break if we hit a wall
-            if point_in_wall(current_x, current_y) {
-                break;
+        self.transition_to(Mode::Intermission);
+    }
+
+    /// The level being played.
+    fn level(&self) -> &'static Level {
+        match self.dungeon {
+            Some(_) => &DUNGEON,
+            None => &LEVELS[self.level],
+        }
+    }
+
+    /// The height, in pixels, that a wall appears as when it is one unit
+    /// away in the level being played.
+    fn wall_height(&self) -> f32 {
+        self.level().wall_height
+    }
+
+    /// Start the next level, going back around to the first after the last.
+    fn load_next_level(&mut self) {
+        match self.dungeon {
+            // a random dungeon just leads on to another one.
+            Some(seed) => self.load_dungeon(seed.wrapping_add(1)),
+            None => self.load_level((self.level + 1) % LEVELS.len()),
+        }
+    }
+
+    /// Start a level from the beginning.
+    fn load_level(&mut self, level: usize) {
+        self.level = level;
+        self.dungeon = None;
+        self.reset_level();
+    }
+
+    /// Start a random dungeon, with a maze made from `seed`.
+    fn load_dungeon(&mut self, seed: u32) {
+        self.dungeon = Some(seed);
+        self.reset_level();
+    }
+
+    /// Put everything in the current level back how it was at the start.
+    fn reset_level(&mut self) {
+        (self.player_x, self.player_y, self.player_angle) = self.level().map.spawn;
+        self.player_velocity = (0.0, 0.0);
+        self.player_turn_velocity = 0.0;
+        self.stamina = Stamina::new();
+        self.crouch = Crouch::new();
+        self.jump = Jump::new();
+        self.dash = Dash::new();
+        self.auto_walk = AutoWalk::new();
+        self.pool_report.reset();
+        self.map = match self.dungeon {
+            Some(seed) => maze::generate(seed),
+            None => self.level().map.load(),
+        };
+        self.keys = 0;
+        self.ammo = STARTING_AMMO;
+        self.triggered = 0;
+        self.heal_fully();
+        self.push_wall = None;
+        self.teleporter = Teleporter::new();
+        self.reset_weather();
+        self.seen = [0; MAX_STREAMED_CELLS / 8];
+        // the map was just loaded on the stack, which has no room left for
+        // a whole new pool of entities as well.
+        self.entities.refill(self.level().map.entities);
+        if self.mutators.shuffle && self.dungeon.is_none() {
+            self.shuffle_entities();
+        }
+        self.apply_difficulty();
+        self.stats = Stats::new(self.entities.enemy_count());
+        self.damage_numbers = DamageNumbers::new();
+        self.decals.clear();
+        self.projectiles.clear();
+        self.charge = 0;
+        self.time = TimeScale::new();
+        self.replay.clear();
+        self.rhythm.clear();
+        messages::clear();
+    }
+
+    /// Check if the map contains a wall in a cell.
+    fn cell_is_wall(&self, x: i32, y: i32) -> bool {
+        is_wall(self.map.tile_at(x, y))
+    }
+
+    /// Check if a point on the map is open to the sky.
+    fn point_is_outdoor(&self, x: f32, y: f32) -> bool {
+        self.map.tile_at(floorf(x) as i32, floorf(y) as i32) == SKY
+    }
+
+    /// Cast a short ray straight ahead of the player, returning the cell of
+    /// the first wall it hits within their reach, along with which way along
+    /// the grid pushing on the face it hit would push that wall.
+    fn probe(&self) -> Option<((i32, i32), (i32, i32))> {
+        let angle = self.player_angle;
+        let h_hit = self.grid_intersection(angle, Side::Horizontal);
+        let v_hit = self.grid_intersection(angle, Side::Vertical);
+        let hit = if h_hit.distance < v_hit.distance {
+            h_hit
+        } else {
+            v_hit
+        };
+        if hit.distance > USE_RANGE {
+            return None;
+        }
+
+        let (forward_x, forward_y) = (cosf(angle), -sinf(angle));
+        let push = match hit.side {
+            Side::Vertical => (if forward_x > 0.0 { 1 } else { -1 }, 0),
+            Side::Horizontal => (0, if forward_y > 0.0 { 1 } else { -1 }),
+            // a slanted face can't be pushed along the grid.
+            Side::Diagonal => return None,
+        };
+        Some(((hit.cell_x, hit.cell_y), push))
+    }
+
+    /// Use whatever the player is facing, returning `true` if there was
+    /// anything there to use. Everything the player can use goes through
+    /// here, found by [`State::probe`].
+    fn activate(&mut self) -> bool {
+        // only one secret wall slides at a time.
+        if self.push_wall.is_some() {
+            return false;
+        }
+
+        let Some(((x, y), (dx, dy))) = self.probe() else {
+            return false;
+        };
+
+        if self.level().map.secrets.contains(&(x, y)) && self.cell_is_wall(x, y) {
+            self.push_wall = Some(PushWall::new(x, y, dx, dy));
+            self.stats.secrets_found += 1;
+            self.award(score::SECRET_POINTS);
+            messages::push("You found a\nsecret!");
+
+            if self.stats.secrets_found as usize == self.level().map.secrets.len() {
+                toasts::push(&toasts::TROPHY, "ALL SECRETS");
+                self.unlock_mutator(|unlocked| &mut unlocked.big_enemies);
             }
 
-            // Otherwise, add dx and dy to our current position and keep going.
-            next_x += dx;
-            next_y += dy;
+            return true;
+        }
+
+        if self.throw_switch(x, y) {
+            return true;
+        }
+
+        if self.map.tile_at(x, y) == EXIT_SWITCH {
+            sfx::play(&sfx::EXIT_SWITCH);
+            self.finish_level();
+            return true;
         }
 
-        // return the distance from next_x and next_y to the player.
-        distance(next_x, next_y)
+        self.unlock_door(x, y)
     }
 
-    /// Returns the nearest wall the ray intersects with on a **vertical** grid line.
-    fn vertical_intersection(&self, angle: f32) -> f32 {
-        // Figure out if the angle is "facing right" on the map.
-        let right = fabsf(floorf((angle - FRAC_PI_2) / PI) % 2.0) != 0.0;
+    /// Slide the current secret wall along, one cell at a time.
+    fn update_push_wall(&mut self) {
+        let Some(mut wall) = self.push_wall.take() else {
+            return;
+        };
+
+        let Some((next_x, next_y)) = wall.tick() else {
+            self.push_wall = Some(wall);
+            return;
+        };
+
+        // stop early rather than sliding into another wall or the player.
+        let player_cell = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        if self.cell_is_wall(next_x, next_y) || player_cell == (next_x, next_y) {
+            return;
+        }
+
+        let tile = self.map.tile_at(wall.x, wall.y);
+        self.map.set_tile(wall.x, wall.y, FLOOR);
+        self.map.set_tile(next_x, next_y, tile);
+        // heard from wherever the wall has got to.
+        let (x, y) = (next_x as f32 + 0.5, next_y as f32 + 0.5);
+        self.play_sound_at(x, y, &sfx::PUSH_WALL);
+
+        if !wall.advance() {
+            self.push_wall = Some(wall);
+        }
+    }
+
+    /// Returns the nearest wall the ray intersects with on a grid line of the
+    /// given `side`, either [`Side::Horizontal`] or [`Side::Vertical`].
+    ///
+    /// Both kinds of line are walked the same way, one cell `across` them at
+    /// a time while sliding `along` them, so vertical lines just swap the x
+    /// and y axes around. The hit's distance is measured straight from the
+    /// player, and its height is left for the caller to fill in.
+    fn grid_intersection(&self, angle: f32, side: Side) -> RayHit {
+        let horizontal = side == Side::Horizontal;
+        let xy = |along: f32, across: f32| {
+            if horizontal {
+                (along, across)
+            } else {
+                (across, along)
+            }
+        };
+        let (player_along, player_across) = xy(self.player_x, self.player_y);
+
+        // Figure out if the angle is "facing up" on the map for horizontal
+        // lines, or "facing right" for vertical ones.
+        let forward = if horizontal {
+            angle::normalize(angle) >= PI
+        } else {
+            !(FRAC_PI_2..3.0 * FRAC_PI_2).contains(&angle::normalize(angle))
+        };
 
-        // first_y and first_x are the first grid intersections that the ray intersects with.
-        let first_x = if right {
-            ceilf(self.player_x) - self.player_x
+        // The first grid line that the ray crosses.
+        let first_across = if forward {
+            ceilf(player_across) - player_across
         } else {
-            floorf(self.player_x) - self.player_x
+            floorf(player_across) - player_across
+        };
+
+        // How far the ray slides along the lines for each step across them.
+        let along = |across: f32| {
+            if horizontal {
+                -across / tanf(angle)
+            } else {
+                -tanf(angle) * across
+            }
         };
-        let first_y = -tanf(angle) * first_x;
+        let d_across = if forward { 1.0 } else { -1.0 };
+        let d_along = along(d_across);
 
-        // The vertical and horizontal ray extensions.
-        let dx = if right { 1.0 } else { -1.0 };
-        let dy = dx * -tanf(angle);
+        // next_along and next_across keep track of how far away the ray is
+        // from the player. Note that these are relative coordinates.
+        let mut next_along = along(first_across);
+        let mut next_across = first_across;
 
-        // next_x and next_y keep track of how far away the ray is from the player.
-        // Note that these are relative coordinates.
-        let mut next_x = first_x;
-        let mut next_y = first_y;
+        // current_x and current_y are absolute coordinate for where the ray
+        // currently is on the map, and behind is the cell the ray came from.
+        let mut current_x = 0.0;
+        let mut current_y = 0.0;
+        let (behind_x, behind_y) = xy(0.0, if forward { -1.0 } else { 1.0 });
+        let outdoor = |x: f32, y: f32| self.point_is_outdoor(x + behind_x, y + behind_y);
 
         // Our draw distance is 256 ray extensions.
         for _ in 0..256 {
-            // current_x and current_y are absolute coordinate for where the ray
-            // currently is on the map.
-            let current_x = if right {
-                next_x + self.player_x
+            let current_along = next_along + player_along;
+            let current_across = if forward {
+                next_across + player_across
             } else {
-                next_x + self.player_x - 1.0
+                next_across + player_across - 1.0
             };
-            let current_y = next_y + self.player_y;
+            (current_x, current_y) = xy(current_along, current_across);
 
-            // Break if we hit a wall
-            if point_in_wall(current_x, current_y) {
+            // Break if we hit a wall, which for a diagonal wall depends on
+            // which half of its edge the ray came in through.
+            let (cell_x, cell_y) = (floorf(current_x) as i32, floorf(current_y) as i32);
+            let tile = self.map.tile_at(cell_x, cell_y);
+            let edge = if forward { 0.0 } else { 1.0 };
+            let (u, v) = xy(current_along - floorf(current_along), edge);
+            if is_solid(tile, u, v) {
                 break;
             }
 
-            // Otherwise, add dx and dy to our current position and keep going.
-            next_x += dx;
-            next_y += dy;
+            if is_diagonal(tile) {
+                let outdoor = outdoor(current_x, current_y);
+                if let Some(hit) = self.diagonal_intersection(cell_x, cell_y, angle, outdoor) {
+                    return hit;
+                }
+            }
+
+            // Otherwise, step across to the next line and keep going.
+            next_along += d_along;
+            next_across += d_across;
+        }
+
+        let (cell_x, cell_y) = (floorf(current_x) as i32, floorf(current_y) as i32);
+        let (current_along, _) = xy(current_x, current_y);
+        let (next_x, next_y) = xy(next_along, next_across);
+
+        RayHit {
+            // the distance from next_x and next_y to the player.
+            distance: distance(next_x, next_y),
+            height: 0,
+            side,
+            tile_id: self.map.tile_at(cell_x, cell_y),
+            tex_u: current_along - floorf(current_along),
+            cell_x,
+            cell_y,
+            // the wall's face looks out onto the cell the ray came from.
+            outdoor: outdoor(current_x, current_y),
+        }
+    }
+
+    /// Returns where a ray hits the slanted face of the diagonal wall in a
+    /// cell, if it hits it at all, with the face looking out `outdoor`.
+    ///
+    /// Like the other intersections, the hit's distance is measured straight
+    /// from the player, and its height is left for the caller to fill in.
+    fn diagonal_intersection(&self, x: i32, y: i32, angle: f32, outdoor: bool) -> Option<RayHit> {
+        let tile = self.map.tile_at(x, y);
+        let (dx, dy) = (cosf(angle), -sinf(angle));
+        let (u, v) = (self.player_x - x as f32, self.player_y - y as f32);
+
+        // how far along the ray it meets the face, running from top-left to
+        // bottom-right for some diagonals and bottom-left to top-right for
+        // the rest.
+        let along = if tile == DIAGONAL_NE || tile == DIAGONAL_SW {
+            (v - u) / (dx - dy)
+        } else {
+            (1.0 - u - v) / (dx + dy)
+        };
+
+        let tex_u = u + along * dx;
+        if !along.is_finite() || along <= 0.0 || !(0.0..=1.0).contains(&tex_u) {
+            return None;
+        }
+
+        Some(RayHit {
+            distance: along,
+            height: 0,
+            side: Side::Diagonal,
+            tile_id: tile,
+            tex_u,
+            cell_x: x,
+            cell_y: y,
+            outdoor,
+        })
+    }
+
+    /// Casts a single ray from the player, returning the nearest wall it hits.
+    ///
+    /// Like the intersections, the hit's distance is measured straight from
+    /// the player, and its height is left for the caller to fill in.
+    fn cast_ray(&self, angle: f32) -> RayHit {
+        // Get the closest horizontal and vertical wall intersections for this angle.
+        let h_hit = self.grid_intersection(angle, Side::Horizontal);
+        let v_hit = self.grid_intersection(angle, Side::Vertical);
+
+        let hit = if h_hit.distance < v_hit.distance {
+            h_hit
+        } else {
+            v_hit
+        };
+
+        // the player can stand in the open half of a diagonal wall's cell, in
+        // which case its face is closer than anything else.
+        let (cell_x, cell_y) = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        if is_diagonal(self.map.tile_at(cell_x, cell_y)) {
+            let outdoor = self.point_is_outdoor(self.player_x, self.player_y);
+            if let Some(own) = self.diagonal_intersection(cell_x, cell_y, angle, outdoor) {
+                return own;
+            }
         }
 
-        // return the distance from next_x and next_y to the player.
-        distance(next_x, next_y)
+        hit
     }
 
-    /// Returns 160 wall heights and their color from the player's perspective.
-    pub fn get_view(&self) -> [(i32, bool); 160] {
+    /// Casts one ray per column from the player's perspective into [`State::view`].
+    ///
+    /// Only the first [`Resolution::rays`] entries are filled in.
+    pub fn cast_view(&mut self) {
         // The player's FOV is split in half by their viewing angle.
         // In order to get the ray's first angle we must
         // add half the FOV to the player's angle to get
         // the edge of the player's FOV.
         let starting_angle = self.player_angle + HALF_FOV;
+        let angle_step = self.resolution.angle_step();
+        let wall_height = self.wall_height();
 
-        let mut walls = [(0, false); 160];
+        for idx in 0..self.resolution.rays() {
+            let angle = starting_angle - idx as f32 * angle_step;
+            let mut hit = self.cast_ray(angle);
 
-        // `idx` is what number ray we're on, `wall` is a mutable reference to
-        // a value in `walls`.
-        for (idx, wall) in walls.iter_mut().enumerate() {
-            let angle = starting_angle - idx as f32 * ANGLE_STEP;
+            // Correct the distance to the camera plane to avoid a fisheye
+            // effect, then convert it into a wall height.
+            hit.distance *= cosf(angle - self.player_angle);
+            hit.height = (wall_height / hit.distance) as i32;
 
-            // Get the closest horizontal and vertical wall intersections for this angle.
-            let h_dist = self.horizontal_intersection(angle);
-            let v_dist = self.vertical_intersection(angle);
+            // whatever a ray hits has been seen, so it goes on the automap.
+            self.reveal(hit.cell_x, hit.cell_y);
 
-            let (min_dist, shadow) = if h_dist < v_dist {
-                (h_dist, false)
-            } else {
-                (v_dist, true)
-            };
+            self.view[idx] = hit;
+        }
+    }
+
+    /// Rotate a point on the map into camera space, returning how far it is
+    /// straight ahead of the player and how far off to their right it is.
+    fn to_camera(&self, x: f32, y: f32) -> (f32, f32) {
+        let (sin, cos) = (sinf(self.player_angle), cosf(self.player_angle));
+        let (dx, dy) = (x - self.player_x, y - self.player_y);
+        (dx * cos - dy * sin, dx * sin + dy * cos)
+    }
+
+    /// The screen column that a point in camera space is drawn at.
+    fn screen_x(&self, depth: f32, lateral: f32) -> f32 {
+        let x = (atanf(lateral / depth) + HALF_FOV) / FOV * SCREEN_SIZE as f32;
+
+        // only positions are mirrored, so sprites are still drawn the right way around.
+        if self.mutators.mirror {
+            SCREEN_SIZE as f32 - x
+        } else {
+            x
+        }
+    }
+
+    /// Which entry of [`State::view`] is drawn in screen column `x`.
+    fn ray_at(&self, x: usize) -> usize {
+        let x = if self.mutators.mirror {
+            SCREEN_SIZE - 1 - x
+        } else {
+            x
+        };
+
+        // at lower resolutions, neighbouring columns share the same ray.
+        x / self.resolution.column_width()
+    }
+
+    /// Returns every entity the player can see, projected onto the screen.
+    pub fn get_sprites(&self) -> SpriteList {
+        let tan_half_fov = tanf(HALF_FOV);
+        let fog_distance = self.fog_distance();
+        let eye_height = self.eye_height();
+        let wall_height = self.wall_height();
+
+        let mut sprites = SpriteList::new();
+
+        for (slot, entity) in self.entities.indexed() {
+            let (depth, lateral) = self.to_camera(entity.x, entity.y);
+            let size = self.mutators.size(entity);
+
+            // cull anything behind the player, lost in the fog, or outside the
+            // wedge of their field of view before doing any more work on it.
+            let radius = entity.graphic.width() as f32 / TEXELS_PER_UNIT / 2.0 * size;
+            if !(NEAR_DISTANCE..=fog_distance).contains(&depth)
+                || fabsf(lateral) - radius > depth * tan_half_fov
+            {
+                continue;
+            }
+
+            // which way the player is from the entity, to pick which side of
+            // it they see.
+            let seen_from = atan2f(entity.y - self.player_y, self.player_x - entity.x);
+
+            sprites.push(Projected {
+                graphic: entity.graphic.seen_from(seen_from - entity.facing),
+                depth,
+                screen_x: self.screen_x(depth, lateral),
+                scale: wall_height / depth,
+                size,
+                tint: if self.point_is_outdoor(entity.x, entity.y) {
+                    self.mutators.tint(slot, entity).sunlit()
+                } else {
+                    self.mutators.tint(slot, entity)
+                },
+                flat: entity.is_pickup() && eye_height / depth > SPLAT_SLOPE,
+            });
+        }
+
+        for shot in self.projectiles.iter() {
+            let (depth, lateral) = self.to_camera(shot.x, shot.y);
+            let radius = FIREBALL.width as f32 / TEXELS_PER_UNIT / 2.0;
+            if !(NEAR_DISTANCE..=fog_distance).contains(&depth)
+                || fabsf(lateral) - radius > depth * tan_half_fov
+            {
+                continue;
+            }
 
-            // Get the minimum of the two distances and convert it into a wall height.
-            *wall = (
-                (WALL_HEIGHT / (min_dist * cosf(angle - self.player_angle))) as i32,
-                shadow,
-            );
+            sprites.push(Projected {
+                graphic: Graphic::Sprite(&FIREBALL),
+                depth,
+                screen_x: self.screen_x(depth, lateral),
+                scale: wall_height / depth,
+                size: 1.0,
+                tint: shot.tint(),
+                flat: false,
+            });
         }
 
-        walls
+        sprites
     }
 }
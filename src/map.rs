@@ -0,0 +1,651 @@
+//! The grid of cells that levels are built on.
+//!
+//! Maps are stored as a byte for the width, a byte for the height, and then
+//! the cells' tile IDs, row by row from the top-left. Long stretches of the
+//! same tile are common, so the cells are packed into runs of a byte for how
+//! many cells in a row have the same tile and a byte for the tile, which get
+//! unpacked into RAM when the map is loaded.
+
+use core::f32::consts::FRAC_1_SQRT_2;
+
+use crate::{
+    math::{ceilf, copysignf, fabsf, floorf},
+    Side,
+};
+
+/// An empty cell, with a ceiling overhead.
+pub const FLOOR: u8 = 0;
+/// An empty cell that's open to the sky.
+pub const SKY: u8 = 1;
+/// A floor of lava, which burns anything standing on it.
+pub const LAVA: u8 = 2;
+/// A floor of acid, which slowly eats away at anything standing on it.
+pub const ACID: u8 = 3;
+/// Walls cut from corner to corner, filling the north-west, north-east,
+/// south-west or south-east half of their cell. The other half is open, so
+/// they come before the walls that fill their whole cell.
+pub const DIAGONAL_NW: u8 = 4;
+pub const DIAGONAL_NE: u8 = 5;
+pub const DIAGONAL_SW: u8 = 6;
+pub const DIAGONAL_SE: u8 = 7;
+/// A bridge over lava, which a switch can lower back into it.
+pub const BRIDGE: u8 = 8;
+/// A wall hanging down from the ceiling that stops short of the floor, which
+/// rays see under and only something crouching can get under.
+pub const LOW_WALL: u8 = 9;
+/// A waist-high wall standing on the floor, which rays see over and only
+/// something jumping can get over.
+pub const HURDLE: u8 = 10;
+/// A floor under shallow water, which slows down anything wading through it.
+pub const WATER: u8 = 11;
+/// A plain stone wall. Every tile ID from here on up is a wall.
+pub const STONE: u8 = 16;
+pub const BRICK: u8 = 17;
+/// Riveted metal panels.
+pub const METAL: u8 = 18;
+/// The frame around a doorway.
+pub const DOOR_FRAME: u8 = 19;
+/// A door that only opens for the red key.
+pub const RED_DOOR: u8 = 20;
+/// A door that only opens for the yellow key.
+pub const YELLOW_DOOR: u8 = 21;
+/// A switch on the wall that finishes the level when the player uses it.
+pub const EXIT_SWITCH: u8 = 22;
+/// A switch on the wall that works gates and bridges elsewhere on the map,
+/// with its lever up, and after it's been thrown.
+pub const SWITCH: u8 = 23;
+pub const SWITCH_THROWN: u8 = 24;
+/// Bars across a doorway, which a switch can raise out of the way.
+pub const GATE: u8 = 25;
+/// A scoreboard hung on the wall, showing the score of a versus match.
+pub const SCOREBOARD: u8 = 26;
+/// A monitor on the wall, showing what the level's security camera sees.
+pub const MONITOR: u8 = 27;
+
+/// How far up from the floor the bottom edge of a low wall is, as a fraction
+/// of a wall's height.
+pub const LOW_WALL_BOTTOM: f32 = 0.4;
+/// How tall a hurdle is, as a fraction of a wall's height.
+pub const HURDLE_TOP: f32 = 0.2;
+
+/// The most cells a map can have, however they're laid out, before it has
+/// to be streamed.
+pub const MAX_CELLS: usize = 1024;
+/// The most cells a streamed map can have.
+pub const MAX_STREAMED_CELLS: usize = 64 * 64;
+
+/// How many cells wide and tall each chunk of a streamed map is.
+const CHUNK_SIZE: i32 = 8;
+/// How many chunks wide and tall the window of a streamed map is.
+const WINDOW_CHUNKS: i32 = 4;
+/// How many cells wide and tall the window of a streamed map is, which
+/// fills every cell that can be held in memory.
+const WINDOW_SIZE: i32 = CHUNK_SIZE * WINDOW_CHUNKS;
+/// How many changed cells of a streamed map are remembered.
+const MAX_EDITS: usize = 32;
+
+/// Check if a tile is a wall, rather than somewhere to stand.
+pub const fn is_wall(tile: u8) -> bool {
+    tile >= STONE
+}
+
+/// Check if a tile is a wall cut from corner to corner.
+pub const fn is_diagonal(tile: u8) -> bool {
+    matches!(tile, DIAGONAL_NW..=DIAGONAL_SE)
+}
+
+/// How a kind of floor affects whoever is walking on it.
+pub struct FloorAttributes {
+    /// How much damage it does every so often, or 0 if it's safe.
+    pub damage: u8,
+    /// How fast anyone walks on it, compared to an ordinary floor.
+    pub speed: f32,
+}
+
+/// How a floor holding `tile` affects whoever is walking on it.
+pub const fn floor_attributes(tile: u8) -> FloorAttributes {
+    match tile {
+        LAVA => FloorAttributes {
+            damage: 4,
+            speed: 1.0,
+        },
+        ACID => FloorAttributes {
+            damage: 2,
+            speed: 1.0,
+        },
+        WATER => FloorAttributes {
+            damage: 0,
+            speed: 0.5,
+        },
+        _ => FloorAttributes {
+            damage: 0,
+            speed: 1.0,
+        },
+    }
+}
+
+/// How far up from the floor the bottom and top edges of a wall that only
+/// fills part of its cell are, as fractions of a wall's height, if `tile` is
+/// one.
+pub const fn partial_wall(tile: u8) -> Option<(f32, f32)> {
+    match tile {
+        LOW_WALL => Some((LOW_WALL_BOTTOM, 1.0)),
+        HURDLE => Some((0.0, HURDLE_TOP)),
+        _ => None,
+    }
+}
+
+/// How far up from the floor a body reaches, from the bottom of its feet to
+/// the top of its head, as fractions of a wall's height. It decides which
+/// partial walls the body gets past.
+#[derive(Clone, Copy)]
+pub struct Stature {
+    pub feet: f32,
+    pub head: f32,
+}
+
+/// Where a body ended up after [sliding](WorldMap::slide) along, and which
+/// axes something stopped it on.
+pub struct Slide {
+    pub x: f32,
+    pub y: f32,
+    pub stopped_x: bool,
+    pub stopped_y: bool,
+}
+
+/// The cells a line passes through on its way across the grid, from the one
+/// it starts in.
+///
+/// Each step is the next cell along, with how far along the line it's
+/// entered, as a multiple of the line's direction, and which kind of grid
+/// line is crossed into it. The cell the line starts in isn't included, and
+/// the walk never ends on its own.
+pub struct GridWalk {
+    x: i32,
+    y: i32,
+    step_x: i32,
+    step_y: i32,
+    /// How far along the line it is between columns, and between rows.
+    delta_x: f32,
+    delta_y: f32,
+    /// How far along the line the next column, and the next row, is crossed.
+    next_x: f32,
+    next_y: f32,
+}
+
+impl GridWalk {
+    /// Walk the grid from `from`, heading along `dir`.
+    pub fn new(from: (f32, f32), dir: (f32, f32)) -> Self {
+        let (x, y) = (floorf(from.0) as i32, floorf(from.1) as i32);
+        let delta_x = 1.0 / fabsf(dir.0);
+        let delta_y = 1.0 / fabsf(dir.1);
+
+        Self {
+            x,
+            y,
+            step_x: if dir.0 < 0.0 { -1 } else { 1 },
+            step_y: if dir.1 < 0.0 { -1 } else { 1 },
+            delta_x,
+            delta_y,
+            next_x: if dir.0 < 0.0 {
+                (from.0 - x as f32) * delta_x
+            } else {
+                (x as f32 + 1.0 - from.0) * delta_x
+            },
+            next_y: if dir.1 < 0.0 {
+                (from.1 - y as f32) * delta_y
+            } else {
+                (y as f32 + 1.0 - from.1) * delta_y
+            },
+        }
+    }
+}
+
+impl Iterator for GridWalk {
+    type Item = ((i32, i32), f32, Side);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (along, side) = if self.next_x < self.next_y {
+            self.x += self.step_x;
+            self.next_x += self.delta_x;
+            (self.next_x - self.delta_x, Side::Vertical)
+        } else {
+            self.y += self.step_y;
+            self.next_y += self.delta_y;
+            (self.next_y - self.delta_y, Side::Horizontal)
+        };
+
+        Some(((self.x, self.y), along, side))
+    }
+}
+
+/// Check if the point (u, v) in a cell holding `tile`, measured from the
+/// cell's top-left corner, is inside a wall.
+pub fn is_solid(tile: u8, u: f32, v: f32) -> bool {
+    match tile {
+        DIAGONAL_NW => u + v < 1.0,
+        DIAGONAL_NE => u > v,
+        DIAGONAL_SW => u < v,
+        DIAGONAL_SE => u + v > 1.0,
+        _ => is_wall(tile),
+    }
+}
+
+/// Unpack runs of tiles, each a byte for how many cells it covers and a byte
+/// for the tile, into the first `len` cells of an array, which they have to
+/// fill exactly.
+const fn unpack_runs<const N: usize>(runs: &[u8], len: usize) -> [u8; N] {
+    let mut cells = [FLOOR; N];
+    let mut cell = 0;
+    let mut run = 0;
+    while run + 1 < runs.len() {
+        let (length, tile) = (runs[run] as usize, runs[run + 1]);
+        assert!(cell + length <= len, "map has too many tiles");
+
+        let mut idx = 0;
+        while idx < length {
+            cells[cell + idx] = tile;
+            idx += 1;
+        }
+
+        cell += length;
+        run += 2;
+    }
+    assert!(
+        run == runs.len() && cell == len,
+        "map has the wrong number of tiles"
+    );
+
+    cells
+}
+
+/// Somewhere the player can be, as far as the raycaster and everything else
+/// that walks or looks around it is concerned.
+///
+/// It only has to say which tile is in each cell, so a world can be read
+/// straight out of ROM, kept in RAM to be changed, or made up as it goes.
+pub trait WorldMap {
+    fn width(&self) -> i32;
+
+    fn height(&self) -> i32;
+
+    /// Get the tile in a cell. Everything off the edge of the map should be
+    /// a wall.
+    fn tile_at(&self, x: i32, y: i32) -> u8;
+
+    /// Check if a point on the map is inside a wall, including the solid
+    /// half of a diagonal wall's cell, and partial walls in the way of a body
+    /// of some `stature`.
+    fn is_solid(&self, x: f32, y: f32, stature: Stature) -> bool {
+        let tile = self.tile_at(floorf(x) as i32, floorf(y) as i32);
+        match partial_wall(tile) {
+            Some((bottom, top)) => stature.head > bottom && stature.feet < top,
+            None => is_solid(tile, x - floorf(x), y - floorf(y)),
+        }
+    }
+
+    /// Check if a round body `radius` across from its center at (x, y) would
+    /// bump into a wall moving in the direction (dir_x, dir_y), which should
+    /// be one unit long. Partial walls only stop it if its `stature` doesn't
+    /// fit over or under them.
+    ///
+    /// Only the half of the body facing the way it's going is checked, so
+    /// anything already touching a wall, like a gate closed up against it,
+    /// can still back away.
+    fn is_blocked(
+        &self,
+        x: f32,
+        y: f32,
+        radius: f32,
+        (dir_x, dir_y): (f32, f32),
+        stature: Stature,
+    ) -> bool {
+        // straight ahead, and halfway around to either side.
+        let side = radius * FRAC_1_SQRT_2;
+        [
+            (dir_x * radius, dir_y * radius),
+            ((dir_x - dir_y) * side, (dir_y + dir_x) * side),
+            ((dir_x + dir_y) * side, (dir_y - dir_x) * side),
+        ]
+        .iter()
+        .any(|&(offset_x, offset_y)| self.is_solid(x + offset_x, y + offset_y, stature))
+    }
+
+    /// Move a round body `radius` across from (x, y) by (dx, dy), one axis at
+    /// a time, so running into a wall at an angle slides it along the wall
+    /// instead of stopping it dead. Once something stops it on an axis, it
+    /// stays stopped on that axis for the rest of the move.
+    ///
+    /// The move is split up into steps no longer than half the body's
+    /// radius, so however fast it's going it can't get past the edge of a
+    /// wall between two checks.
+    fn slide(
+        &self,
+        (x, y): (f32, f32),
+        (dx, dy): (f32, f32),
+        radius: f32,
+        stature: Stature,
+    ) -> Slide {
+        let steps = ceilf(fabsf(dx).max(fabsf(dy)) * 2.0 / radius).max(1.0);
+        let (step_x, step_y) = (dx / steps, dy / steps);
+        let mut slide = Slide {
+            x,
+            y,
+            stopped_x: false,
+            stopped_y: false,
+        };
+
+        for _ in 0..steps as u32 {
+            if step_x != 0.0 && !slide.stopped_x {
+                let x = slide.x + step_x;
+                let dir = (copysignf(1.0, step_x), 0.0);
+                slide.stopped_x = self.is_blocked(x, slide.y, radius, dir, stature);
+                if !slide.stopped_x {
+                    slide.x = x;
+                }
+            }
+
+            if step_y != 0.0 && !slide.stopped_y {
+                let y = slide.y + step_y;
+                let dir = (0.0, copysignf(1.0, step_y));
+                slide.stopped_y = self.is_blocked(slide.x, y, radius, dir, stature);
+                if !slide.stopped_y {
+                    slide.y = y;
+                }
+            }
+        }
+
+        slide
+    }
+}
+
+/// A map loaded into memory, where it can be changed as the game goes on.
+///
+/// Maps too big to fit in memory are streamed instead: they're cut into
+/// square chunks that are each packed on their own, and only a window of
+/// chunks around the player is unpacked at a time. Everything outside the
+/// window is treated as a wall, but the window reaches further than the fog
+/// so that nobody can tell.
+pub struct Map {
+    width: usize,
+    height: usize,
+    /// Every cell of the map, or for a streamed map, the cells in the window,
+    /// each kept in the slot given by [`Map::index`].
+    tiles: [u8; MAX_CELLS],
+    /// The packed chunks of a streamed map, row by row, or nothing if the
+    /// whole map fits in memory.
+    chunks: &'static [&'static [u8]],
+    /// The chunk at the top-left corner of a streamed map's window.
+    window: (i32, i32),
+    /// Cells of a streamed map that have changed since it was loaded, which
+    /// would otherwise be lost whenever the chunks they're in are unpacked
+    /// again.
+    edits: [Option<(u8, u8, u8)>; MAX_EDITS],
+}
+
+impl Map {
+    /// Load a map from its width, height and runs of tiles, laid out one
+    /// after another.
+    pub const fn load(data: &[u8]) -> Self {
+        let (width, height) = (data[0] as usize, data[1] as usize);
+        assert!(width * height <= MAX_CELLS, "map is too big");
+
+        Self {
+            width,
+            height,
+            tiles: unpack_runs(data.split_at(2).1, width * height),
+            chunks: &[],
+            window: (0, 0),
+            edits: [None; MAX_EDITS],
+        }
+    }
+
+    /// Start streaming a map from its chunks, unpacking the ones around the
+    /// point (x, y).
+    pub fn stream(
+        width: usize,
+        height: usize,
+        chunks: &'static [&'static [u8]],
+        x: f32,
+        y: f32,
+    ) -> Self {
+        assert!(
+            width * height <= MAX_STREAMED_CELLS,
+            "map is too big to stream"
+        );
+
+        let mut map = Self {
+            width,
+            height,
+            tiles: [STONE; MAX_CELLS],
+            chunks,
+            window: (window_start(x), window_start(y)),
+            edits: [None; MAX_EDITS],
+        };
+
+        let (left, top) = map.window;
+        for chunk_y in top..top + WINDOW_CHUNKS {
+            for chunk_x in left..left + WINDOW_CHUNKS {
+                map.unpack_chunk(chunk_x, chunk_y);
+            }
+        }
+
+        map
+    }
+
+    /// Create a map with every cell holding the same tile.
+    pub const fn filled(width: usize, height: usize, tile: u8) -> Self {
+        assert!(width * height <= MAX_CELLS, "map is too big");
+
+        Self {
+            width,
+            height,
+            tiles: [tile; MAX_CELLS],
+            chunks: &[],
+            window: (0, 0),
+            edits: [None; MAX_EDITS],
+        }
+    }
+
+    fn is_streamed(&self) -> bool {
+        !self.chunks.is_empty()
+    }
+
+    /// How many changed cells of a streamed map are being remembered, and how
+    /// many can be.
+    pub fn edit_usage(&self) -> (usize, usize) {
+        (self.edits.iter().flatten().count(), MAX_EDITS)
+    }
+
+    /// Where a cell comes when counting row by row from the top-left, if
+    /// it's on the map.
+    pub fn cell_number(&self, x: i32, y: i32) -> Option<usize> {
+        if (0..self.width()).contains(&x) && (0..self.height()).contains(&y) {
+            Some(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Where a cell is kept in memory, if it's on the map and, for a
+    /// streamed map, in the window.
+    pub fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.is_streamed() {
+            return self.cell_number(x, y);
+        }
+
+        let (left, top) = (self.window.0 * CHUNK_SIZE, self.window.1 * CHUNK_SIZE);
+        let in_window =
+            (left..left + WINDOW_SIZE).contains(&x) && (top..top + WINDOW_SIZE).contains(&y);
+        self.cell_number(x, y)
+            .and(in_window.then(|| window_slot(x, y)))
+    }
+
+    /// Change the tile in a cell, if it's on the map.
+    pub fn set_tile(&mut self, x: i32, y: i32, tile: u8) {
+        let Some(idx) = self.index(x, y) else {
+            return;
+        };
+        self.tiles[idx] = tile;
+
+        if self.is_streamed() {
+            let edit = (x as u8, y as u8, tile);
+            let same_cell = |slot: &Option<(u8, u8, u8)>| {
+                slot.is_some_and(|(ex, ey, _)| (ex, ey) == (edit.0, edit.1))
+            };
+            // once there's no more room, later changes are forgotten when
+            // the player walks away from them.
+            let slot = match self.edits.iter().position(same_cell) {
+                Some(slot) => Some(slot),
+                None => self.edits.iter().position(Option::is_none),
+            };
+            if let Some(slot) = slot {
+                self.edits[slot] = Some(edit);
+            }
+        }
+    }
+
+    /// Move a streamed map's window along to keep the point (x, y) near its
+    /// middle, unpacking the chunks that come into it.
+    pub fn stream_around(&mut self, x: f32, y: f32) {
+        if !self.is_streamed() {
+            return;
+        }
+
+        let (old_left, old_top) = self.window;
+        let (left, top) = (window_start(x), window_start(y));
+        self.window = (left, top);
+
+        for chunk_y in top..top + WINDOW_CHUNKS {
+            for chunk_x in left..left + WINDOW_CHUNKS {
+                let was_in_window = (old_left..old_left + WINDOW_CHUNKS).contains(&chunk_x)
+                    && (old_top..old_top + WINDOW_CHUNKS).contains(&chunk_y);
+                if !was_in_window {
+                    self.unpack_chunk(chunk_x, chunk_y);
+                }
+            }
+        }
+    }
+
+    /// Unpack one of a streamed map's chunks into its place in the window,
+    /// along with any changes made to it.
+    fn unpack_chunk(&mut self, chunk_x: i32, chunk_y: i32) {
+        let chunks_wide = (self.width() + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        let chunks_high = (self.height() + CHUNK_SIZE - 1) / CHUNK_SIZE;
+
+        // chunks past the edge of the map are solid walls.
+        const CHUNK_CELLS: usize = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+        let cells = if (0..chunks_wide).contains(&chunk_x) && (0..chunks_high).contains(&chunk_y) {
+            let runs = self.chunks[(chunk_y * chunks_wide + chunk_x) as usize];
+            unpack_runs(runs, CHUNK_CELLS)
+        } else {
+            [STONE; CHUNK_CELLS]
+        };
+
+        for (idx, &tile) in cells.iter().enumerate() {
+            let x = chunk_x * CHUNK_SIZE + idx as i32 % CHUNK_SIZE;
+            let y = chunk_y * CHUNK_SIZE + idx as i32 / CHUNK_SIZE;
+            self.tiles[window_slot(x, y)] = tile;
+        }
+
+        for &(x, y, tile) in self.edits.iter().flatten() {
+            let (x, y) = (x as i32, y as i32);
+            if x.div_euclid(CHUNK_SIZE) == chunk_x && y.div_euclid(CHUNK_SIZE) == chunk_y {
+                self.tiles[window_slot(x, y)] = tile;
+            }
+        }
+    }
+
+    /// Get the tile in a cell, reading it out of a streamed map's packed
+    /// chunks if it's outside the window, rather than treating it as a wall
+    /// like [`WorldMap::tile_at`] does. Everything off the edge of the map is
+    /// still a wall.
+    pub fn tile_anywhere(&self, x: i32, y: i32) -> u8 {
+        if let Some(idx) = self.index(x, y) {
+            return self.tiles[idx];
+        }
+        if !self.is_streamed() || self.cell_number(x, y).is_none() {
+            return STONE;
+        }
+
+        let edit = self
+            .edits
+            .iter()
+            .flatten()
+            .find(|&&(ex, ey, _)| (ex as i32, ey as i32) == (x, y));
+        if let Some(&(_, _, tile)) = edit {
+            return tile;
+        }
+
+        // only the runs up to the cell are read, rather than unpacking the
+        // whole chunk.
+        let chunks_wide = (self.width() + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        let runs = self.chunks[(y / CHUNK_SIZE * chunks_wide + x / CHUNK_SIZE) as usize];
+        let cell = (y % CHUNK_SIZE * CHUNK_SIZE + x % CHUNK_SIZE) as usize;
+        let mut end = 0;
+        for run in runs.chunks_exact(2) {
+            end += run[0] as usize;
+            if cell < end {
+                return run[1];
+            }
+        }
+        STONE
+    }
+
+    /// The tiles of every cell kept in memory.
+    fn resident_tiles(&self) -> &[u8] {
+        &self.tiles[..(self.width * self.height).min(MAX_CELLS)]
+    }
+
+    /// Check if any cell on the map is open to the sky.
+    pub fn has_sky(&self) -> bool {
+        self.resident_tiles().contains(&SKY)
+    }
+
+    /// Check if any cell on the map has a floor drawn in its own colors,
+    /// like lava or water.
+    pub fn has_marked_floors(&self) -> bool {
+        self.resident_tiles()
+            .iter()
+            .any(|&tile| matches!(tile, LAVA | ACID | WATER))
+    }
+
+    /// Check if any cell on the map has a wall that only fills part of it.
+    pub fn has_partial_walls(&self) -> bool {
+        self.resident_tiles()
+            .iter()
+            .any(|&tile| partial_wall(tile).is_some())
+    }
+}
+
+impl WorldMap for Map {
+    fn width(&self) -> i32 {
+        self.width as i32
+    }
+
+    fn height(&self) -> i32 {
+        self.height as i32
+    }
+
+    fn tile_at(&self, x: i32, y: i32) -> u8 {
+        match self.index(x, y) {
+            Some(idx) => self.tiles[idx],
+            None => STONE,
+        }
+    }
+}
+
+/// The first chunk along one axis of a streamed map's window, to keep the
+/// point at `position` along that axis at least a chunk and a half from the
+/// window's edges.
+fn window_start(position: f32) -> i32 {
+    let cell = floorf(position) as i32;
+    (cell - WINDOW_SIZE / 2 + CHUNK_SIZE / 2).div_euclid(CHUNK_SIZE)
+}
+
+/// Where a cell of a streamed map is kept in memory while it's in the
+/// window. Cells wrap around the window, so moving it along only needs the
+/// chunks coming into it to be unpacked.
+fn window_slot(x: i32, y: i32) -> usize {
+    (y.rem_euclid(WINDOW_SIZE) * WINDOW_SIZE + x.rem_euclid(WINDOW_SIZE)) as usize
+}
@@ -0,0 +1,94 @@
+//! Maths that `core` doesn't provide without `std`.
+
+use core::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI, TAU};
+
+const FIVE_PI_SQUARED: f32 = 5.0 * (PI * PI);
+
+pub fn sinf(mut x: f32) -> f32 {
+    let y = x / TAU;
+    let z = y - floorf(y);
+    x = z * TAU;
+
+    let sinf_imp = |x: f32| -> f32 {
+        // these magic numbers were discovered 1400 years ago!
+        (16.0 * x * (PI - x)) / (FIVE_PI_SQUARED - (4.0 * x * (PI - x)))
+    };
+
+    if x > PI {
+        -sinf_imp(x - PI)
+    } else {
+        sinf_imp(x)
+    }
+}
+
+pub fn cosf(x: f32) -> f32 {
+    sinf(x + FRAC_PI_2)
+}
+
+pub fn tanf(x: f32) -> f32 {
+    sinf(x) / cosf(x)
+}
+
+pub fn atanf(x: f32) -> f32 {
+    if fabsf(x) > 1.0 {
+        // atan(x) = ±π/2 - atan(1/x), keeping the approximation in range.
+        let offset = if x > 0.0 { FRAC_PI_2 } else { -FRAC_PI_2 };
+        offset - atanf(1.0 / x)
+    } else {
+        // good to within about 0.0015 radians on [-1, 1].
+        FRAC_PI_4 * x - x * (fabsf(x) - 1.0) * (0.2447 + 0.0663 * fabsf(x))
+    }
+}
+
+/// Get the angle from the positive x axis to the point (x, y), from -π to π.
+pub fn atan2f(y: f32, x: f32) -> f32 {
+    if x > 0.0 {
+        atanf(y / x)
+    } else if x < 0.0 && y >= 0.0 {
+        atanf(y / x) + PI
+    } else if x < 0.0 {
+        atanf(y / x) - PI
+    } else if y > 0.0 {
+        FRAC_PI_2
+    } else if y < 0.0 {
+        -FRAC_PI_2
+    } else {
+        0.0
+    }
+}
+
+pub fn sqrtf(x: f32) -> f32 {
+    unsafe { core::intrinsics::sqrtf32(x) }
+}
+
+pub fn floorf(x: f32) -> f32 {
+    unsafe { core::intrinsics::floorf32(x) }
+}
+
+pub fn ceilf(x: f32) -> f32 {
+    unsafe { core::intrinsics::ceilf32(x) }
+}
+
+pub fn fabsf(x: f32) -> f32 {
+    unsafe { core::intrinsics::fabsf32(x) }
+}
+
+pub fn copysignf(x: f32, sign: f32) -> f32 {
+    unsafe { core::intrinsics::copysignf32(x, sign) }
+}
+
+/// Get the distance from (0.0, 0.0) to (x, y).
+pub fn distance(a: f32, b: f32) -> f32 {
+    sqrtf((a * a) + (b * b))
+}
+
+/// Blend between `a` and `b`, where `t` runs from 0.0 (all `a`) to 1.0 (all `b`).
+pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Ease `t` from 0.0 to 1.0 so that it starts and ends slowly.
+pub fn ease_in_out(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
@@ -0,0 +1,70 @@
+//! Random mazes, made fresh from a seed for each random dungeon.
+//!
+//! Mazes are carved with a recursive backtracker, which visits every cell
+//! and leaves exactly one path between any two of them. That means the exit
+//! can go anywhere and still be reachable from the start.
+
+use crate::{
+    map::{Map, FLOOR, STONE},
+    rng::Rng,
+};
+
+/// How many cells there are along each side of the maze. There's a wall
+/// between every pair of cells, and all around the outside.
+const CELLS: usize = 10;
+
+/// How many tiles there are along each side of a maze's map.
+pub const SIZE: i32 = CELLS as i32 * 2 + 1;
+
+/// The four directions the maze can be carved in.
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+/// Carve a maze into a map full of walls. The player starts in the top-left
+/// cell, and the exit is in the bottom-right one.
+pub fn generate(seed: u32) -> Map {
+    let mut map = Map::filled(SIZE as usize, SIZE as usize, STONE);
+    let mut rng = Rng::new(seed);
+
+    let mut visited = [false; CELLS * CELLS];
+    let mut path = [(0, 0); CELLS * CELLS];
+    let mut len = 1;
+
+    visited[0] = true;
+    map.set_tile(1, 1, FLOOR);
+
+    while len > 0 {
+        let (x, y) = path[len - 1];
+
+        let mut unvisited = [(0, 0); DIRECTIONS.len()];
+        let mut count = 0;
+        for (dx, dy) in DIRECTIONS {
+            let (next_x, next_y) = (x + dx, y + dy);
+            if (0..CELLS as i32).contains(&next_x)
+                && (0..CELLS as i32).contains(&next_y)
+                && !visited[next_y as usize * CELLS + next_x as usize]
+            {
+                unvisited[count] = (dx, dy);
+                count += 1;
+            }
+        }
+
+        // nowhere new to go from here, so back up and try from the cell before.
+        if count == 0 {
+            len -= 1;
+            continue;
+        }
+
+        let (dx, dy) = unvisited[rng.below(count)];
+        let (next_x, next_y) = (x + dx, y + dy);
+
+        // knock down the wall between the cells, then step into the next one.
+        map.set_tile(x * 2 + 1 + dx, y * 2 + 1 + dy, FLOOR);
+        map.set_tile(next_x * 2 + 1, next_y * 2 + 1, FLOOR);
+
+        visited[next_y as usize * CELLS + next_x as usize] = true;
+        path[len] = (next_x, next_y);
+        len += 1;
+    }
+
+    map
+}
@@ -0,0 +1,114 @@
+//! Messages shown to the player in a box over the bottom of the screen.
+//!
+//! Anything can queue up a message with [`push`]. Messages are shown one at
+//! a time, typing themselves out, until the player dismisses them.
+
+use crate::{text::text, ui::Ui};
+
+/// The most messages that can be waiting to be shown at once.
+const CAPACITY: usize = 4;
+
+/// How many updates it takes to type out each character.
+const FRAMES_PER_CHAR: usize = 2;
+
+/// Messages waiting to be shown, with the one on screen at the front.
+pub struct Messages {
+    queue: [&'static str; CAPACITY],
+    len: usize,
+    /// How many updates the front message has been on screen for.
+    frames: usize,
+}
+
+static mut MESSAGES: Messages = Messages::new();
+
+/// Queue up a message to be shown to the player. Lines should be broken up
+/// with `\n` so that each fits inside the box, which holds four lines of 18
+/// characters.
+///
+/// The message is dropped if too many are already waiting.
+pub fn push(message: &'static str) {
+    unsafe { MESSAGES.push(message) }
+}
+
+/// Advance the message on screen, if there is one.
+///
+/// Returns `true` if a message is showing, in which case `dismiss` was used to
+/// skip its typing or dismiss it and shouldn't be used for anything else.
+pub fn update(dismiss: bool) -> bool {
+    unsafe { MESSAGES.update(dismiss) }
+}
+
+/// Draw the message on screen, if there is one.
+pub fn draw() {
+    unsafe { MESSAGES.draw() }
+}
+
+/// How many messages are waiting, including the one on screen, and how many
+/// there's room for.
+pub fn usage() -> (usize, usize) {
+    unsafe { (MESSAGES.len, CAPACITY) }
+}
+
+/// Throw away every message, including the one on screen.
+pub fn clear() {
+    unsafe { MESSAGES = Messages::new() }
+}
+
+impl Messages {
+    const fn new() -> Self {
+        Self {
+            queue: [""; CAPACITY],
+            len: 0,
+            frames: 0,
+        }
+    }
+
+    fn push(&mut self, message: &'static str) {
+        if self.len < CAPACITY {
+            self.queue[self.len] = message;
+            self.len += 1;
+        }
+    }
+
+    /// How much of the front message has been typed out so far.
+    fn revealed(&self) -> usize {
+        (self.frames / FRAMES_PER_CHAR).min(self.queue[0].len())
+    }
+
+    fn update(&mut self, dismiss: bool) -> bool {
+        if self.len == 0 {
+            return false;
+        }
+
+        let fully_revealed = self.revealed() == self.queue[0].len();
+
+        if dismiss && fully_revealed {
+            self.queue.rotate_left(1);
+            self.len -= 1;
+            self.frames = 0;
+        } else if dismiss {
+            self.frames = self.queue[0].len() * FRAMES_PER_CHAR;
+        } else {
+            self.frames += 1;
+        }
+
+        true
+    }
+
+    fn draw(&self) {
+        if self.len == 0 {
+            return;
+        }
+
+        let message = self.queue[0];
+        let revealed = self.revealed();
+
+        let mut ui = Ui::new(4, 116, 0);
+        ui.dialog(152, 40).label(&message[..revealed]);
+
+        // let the player know they can move on once the whole thing is out.
+        if revealed == message.len() {
+            text("\u{80}", 144, 144);
+        }
+    }
+}
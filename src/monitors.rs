@@ -0,0 +1,136 @@
+//! Monitors on the walls, showing what a security camera elsewhere on the
+//! map sees, so the player can scout ahead.
+//!
+//! The camera's view is drawn into a texture every few updates, casting one
+//! ray for each column of the monitor's tiny screen. Enemies in view show up
+//! as dark figures standing in it.
+
+use crate::{
+    framebuffer::SCREEN_SIZE,
+    math::{atanf, cosf},
+    texture::{Slot, Texture},
+    Side, State, FOV, HALF_FOV, NEAR_DISTANCE,
+};
+
+/// How many updates pass between redraws of the camera's view.
+const REFRESH_INTERVAL: u32 = 6;
+
+/// Where the screen is on the monitor, inside its bezel, in texels.
+const SCREEN_LEFT: usize = 2;
+const SCREEN_TOP: usize = 3;
+const SCREEN_WIDTH: usize = 12;
+const SCREEN_HEIGHT: usize = 10;
+
+/// The colors of the monitor's bezel, the glow of its screen, the walls in
+/// view on their lit and shaded sides, and enemies standing in front of them.
+const BEZEL: u8 = 3;
+const GLOW: u8 = 1;
+const LIT_WALL: u8 = 2;
+const SHADED_WALL: u8 = 3;
+const FIGURE: u8 = 3;
+
+impl State {
+    /// Redraw what the level's security camera sees, every few updates, if
+    /// it has one.
+    pub fn update_monitors(&mut self) {
+        let Some((x, y, angle)) = self.level().map.camera else {
+            return;
+        };
+        if self.stats.frames % REFRESH_INTERVAL != 0 {
+            return;
+        }
+
+        // the camera's rays are cast as if the player were standing there.
+        let player = (self.player_x, self.player_y, self.player_angle);
+        (self.player_x, self.player_y, self.player_angle) = (x, y, angle);
+
+        let mut walls = [(0.0, Side::Horizontal); SCREEN_WIDTH];
+        for (column, wall) in walls.iter_mut().enumerate() {
+            let ray_angle = angle + HALF_FOV - (column as f32 + 0.5) * FOV / SCREEN_WIDTH as f32;
+            let h_hit = self.grid_intersection(ray_angle, Side::Horizontal);
+            let v_hit = self.grid_intersection(ray_angle, Side::Vertical);
+            let hit = if h_hit.distance < v_hit.distance {
+                h_hit
+            } else {
+                v_hit
+            };
+            *wall = (hit.distance * cosf(ray_angle - angle), hit.side);
+        }
+
+        // the nearest enemy in each column, if any are in front of the walls.
+        let mut figures = [None; SCREEN_WIDTH];
+        for (_, entity) in self.entities.indexed() {
+            if entity.health.is_none() {
+                continue;
+            }
+
+            let (depth, lateral) = self.to_camera(entity.x, entity.y);
+            let column = (atanf(lateral / depth) + HALF_FOV) / FOV * SCREEN_WIDTH as f32;
+            if depth < NEAR_DISTANCE || !(0.0..SCREEN_WIDTH as f32).contains(&column) {
+                continue;
+            }
+
+            let column = column as usize;
+            let nearer = figures[column].map_or(true, |nearest| depth < nearest);
+            if depth < walls[column].0 && nearer {
+                figures[column] = Some(depth);
+            }
+        }
+
+        (self.player_x, self.player_y, self.player_angle) = player;
+
+        // walls are as tall on the monitor, for its width, as on the screen.
+        let wall_height = self.wall_height() * SCREEN_WIDTH as f32 / SCREEN_SIZE as f32;
+        let texture = self.textures.get_mut(Slot::Monitor);
+        draw_bezel(texture);
+        for (column, (&(distance, side), figure)) in walls.iter().zip(figures).enumerate() {
+            let color = match side {
+                Side::Horizontal => LIT_WALL,
+                _ => SHADED_WALL,
+            };
+            let u = SCREEN_LEFT + column;
+            fill_span(texture, u, wall_height / distance, color);
+            if let Some(depth) = figure {
+                // figures stand on the floor, and are half as tall as a wall.
+                let height = wall_height / depth;
+                let floor = (SCREEN_HEIGHT as f32 + height) / 2.0;
+                fill_rows(texture, u, floor - height / 2.0, floor, FIGURE);
+            }
+        }
+    }
+}
+
+/// Draw the monitor's bezel, with its screen left glowing and blank.
+fn draw_bezel(texture: &mut Texture) {
+    texture.clear(0);
+    texture.fill_rect(
+        SCREEN_LEFT - 1,
+        SCREEN_TOP - 1,
+        SCREEN_WIDTH + 2,
+        SCREEN_HEIGHT + 2,
+        BEZEL,
+    );
+    texture.fill_rect(SCREEN_LEFT, SCREEN_TOP, SCREEN_WIDTH, SCREEN_HEIGHT, GLOW);
+}
+
+/// Fill a wall `height` texels tall, centered on the screen, into column `u`.
+fn fill_span(texture: &mut Texture, u: usize, height: f32, color: u8) {
+    let middle = SCREEN_HEIGHT as f32 / 2.0;
+    fill_rows(
+        texture,
+        u,
+        middle - height / 2.0,
+        middle + height / 2.0,
+        color,
+    );
+}
+
+/// Fill the rows of the screen between `top` and `bottom` in column `u`,
+/// clipped to the screen.
+fn fill_rows(texture: &mut Texture, u: usize, top: f32, bottom: f32, color: u8) {
+    let top = (top + 0.5).clamp(0.0, SCREEN_HEIGHT as f32) as usize;
+    let bottom = (bottom + 0.5).clamp(0.0, SCREEN_HEIGHT as f32) as usize;
+    for v in top..bottom {
+        texture.set(u, SCREEN_TOP + v, color);
+    }
+}
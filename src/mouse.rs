@@ -0,0 +1,49 @@
+//! Turning with the mouse and attacking with a click, for players who'd
+//! rather not use the gamepad for it.
+//!
+//! WASM-4 only says where the mouse is, not how far it's moved, so the
+//! position on the last update is kept to work out how far it's gone since.
+
+const MOUSE_X: *const i16 = 0x1a as *const i16;
+const MOUSE_BUTTONS: *const u8 = 0x1e as *const u8;
+
+const MOUSE_LEFT: u8 = 1;
+
+/// How many radians the view turns for each pixel the mouse moves.
+const SENSITIVITY: f32 = 0.012;
+/// The most pixels the mouse can count as moving in one update. The mouse
+/// jumps when it leaves the screen and comes back somewhere else, which
+/// shouldn't spin the player around.
+const MAX_MOVE: i16 = 24;
+
+/// What the mouse did since the last update.
+pub struct MouseInput {
+    /// How many radians to turn anticlockwise.
+    pub turn: f32,
+    /// Whether the left button was just clicked.
+    pub clicked: bool,
+}
+
+/// The mouse's position and buttons on the previous update.
+static mut PREVIOUS: Option<(i16, u8)> = None;
+
+/// Forget where the mouse was, so the next read starts afresh without it
+/// having moved.
+pub fn forget() {
+    unsafe { PREVIOUS = None };
+}
+
+/// Read what the mouse has done since the last time this was called, which
+/// should be on every update.
+pub fn read() -> MouseInput {
+    let (x, buttons) = unsafe { (*MOUSE_X, *MOUSE_BUTTONS) };
+    let (previous_x, previous_buttons) = unsafe { PREVIOUS.unwrap_or((x, buttons)) };
+    unsafe { PREVIOUS = Some((x, buttons)) };
+
+    // moving the mouse right turns the player to the right, clockwise.
+    let moved = (x - previous_x).clamp(-MAX_MOVE, MAX_MOVE);
+    MouseInput {
+        turn: -(moved as f32) * SENSITIVITY,
+        clicked: buttons & MOUSE_LEFT != 0 && previous_buttons & MOUSE_LEFT == 0,
+    }
+}
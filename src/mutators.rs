@@ -0,0 +1,200 @@
+//! Optional twists on the game, picked from the title screen before a run.
+//!
+//! Records set with mutators that change how the game plays are kept apart
+//! from the rest, so they don't compete with normal runs. Purely cosmetic
+//! mutators have to be unlocked first, as rewards for playing well.
+
+use crate::{entities::Entity, sprites::Tint, title, toasts, ui::Ui, Mode, State};
+
+/// How many times bigger enemies are drawn with [`Mutators::big_enemies`] on.
+const BIG_ENEMY_SIZE: f32 = 1.5;
+
+/// How many times faster enemies chase the player with
+/// [`Mutators::fast_enemies`] on.
+const FAST_ENEMY_SPEED: f32 = 2.0;
+
+/// The tints handed out to enemies with [`Mutators::party_colors`] on.
+const PARTY_TINTS: [Tint; 4] = [Tint::Normal, Tint::Team(1), Tint::Frozen, Tint::Poisoned];
+
+/// Which mutators are switched on, or which have been unlocked.
+#[derive(Clone, Copy)]
+pub struct Mutators {
+    /// Flip the view from left to right, along with the controls for turning.
+    pub mirror: bool,
+    /// Draw enemies bigger than normal, without making them any easier to hit.
+    pub big_enemies: bool,
+    /// Draw every enemy in its own colors.
+    pub party_colors: bool,
+    /// Shuffle where items and enemies are placed in each level, differently
+    /// every run.
+    pub shuffle: bool,
+    /// Hold enemy attacks, and the switches and doors the player uses, until
+    /// the next beat of the [`rhythm`](crate::rhythm) clock.
+    pub rhythm: bool,
+    /// Have enemies chase the player twice as fast.
+    pub fast_enemies: bool,
+    /// Leave the player with a single point of health, so any hit kills them.
+    pub one_hp: bool,
+    /// Never run out of ammo.
+    pub infinite_ammo: bool,
+}
+
+impl Mutators {
+    /// A normal game.
+    pub const NONE: Self = Self {
+        mirror: false,
+        big_enemies: false,
+        party_colors: false,
+        shuffle: false,
+        rhythm: false,
+        fast_enemies: false,
+        one_hp: false,
+        infinite_ammo: false,
+    };
+
+    /// The mutators that can be picked before any have been unlocked.
+    pub const UNLOCKED_AT_START: Self = Self {
+        mirror: true,
+        shuffle: true,
+        rhythm: true,
+        fast_enemies: true,
+        one_hp: true,
+        infinite_ammo: true,
+        ..Self::NONE
+    };
+
+    /// Whether any mutators that change how the game plays are switched on.
+    pub fn change_play(self) -> bool {
+        self.mirror
+            || self.shuffle
+            || self.rhythm
+            || self.fast_enemies
+            || self.one_hp
+            || self.infinite_ammo
+    }
+
+    /// How many times bigger than normal to draw an entity.
+    pub fn size(self, entity: &Entity) -> f32 {
+        if self.big_enemies && entity.health.is_some() {
+            BIG_ENEMY_SIZE
+        } else {
+            1.0
+        }
+    }
+
+    /// How many times faster than normal enemies chase the player.
+    pub fn enemy_speed(self) -> f32 {
+        if self.fast_enemies {
+            FAST_ENEMY_SPEED
+        } else {
+            1.0
+        }
+    }
+
+    /// The tint to draw an entity with this frame, given the slot it's in.
+    pub fn tint(self, slot: usize, entity: &Entity) -> Tint {
+        // bosses keep their own colors, so they still stand out.
+        if self.party_colors && !entity.flash && entity.health.is_some() && !entity.boss {
+            PARTY_TINTS[slot % PARTY_TINTS.len()]
+        } else {
+            entity.current_tint()
+        }
+    }
+}
+
+impl State {
+    /// Unlock the mutator `pick` chooses, letting the player know and
+    /// saving it to the disk if it's the first time.
+    pub fn unlock_mutator(&mut self, pick: fn(&mut Mutators) -> &mut bool) {
+        let unlocked = pick(&mut self.unlocked);
+        if !*unlocked {
+            *unlocked = true;
+            toasts::push(&toasts::TROPHY, "NEW MUTATOR");
+            self.save_options();
+        }
+    }
+
+    /// Show the menu for switching mutators on and off, going back to the
+    /// title screen when the player is done.
+    pub fn update_mutators(&mut self, pressed: u8) {
+        let mut ui = Ui::new(24, 8, pressed);
+
+        ui.label("MUTATORS");
+        ui.space(8);
+
+        let mirror = match self.mutators.mirror {
+            true => "MIRROR: ON",
+            false => "MIRROR: OFF",
+        };
+        let big_enemies = match (self.unlocked.big_enemies, self.mutators.big_enemies) {
+            (false, _) => "LOCKED",
+            (true, true) => "BIG FOES: ON",
+            (true, false) => "BIG FOES: OFF",
+        };
+        let party_colors = match (self.unlocked.party_colors, self.mutators.party_colors) {
+            (false, _) => "LOCKED",
+            (true, true) => "PARTY: ON",
+            (true, false) => "PARTY: OFF",
+        };
+
+        let shuffle = match self.mutators.shuffle {
+            true => "SHUFFLE: ON",
+            false => "SHUFFLE: OFF",
+        };
+        let rhythm = match self.mutators.rhythm {
+            true => "RHYTHM: ON",
+            false => "RHYTHM: OFF",
+        };
+        let fast_enemies = match self.mutators.fast_enemies {
+            true => "FAST FOES: ON",
+            false => "FAST FOES: OFF",
+        };
+        let one_hp = match self.mutators.one_hp {
+            true => "ONE HP: ON",
+            false => "ONE HP: OFF",
+        };
+        let infinite_ammo = match self.mutators.infinite_ammo {
+            true => "INF AMMO: ON",
+            false => "INF AMMO: OFF",
+        };
+
+        let items = [
+            mirror,
+            big_enemies,
+            party_colors,
+            shuffle,
+            rhythm,
+            fast_enemies,
+            one_hp,
+            infinite_ammo,
+            "BACK",
+        ];
+        match ui.menu(&items, &mut self.menu_focus) {
+            Some(0) => self.mutators.mirror = !self.mutators.mirror,
+            Some(1) if self.unlocked.big_enemies => {
+                self.mutators.big_enemies = !self.mutators.big_enemies
+            }
+            Some(2) if self.unlocked.party_colors => {
+                self.mutators.party_colors = !self.mutators.party_colors
+            }
+            Some(3) => self.mutators.shuffle = !self.mutators.shuffle,
+            Some(4) => self.mutators.rhythm = !self.mutators.rhythm,
+            Some(5) => self.mutators.fast_enemies = !self.mutators.fast_enemies,
+            Some(6) => self.mutators.one_hp = !self.mutators.one_hp,
+            Some(7) => self.mutators.infinite_ammo = !self.mutators.infinite_ammo,
+            Some(8) => {
+                self.mode = Mode::Title;
+                self.menu_focus = title::MUTATORS_ITEM;
+            }
+            _ => {}
+        }
+
+        ui.space(4);
+        match self.menu_focus {
+            1 if !self.unlocked.big_enemies => ui.hint("FIND EVERY SECRET\nIN A LEVEL"),
+            2 if !self.unlocked.party_colors => ui.hint("BEAT A PAR TIME"),
+            _ if self.mutators.change_play() => ui.hint("RECORDS ARE KEPT\nSEPARATELY"),
+            _ => ui.hint("\u{86}\u{87} CHOOSE  \u{80} PICK"),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! A debug mode for walking through walls, to get around a level while
+//! building it and look at its walls from any side.
+//!
+//! It's switched on and off by pressing a secret sequence of buttons while
+//! playing, and shows on the HUD while it's on.
+
+use crate::{
+    framebuffer::SCREEN_SIZE, text::text, State, BUTTON_DOWN, BUTTON_LEFT, BUTTON_RIGHT, BUTTON_UP,
+    DRAW_COLORS,
+};
+
+/// The buttons to press, one after another, to switch noclip on or off.
+const CODE: [u8; 8] = [
+    BUTTON_UP,
+    BUTTON_UP,
+    BUTTON_DOWN,
+    BUTTON_DOWN,
+    BUTTON_LEFT,
+    BUTTON_RIGHT,
+    BUTTON_LEFT,
+    BUTTON_RIGHT,
+];
+
+/// Whether the player can walk through walls, and how much of the code
+/// they've pressed so far.
+pub struct Noclip {
+    pub enabled: bool,
+    progress: usize,
+}
+
+impl Noclip {
+    pub const fn new() -> Self {
+        Self {
+            enabled: false,
+            progress: 0,
+        }
+    }
+
+    /// Keep track of the buttons `pressed` this update, switching noclip on
+    /// or off once the whole code has been pressed.
+    pub fn enter(&mut self, pressed: u8) {
+        if pressed == 0 {
+            return;
+        }
+
+        self.progress = if pressed == CODE[self.progress] {
+            self.progress + 1
+        } else if pressed == CODE[0] {
+            1
+        } else {
+            0
+        };
+
+        if self.progress == CODE.len() {
+            self.enabled = !self.enabled;
+            self.progress = 0;
+        }
+    }
+}
+
+impl State {
+    /// Show that noclip is on in the bottom-right corner of the screen, just
+    /// above the score.
+    pub fn draw_noclip(&self) {
+        if !self.noclip.enabled {
+            return;
+        }
+
+        let label = "NOCLIP";
+        let x = SCREEN_SIZE as i32 - 4 - 8 * label.len() as i32;
+        unsafe { *DRAW_COLORS = 0x4 };
+        text(label, x, SCREEN_SIZE as i32 - 22);
+    }
+}
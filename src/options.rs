@@ -0,0 +1,102 @@
+//! Settings for how the game looks and plays, picked from the title screen
+//! or the pause menu.
+
+use crate::{title, ui::Ui, Mode, Resolution, State};
+
+/// Where the options menu has its "CONTROLS" item.
+pub const CONTROLS_ITEM: usize = 7;
+
+impl State {
+    /// Show the menu for changing settings, going back to the title screen
+    /// when the player is done.
+    pub fn update_options(&mut self, pressed: u8) {
+        let mut ui = Ui::new(24, 16, pressed);
+
+        ui.label("OPTIONS");
+        ui.space(8);
+
+        let resolution = match self.resolution {
+            Resolution::Full => "RES: FULL",
+            Resolution::Half => "RES: HALF",
+        };
+
+        let damage_numbers = if self.show_damage_numbers {
+            "DAMAGE: ON"
+        } else {
+            "DAMAGE: OFF"
+        };
+
+        let motion = if self.reduce_motion {
+            "MOTION: LESS"
+        } else {
+            "MOTION: FULL"
+        };
+
+        let inputs = if self.show_inputs {
+            "INPUTS: ON"
+        } else {
+            "INPUTS: OFF"
+        };
+
+        let mouse = if self.mouse_look {
+            "MOUSE: ON"
+        } else {
+            "MOUSE: OFF"
+        };
+
+        let timer = if self.show_timer {
+            "TIMER: ON"
+        } else {
+            "TIMER: OFF"
+        };
+
+        let pools = if self.pool_report.enabled {
+            "POOLS: ON"
+        } else {
+            "POOLS: OFF"
+        };
+
+        let items = [
+            resolution,
+            damage_numbers,
+            motion,
+            inputs,
+            mouse,
+            timer,
+            pools,
+            "CONTROLS",
+            "BACK",
+        ];
+        match ui.menu(&items, &mut self.menu_focus) {
+            Some(0) => {
+                self.resolution = match self.resolution {
+                    Resolution::Full => Resolution::Half,
+                    Resolution::Half => Resolution::Full,
+                }
+            }
+            Some(1) => self.show_damage_numbers = !self.show_damage_numbers,
+            Some(2) => self.reduce_motion = !self.reduce_motion,
+            Some(3) => self.show_inputs = !self.show_inputs,
+            Some(4) => self.mouse_look = !self.mouse_look,
+            Some(5) => self.show_timer = !self.show_timer,
+            Some(6) => self.pool_report.enabled = !self.pool_report.enabled,
+            Some(CONTROLS_ITEM) => {
+                self.mode = Mode::Controls;
+                self.menu_focus = 0;
+            }
+            Some(_) if self.pause.in_options => {
+                self.save_options();
+                self.return_to_pause();
+            }
+            Some(_) => {
+                self.save_options();
+                self.mode = Mode::Title;
+                self.menu_focus = title::OPTIONS_ITEM;
+            }
+            None => {}
+        }
+
+        ui.space(8);
+        ui.hint("\u{86}\u{87} CHOOSE  \u{80} PICK");
+    }
+}
@@ -0,0 +1,76 @@
+//! The colors the game is drawn in, and effects that shift them.
+
+use core::f32::consts::TAU;
+
+use crate::math::{cosf, lerp};
+
+const PALETTE: *mut [u32; 4] = 0x04 as *mut [u32; 4];
+
+/// A range of colors from lightest to darkest, washed in red.
+const RED: [u32; 4] = [0xf8d8c8, 0xc86c5c, 0x682c28, 0x210807];
+
+/// A palette entry that cycles towards another color and back again, for
+/// things like glowing lights or flickering torches.
+pub struct Cycle {
+    /// Which of the four palette entries changes.
+    pub entry: usize,
+    /// The color the entry cycles towards.
+    pub color: u32,
+    /// How many updates it takes to get there and back.
+    pub period: u32,
+}
+
+/// Run every cycle over `colors`, giving the colors `frame` updates in.
+pub fn cycle(colors: &[u32; 4], cycles: &[Cycle], frame: u32) -> [u32; 4] {
+    let mut cycled = *colors;
+
+    for cycle in cycles {
+        let phase = (frame % cycle.period) as f32 / cycle.period as f32;
+        // ease there and back, rather than snapping back at the end.
+        let amount = (1.0 - cosf(phase * TAU)) / 2.0;
+        cycled[cycle.entry] = blend(colors[cycle.entry], cycle.color, amount);
+    }
+
+    cycled
+}
+
+/// Blend every color in `from` towards the same entry in `to`, by `amount`
+/// from 0.0 (all `from`) to 1.0 (all `to`).
+pub fn mix(from: &[u32; 4], to: &[u32; 4], amount: f32) -> [u32; 4] {
+    let mut mixed = *from;
+
+    for (idx, color) in mixed.iter_mut().enumerate() {
+        *color = blend(from[idx], to[idx], amount);
+    }
+
+    mixed
+}
+
+/// Set the palette for this frame, starting from `colors`.
+///
+/// `red` blends every color towards its red counterpart, and `fade` then
+/// blends everything towards the lightest color, both running from 0.0
+/// (unchanged) to 1.0 (completely blended).
+pub fn set(colors: &[u32; 4], red: f32, fade: f32) {
+    let mut palette = [0; 4];
+
+    for (idx, color) in palette.iter_mut().enumerate() {
+        let tinted = blend(colors[idx], RED[idx], red);
+        *color = blend(tinted, blend(colors[0], RED[0], red), fade);
+    }
+
+    unsafe { *PALETTE = palette };
+}
+
+/// Blend between two 0xRRGGBB colors, one channel at a time.
+fn blend(from: u32, to: u32, amount: f32) -> u32 {
+    let mut blended = 0;
+
+    for shift in [0, 8, 16] {
+        let from = ((from >> shift) & 0xff) as f32;
+        let to = ((to >> shift) & 0xff) as f32;
+        blended |= (lerp(from, to, amount) as u32) << shift;
+    }
+
+    blended
+}
@@ -0,0 +1,116 @@
+//! Walls that only fill part of their cell from the floor to the ceiling:
+//! low walls hanging down from the ceiling, and hurdles standing on the
+//! floor.
+//!
+//! Rays carry on past them, since there's more of the level to see under
+//! or over them, so they're found by following each ray through the grid a
+//! second time, and drawn over whatever the ray hit behind them.
+
+use crate::{
+    map::{partial_wall, GridWalk, WorldMap},
+    math::{cosf, floorf, sinf},
+    Side, State,
+};
+
+/// The most runs of partial walls drawn along a single ray.
+const MAX_SPANS: usize = 4;
+/// The most cells a ray is followed through while looking for partial walls.
+const MAX_STEPS: usize = 32;
+
+/// A run of the same kind of partial wall in a row along a ray, with how far
+/// it is from the camera plane to where the ray goes into them and comes
+/// back out.
+#[derive(Clone, Copy)]
+pub struct PartialWallSpan {
+    pub near: f32,
+    pub far: f32,
+    /// Which kind of grid line the face of the first wall is on.
+    pub side: Side,
+    /// How far up from the floor the walls' bottom and top edges are.
+    pub bottom: f32,
+    pub top: f32,
+}
+
+/// The runs of partial walls along a ray, from nearest to furthest.
+pub struct PartialWallSpans {
+    spans: [Option<PartialWallSpan>; MAX_SPANS],
+}
+
+impl PartialWallSpans {
+    pub const fn new() -> Self {
+        Self {
+            spans: [None; MAX_SPANS],
+        }
+    }
+
+    /// Every run of partial walls, from nearest to furthest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &PartialWallSpan> {
+        self.spans.iter().flatten()
+    }
+
+    /// Add a run of partial walls behind all the others, if there's room.
+    fn push(&mut self, span: PartialWallSpan) {
+        if let Some(slot) = self.spans.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(span);
+        }
+    }
+}
+
+impl State {
+    /// Every run of partial walls looking along `angle`, up to `limit` away
+    /// from the camera plane.
+    pub fn partial_wall_spans(&self, angle: f32, limit: f32) -> PartialWallSpans {
+        let (dir_x, dir_y) = (cosf(angle), -sinf(angle));
+        let correction = cosf(angle - self.player_angle);
+        let (x, y) = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+
+        // a player crouching under a low wall, or standing on a hurdle, is
+        // already in a run of them, so there's no face to it.
+        let mut spans = PartialWallSpans::new();
+        let mut open = partial_wall(self.map.tile_at(x, y)).map(|(bottom, top)| PartialWallSpan {
+            near: 0.0,
+            far: 0.0,
+            side: Side::Horizontal,
+            bottom,
+            top,
+        });
+
+        let cells = GridWalk::new((self.player_x, self.player_y), (dir_x, dir_y));
+        for ((x, y), along, side) in cells.take(MAX_STEPS) {
+            // a run ends wherever the kind of wall changes.
+            let depth = (along * correction).min(limit);
+            let wall = partial_wall(self.map.tile_at(x, y));
+            if let Some(mut span) = open {
+                if wall != Some((span.bottom, span.top)) {
+                    span.far = depth;
+                    spans.push(span);
+                    open = None;
+                }
+            }
+            if let (None, Some((bottom, top))) = (open, wall) {
+                if depth < limit {
+                    open = Some(PartialWallSpan {
+                        near: depth,
+                        far: depth,
+                        side,
+                        bottom,
+                        top,
+                    });
+                }
+            }
+
+            if depth >= limit {
+                break;
+            }
+        }
+
+        // a run still going at the limit ends there, against whatever the
+        // ray hit.
+        if let Some(mut span) = open {
+            span.far = limit;
+            spans.push(span);
+        }
+
+        spans
+    }
+}
@@ -0,0 +1,136 @@
+//! Pausing the game, with an action of its own in the
+//! [`controls`](crate::controls) so that it doesn't get in the way of what
+//! the other buttons do.
+//!
+//! The game stops where it is, with the last frame left on the screen and
+//! dimmed behind a menu for carrying on, starting the level over, changing
+//! the options, or giving up.
+
+use crate::{
+    controls::BUTTON_PAUSE,
+    framebuffer::{copy_rows, paste_rows, ROW_BYTES, SCREEN_SIZE},
+    ui::Ui,
+    Mode, State,
+};
+
+const SYSTEM_FLAGS: *mut u8 = 0x1f as *mut u8;
+const SYSTEM_PRESERVE_FRAMEBUFFER: u8 = 1;
+
+/// The menu items, in order.
+const RESUME_ITEM: usize = 0;
+const RESTART_ITEM: usize = 1;
+const OPTIONS_ITEM: usize = 2;
+
+/// Every other pixel of a byte, on even and odd rows, so that dimming the
+/// screen leaves a checkerboard of the darkest color over it.
+const DIM_MASKS: [u8; 2] = [0b0011_0011, 0b1100_1100];
+
+pub struct Pause {
+    /// Whether the pause action was held on the last update, so that it only
+    /// pauses when it's first pressed.
+    held: bool,
+    /// Whether the options were opened from the pause menu, so they go back
+    /// to it rather than the title screen.
+    pub in_options: bool,
+    /// Whether the screen was cleared while the game was paused, so the
+    /// frozen view has to be drawn again.
+    redraw: bool,
+}
+
+impl Pause {
+    pub const fn new() -> Self {
+        Self {
+            held: false,
+            in_options: false,
+            redraw: false,
+        }
+    }
+}
+
+/// Darken everything on the screen, for the menu to stand out from.
+///
+/// Doing this again to a screen that's already been dimmed leaves it as it
+/// is, so the frozen frame can be dimmed on every update.
+fn dim_screen() {
+    let mut row = [0; ROW_BYTES];
+    for y in 0..SCREEN_SIZE {
+        copy_rows(y, &mut row);
+        for byte in row.iter_mut() {
+            *byte |= DIM_MASKS[y % 2];
+        }
+        paste_rows(y, &row);
+    }
+}
+
+impl State {
+    /// Pause the game when the pause action is pressed, given the buttons
+    /// held on the gamepad after they've been mapped by the controls.
+    pub fn update_pause_button(&mut self, gamepad: u8) {
+        let held = gamepad & BUTTON_PAUSE != 0;
+        if held && !self.pause.held {
+            self.mode = Mode::Paused;
+            self.menu_focus = RESUME_ITEM;
+        }
+        self.pause.held = held;
+    }
+
+    /// Show the pause menu over the frozen frame, until the player picks
+    /// something from it.
+    pub fn update_paused(&mut self, pressed: u8) {
+        if self.pause.redraw {
+            self.cast_view();
+            self.draw_view();
+            self.pause.redraw = false;
+        }
+        dim_screen();
+
+        let mut ui = Ui::new(24, 40, pressed);
+        let mut dialog = ui.dialog(112, 72);
+        dialog.label("PAUSED");
+        dialog.space(4);
+
+        // a time attack run can only be started over on a continue.
+        let restart = match self.out_of_continues() {
+            true => "NO CONTINUES",
+            false => "RESTART",
+        };
+        let items = ["RESUME", restart, "OPTIONS", "QUIT"];
+        match dialog.menu(&items, &mut self.menu_focus) {
+            Some(RESUME_ITEM) => self.resume(),
+            Some(RESTART_ITEM) if self.out_of_continues() => {}
+            Some(RESTART_ITEM) => self.transition_to(Mode::Playing),
+            Some(OPTIONS_ITEM) => {
+                self.pause.in_options = true;
+                self.mode = Mode::Options;
+                self.menu_focus = 0;
+            }
+            Some(_) => self.transition_to(Mode::Title),
+            None => {}
+        }
+    }
+
+    /// Carry on playing from where the game was paused.
+    fn resume(&mut self) {
+        self.mode = Mode::Playing;
+    }
+
+    /// Go back to the pause menu from the options.
+    pub fn return_to_pause(&mut self) {
+        self.pause.in_options = false;
+        self.pause.redraw = true;
+        self.mode = Mode::Paused;
+        self.menu_focus = OPTIONS_ITEM;
+    }
+
+    /// Keep the screen from being cleared between updates while the game is
+    /// paused, so the frozen frame stays up behind the menu.
+    pub fn preserve_paused_frame(&self) {
+        unsafe {
+            if self.mode == Mode::Paused {
+                *SYSTEM_FLAGS |= SYSTEM_PRESERVE_FRAMEBUFFER;
+            } else {
+                *SYSTEM_FLAGS &= !SYSTEM_PRESERVE_FRAMEBUFFER;
+            }
+        }
+    }
+}
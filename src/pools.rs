@@ -0,0 +1,111 @@
+//! A debug report of how full each of the fixed-size pools gets, so their
+//! sizes can be tuned to what the levels actually need.
+//!
+//! While it's switched on in the options, every pool's usage is shown over
+//! the view along with the most it's held since the level started, and
+//! those high-water marks are traced to the console when the level ends.
+
+use crate::{
+    messages,
+    text::{text, trace, Line},
+    toasts, State, DRAW_COLORS,
+};
+
+/// Each of the pools, as shown in the report.
+const POOLS: [&str; 8] = [
+    "ENTITIES", "SPRITES", "DAMAGE", "DECALS", "SHOTS", "EDITS", "MESSAGES", "TOASTS",
+];
+
+/// Where the report goes on screen, below the time attack clock.
+const REPORT_X: i32 = 4;
+const REPORT_Y: i32 = 26;
+const LINE_HEIGHT: i32 = 9;
+
+/// The most each pool has held since the level started.
+pub struct PoolReport {
+    pub enabled: bool,
+    peaks: [usize; POOLS.len()],
+}
+
+impl PoolReport {
+    pub const fn new() -> Self {
+        Self {
+            enabled: false,
+            peaks: [0; POOLS.len()],
+        }
+    }
+
+    /// Start counting the high-water marks again, for a new level.
+    pub fn reset(&mut self) {
+        self.peaks = [0; POOLS.len()];
+    }
+}
+
+impl State {
+    /// How many of each pool's slots are taken, in the order of [`POOLS`],
+    /// along with how many slots it has.
+    fn pool_usage(&self) -> [(usize, usize); POOLS.len()] {
+        [
+            self.entities.usage(),
+            self.get_sprites().usage(),
+            self.damage_numbers.usage(),
+            self.decals.usage(),
+            self.projectiles.usage(),
+            self.map.edit_usage(),
+            messages::usage(),
+            toasts::usage(),
+        ]
+    }
+
+    /// Raise each pool's high-water mark to how full it is now.
+    pub fn update_pool_report(&mut self) {
+        if !self.pool_report.enabled {
+            return;
+        }
+
+        let usage = self.pool_usage();
+        for (peak, (used, _)) in self.pool_report.peaks.iter_mut().zip(usage) {
+            *peak = (*peak).max(used);
+        }
+    }
+
+    /// Draw how full each pool is, and the most it's held, as `NAME 3/32 5`.
+    pub fn draw_pool_report(&self) {
+        if !self.pool_report.enabled {
+            return;
+        }
+
+        unsafe { *DRAW_COLORS = 0x14 };
+        let usage = self.pool_usage();
+        for (idx, (name, (used, capacity))) in POOLS.iter().zip(usage).enumerate() {
+            let mut line = Line::new();
+            line.push_str(name)
+                .push_str(" ")
+                .push_number(used as u32)
+                .push_str("/")
+                .push_number(capacity as u32)
+                .push_str(" ")
+                .push_number(self.pool_report.peaks[idx] as u32);
+            text(line.as_str(), REPORT_X, REPORT_Y + idx as i32 * LINE_HEIGHT);
+        }
+    }
+
+    /// Write each pool's high-water mark out to the console.
+    pub fn trace_pool_report(&self) {
+        if !self.pool_report.enabled {
+            return;
+        }
+
+        let usage = self.pool_usage();
+        for ((name, (_, capacity)), peak) in POOLS.iter().zip(usage).zip(self.pool_report.peaks) {
+            let mut line = Line::new();
+            line.push_str("PEAK ")
+                .push_str(name)
+                .push_str(" ")
+                .push_number(peak as u32)
+                .push_str("/")
+                .push_number(capacity as u32);
+            trace(line.as_str());
+        }
+    }
+}
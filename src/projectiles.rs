@@ -0,0 +1,231 @@
+//! Shots that fly across the map rather than hitting in an instant: the
+//! player's fireballs, and the bolts bosses throw back at them.
+//!
+//! Holding X charges up a fireball, which is thrown once it's let go of,
+//! as long as it was held long enough and the player has the ammo for it.
+//! Every shot is swept along the path it takes each update, so it can't
+//! skip past anything however fast it's going, and hits the first thing in
+//! its way: a wall, or whoever it was thrown at.
+
+use crate::{
+    entities::BODY_RADIUS,
+    map::{Stature, WorldMap},
+    math::{cosf, distance, floorf, sinf},
+    sfx,
+    sprites::{Sprite, Tint},
+    timestep::DT,
+    State,
+};
+
+/// The most shots that can be in the air at once. Any more are dropped.
+const CAPACITY: usize = 16;
+
+/// How big around a shot is, in map units.
+const RADIUS: f32 = 0.1;
+/// Shots fly at about the height of the player's eyes, so they go over low
+/// walls and under hurdles just like a bullet would.
+const STATURE: Stature = Stature {
+    feet: 0.4,
+    head: 0.6,
+};
+/// How many updates a shot flies for before it fizzles out.
+const LIFETIME: u8 = 180;
+
+/// How many updates X has to be held for to charge up a fireball.
+const CHARGE_FRAMES: u8 = 30;
+/// How many rounds of ammo a fireball takes.
+const FIREBALL_AMMO: u8 = 5;
+/// How fast fireballs fly, in map units per second, and how much damage
+/// they do.
+const FIREBALL_SPEED: f32 = 7.0;
+const FIREBALL_DAMAGE: u8 = 60;
+
+/// How fast enemies' shots fly, in map units per second, and how much
+/// damage they do.
+const ENEMY_SHOT_SPEED: f32 = 4.0;
+const ENEMY_SHOT_DAMAGE: u8 = 10;
+
+/// A ball of fire, floating at about eye height.
+pub const FIREBALL: Sprite = Sprite {
+    width: 8,
+    height: 16,
+    data: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a, 0xa0, 0x29, 0x68, 0x25,
+        0x58, 0x25, 0x58, 0x29, 0x68, 0x0a, 0xa0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ],
+};
+
+/// Who threw a shot, which decides who it can hit.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Owner {
+    Player,
+    Enemy,
+}
+
+/// A shot in flight.
+#[derive(Clone, Copy)]
+pub struct Projectile {
+    pub x: f32,
+    pub y: f32,
+    /// Which way the shot is going and how fast, in map units per second.
+    velocity: (f32, f32),
+    owner: Owner,
+    /// The cell the shot was thrown from, which gets the blame if it kills
+    /// the player.
+    source: (i32, i32),
+    /// How many more updates the shot flies for.
+    frames_left: u8,
+}
+
+impl Projectile {
+    /// The tint the shot is drawn with, picking out enemies' shots in the
+    /// same colors as the bosses that throw them.
+    pub const fn tint(&self) -> Tint {
+        match self.owner {
+            Owner::Player => Tint::Normal,
+            Owner::Enemy => Tint::Team(1),
+        }
+    }
+}
+
+pub struct Projectiles {
+    shots: [Option<Projectile>; CAPACITY],
+}
+
+impl Projectiles {
+    pub const fn new() -> Self {
+        Self {
+            shots: [None; CAPACITY],
+        }
+    }
+
+    /// Throw a shot from (x, y) at `angle` radians anticlockwise from east,
+    /// unless there are already too many in the air.
+    pub fn launch(&mut self, owner: Owner, (x, y): (f32, f32), angle: f32) {
+        let Some(slot) = self.shots.iter_mut().find(|slot| slot.is_none()) else {
+            return;
+        };
+
+        let speed = match owner {
+            Owner::Player => FIREBALL_SPEED,
+            Owner::Enemy => ENEMY_SHOT_SPEED,
+        };
+        *slot = Some(Projectile {
+            x,
+            y,
+            velocity: (cosf(angle) * speed, -sinf(angle) * speed),
+            owner,
+            source: (floorf(x) as i32, floorf(y) as i32),
+            frames_left: LIFETIME,
+        });
+    }
+
+    /// Drop every shot in the air.
+    pub fn clear(&mut self) {
+        self.shots = [None; CAPACITY];
+    }
+
+    /// Every shot in the air.
+    pub fn iter(&self) -> impl Iterator<Item = &Projectile> {
+        self.shots.iter().flatten()
+    }
+
+    /// How many shots are in the air, and how many there's room for.
+    pub fn usage(&self) -> (usize, usize) {
+        (self.iter().count(), CAPACITY)
+    }
+}
+
+/// Check if something at `point` is within `reach` of the path a shot
+/// takes from `from` to `to`.
+fn passes_within(from: (f32, f32), to: (f32, f32), point: (f32, f32), reach: f32) -> bool {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_squared = dx * dx + dy * dy;
+    let along = if length_squared > 0.0 {
+        (((point.0 - from.0) * dx + (point.1 - from.1) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let closest = (from.0 + dx * along, from.1 + dy * along);
+    distance(point.0 - closest.0, point.1 - closest.1) < reach
+}
+
+impl State {
+    /// Charge up a fireball while X is `held`, throwing it once it's let go
+    /// of if it was held for long enough.
+    pub fn charge_fireball(&mut self, held: bool) {
+        if held {
+            self.charge = self.charge.saturating_add(1);
+            if self.charge == CHARGE_FRAMES && self.ammo >= FIREBALL_AMMO {
+                sfx::play(&sfx::FIREBALL_READY);
+            }
+            return;
+        }
+
+        let charged = self.charge >= CHARGE_FRAMES;
+        self.charge = 0;
+        if !charged || self.ammo < FIREBALL_AMMO {
+            return;
+        }
+
+        if !self.mutators.infinite_ammo {
+            self.ammo -= FIREBALL_AMMO;
+        }
+        let (x, y) = (self.player_x, self.player_y);
+        self.projectiles
+            .launch(Owner::Player, (x, y), self.player_angle);
+        sfx::play(&sfx::FIREBALL);
+    }
+
+    /// Sweep every shot along its path, stopping it at the first thing in
+    /// its way.
+    pub fn update_projectiles(&mut self) {
+        let player = (self.player_x, self.player_y);
+
+        for idx in 0..CAPACITY {
+            let Some(mut shot) = self.projectiles.shots[idx] else {
+                continue;
+            };
+
+            let from = (shot.x, shot.y);
+            let step = (shot.velocity.0 * DT, shot.velocity.1 * DT);
+            let slide = self.map.slide(from, step, RADIUS, STATURE);
+            let to = (slide.x, slide.y);
+            let reach = BODY_RADIUS + RADIUS;
+
+            // anyone in the way is hit before the wall behind them.
+            let hit = match shot.owner {
+                Owner::Player => {
+                    let target = self.entities.indexed().find_map(|(target, entity)| {
+                        let in_way = passes_within(from, to, (entity.x, entity.y), reach);
+                        (entity.health.is_some() && in_way).then_some(target)
+                    });
+                    if let Some(target) = target {
+                        self.damage_entity(target, FIREBALL_DAMAGE);
+                    }
+                    target.is_some()
+                }
+                Owner::Enemy if passes_within(from, to, player, reach) => {
+                    self.hurt(ENEMY_SHOT_DAMAGE, shot.source);
+                    true
+                }
+                Owner::Enemy => false,
+            };
+
+            shot.frames_left = shot.frames_left.saturating_sub(1);
+            if hit || slide.stopped_x || slide.stopped_y || shot.frames_left == 0 {
+                if !hit {
+                    // heard from wherever the shot came to a stop.
+                    self.play_sound_at(to.0, to.1, &sfx::FIZZLE);
+                }
+                self.projectiles.shots[idx] = None;
+                continue;
+            }
+
+            (shot.x, shot.y) = to;
+            self.projectiles.shots[idx] = Some(shot);
+        }
+    }
+}
@@ -0,0 +1,440 @@
+//! Drawing the world from the player's point of view.
+
+use crate::{
+    angle,
+    floors::floor_color,
+    framebuffer::{ColumnStage, PIXELS_PER_BYTE, SCREEN_SIZE},
+    map::{
+        WorldMap, ACID, BRICK, DOOR_FRAME, EXIT_SWITCH, GATE, LAVA, METAL, MONITOR, RED_DOOR,
+        SCOREBOARD, SKY, SWITCH, SWITCH_THROWN, WATER, YELLOW_DOOR,
+    },
+    math::{cosf, floorf, sinf},
+    partial_walls::{PartialWallSpan, PartialWallSpans},
+    texture::{Slot, Texture},
+    Mode, RayHit, Side, State, FOV, HALF_FOV, TAU,
+};
+
+/// The screen row level with the camera.
+pub const HORIZON: i32 = 80;
+
+/// The color of the ceiling indoors, and of the floor everywhere.
+const BACKGROUND: u8 = 0;
+
+/// The color of the seams, mortar and rivets drawn over patterned walls.
+const SEAM: u8 = 3;
+
+/// How many texels across and down a wall's pattern is.
+const WALL_TEXELS: i32 = 16;
+/// Walls drawn shorter than this many pixels have their pattern sampled at
+/// half resolution, since most of its texels would be skipped over anyway.
+const DETAIL_HEIGHT: i32 = 2 * WALL_TEXELS;
+
+/// The color of the distant mountains and the haze in the skybox.
+const MOUNTAINS: u8 = 1;
+
+/// Heights of the mountains on the skybox, in pixels above the horizon,
+/// spread evenly around the whole horizon.
+const SKYLINE: [u8; 16] = [6, 9, 14, 11, 7, 4, 5, 10, 16, 12, 8, 9, 6, 3, 4, 5];
+
+impl State {
+    /// The texture drawn at runtime for a kind of wall, if it has one.
+    fn wall_texture(&self, tile: u8) -> Option<&Texture> {
+        match (tile, self.mode) {
+            (SCOREBOARD, Mode::Versus) => Some(self.textures.get(Slot::Scoreboard)),
+            (MONITOR, _) => Some(self.textures.get(Slot::Monitor)),
+            _ => None,
+        }
+    }
+
+    /// Draw the world as seen by the rays from the last call to [`State::cast_view`].
+    pub fn draw_view(&self) {
+        self.draw_view_on(ColumnStage::new());
+    }
+
+    /// Draw the world like [`State::draw_view`] does, but only onto the rows
+    /// of the screen that `stage` writes out.
+    pub fn draw_view_on(&self, mut stage: ColumnStage) {
+        let sprites = self.get_sprites();
+        let angle_step = self.resolution.angle_step();
+        let has_sky = self.map.has_sky();
+        let has_marked_floors = self.map.has_marked_floors();
+        let has_floor_patterns = !self.level().map.floors.is_empty();
+        let has_partial_walls = self.map.has_partial_walls();
+        let eye_height = self.eye_height();
+        let wall_height = self.wall_height();
+        let fog_distance = self.fog_distance();
+        let indoors = has_sky && !self.point_is_outdoor(self.player_x, self.player_y);
+
+        // go through each group of four columns on screen, stage the walls in
+        // their centers and any sprites in front of them, then write the whole
+        // group to the framebuffer at once.
+        for group in (0..SCREEN_SIZE).step_by(PIXELS_PER_BYTE) {
+            for lane in 0..PIXELS_PER_BYTE {
+                let x = group + lane;
+
+                let ray = self.ray_at(x);
+                let hit = &self.view[ray];
+                let texture = self.wall_texture(hit.tile_id);
+                draw_wall_column(&mut stage, lane, x, hit, texture, eye_height, fog_distance);
+                if hit.distance <= fog_distance {
+                    let top = HORIZON - height_above_horizon(hit, eye_height);
+                    self.decals.draw_column(stage.column(lane), hit, top);
+                }
+
+                let angle = self.player_angle + HALF_FOV - ray as f32 * angle_step;
+                if has_sky {
+                    let top = wall_top(hit, eye_height, fog_distance) as usize;
+                    self.draw_ceiling(&mut stage.column(lane)[..top], angle, eye_height);
+                }
+                if has_marked_floors || has_floor_patterns {
+                    let bottom = wall_bottom(hit, eye_height, fog_distance) as usize;
+                    let floor = &mut stage.column(lane)[bottom..];
+                    self.draw_floor(floor, bottom, x, angle, eye_height);
+                }
+                if indoors {
+                    let limit = hit.distance.min(fog_distance);
+                    if let Some(depth) = self.skylight_depth(angle, limit) {
+                        let height = wall_height / depth;
+                        draw_light_shaft(stage.column(lane), x, height, eye_height);
+                    }
+                }
+
+                let partial_walls = if has_partial_walls {
+                    self.partial_wall_spans(angle, hit.distance.min(fog_distance))
+                } else {
+                    PartialWallSpans::new()
+                };
+
+                // sprites are drawn between the partial walls in front of them
+                // and the ones behind, working from the back forwards.
+                let mut behind = hit.distance;
+                for span in partial_walls.iter().rev() {
+                    sprites.draw_column(
+                        &mut stage,
+                        lane,
+                        x,
+                        span.near..behind,
+                        eye_height,
+                        &self.textures,
+                    );
+                    draw_partial_wall(stage.column(lane), x, span, wall_height, eye_height);
+                    behind = span.near;
+                }
+                sprites.draw_column(&mut stage, lane, x, 0.0..behind, eye_height, &self.textures);
+            }
+
+            stage.blit(group);
+        }
+    }
+
+    /// Redraw the floor below a wall in screen column `x`, marking out any
+    /// floors that hurt and painting any patterns across the rest. `floor`
+    /// starts at screen row `top`.
+    fn draw_floor(&self, floor: &mut [u8], top: usize, x: usize, angle: f32, eye_height: f32) {
+        let (dir_x, dir_y) = (cosf(angle), -sinf(angle));
+        // undo the fisheye correction, since we're walking along the ray itself.
+        let correction = cosf(angle - self.player_angle);
+        let wall_height = self.wall_height();
+
+        for (row, pixel) in floor.iter_mut().enumerate() {
+            let y = top + row;
+            let rows_below_horizon = (y as i32 - HORIZON) as f32 + 0.5;
+
+            // the floor at this row is as far away as a wall whose bottom
+            // edge is drawn on this row would be.
+            let distance = wall_height * eye_height / rows_below_horizon / correction;
+            let map_x = self.player_x + dir_x * distance;
+            let map_y = self.player_y + dir_y * distance;
+
+            let tile = self.map.tile_at(floorf(map_x) as i32, floorf(map_y) as i32);
+            // sunlight washes out half of any pattern on floors open to the sky.
+            let color = marked_floor_color(tile, x, y).or_else(|| {
+                floor_color(self.level().map.floors, map_x, map_y)
+                    .filter(|_| tile != SKY || (x + y) % 2 == 0)
+            });
+            if let Some(color) = color {
+                *pixel = color;
+            }
+        }
+    }
+
+    /// Redraw the ceiling above a wall, showing the skybox wherever the ceiling
+    /// would be over a cell that's open to the sky.
+    fn draw_ceiling(&self, ceiling: &mut [u8], angle: f32, eye_height: f32) {
+        let (dir_x, dir_y) = (cosf(angle), -sinf(angle));
+        // undo the fisheye correction, since we're walking along the ray itself.
+        let correction = cosf(angle - self.player_angle);
+        let skyline = skyline_height(angle);
+        let wall_height = self.wall_height();
+
+        // the skybox is infinitely far away, so it only turns with the player.
+        let sky_x = (angle::turns(angle) * SCREEN_SIZE as f32 * TAU / FOV) as usize;
+
+        for (y, pixel) in ceiling.iter_mut().enumerate() {
+            let rows_above_horizon = (HORIZON - y as i32) as f32 - 0.5;
+
+            // the ceiling at this row is as far away as a wall whose top edge
+            // is drawn on this row would be.
+            let distance = wall_height * (1.0 - eye_height) / rows_above_horizon / correction;
+            let map_x = self.player_x + dir_x * distance;
+            let map_y = self.player_y + dir_y * distance;
+
+            *pixel = if !self.point_is_outdoor(map_x, map_y) {
+                BACKGROUND
+            } else if rows_above_horizon < skyline {
+                MOUNTAINS
+            } else {
+                sky_haze(sky_x, y)
+            };
+        }
+    }
+}
+
+/// How many pixels of a wall are drawn above the horizon, with the camera
+/// `eye_height` of the way up the wall.
+fn height_above_horizon(hit: &RayHit, eye_height: f32) -> i32 {
+    (hit.height as f32 * (1.0 - eye_height)) as i32
+}
+
+/// The screen row just below the bottom of a wall, which may be off screen,
+/// or the horizon if it's further away than `fog_distance`.
+fn wall_bottom(hit: &RayHit, eye_height: f32, fog_distance: f32) -> i32 {
+    if hit.distance > fog_distance {
+        HORIZON
+    } else {
+        let top = HORIZON - height_above_horizon(hit, eye_height);
+        (top + hit.height).clamp(HORIZON, SCREEN_SIZE as i32)
+    }
+}
+
+/// The screen row where the top of a wall is drawn, which may be off screen,
+/// or the horizon if it's further away than `fog_distance`.
+fn wall_top(hit: &RayHit, eye_height: f32, fog_distance: f32) -> i32 {
+    if hit.distance > fog_distance {
+        HORIZON
+    } else {
+        (HORIZON - height_above_horizon(hit, eye_height)).clamp(0, HORIZON)
+    }
+}
+
+/// Fill a staged screen column with a wall of the given height, level with
+/// the camera at `eye_height`, with the background above and below it. Walls
+/// further away than `fog_distance` are lost in the fog.
+///
+/// The wall's color depends on which way it faces and whether it's outdoors,
+/// and its kind of tile decides the pattern drawn over the top, unless it has
+/// a `texture` drawn at runtime instead.
+fn draw_wall_column(
+    stage: &mut ColumnStage,
+    lane: usize,
+    x: usize,
+    hit: &RayHit,
+    texture: Option<&Texture>,
+    eye_height: f32,
+    fog_distance: f32,
+) {
+    // walls open to the sky are lit up a step brighter than walls indoors,
+    // with their brighter side dithered towards the lightest color.
+    let (color, dither) = match (hit.side, hit.outdoor) {
+        (Side::Horizontal, false) => (2, 2),
+        (Side::Vertical, false) => (1, 1),
+        (Side::Horizontal, true) => (1, 1),
+        (Side::Vertical, true) => (1, 0),
+        // slanted faces are shaded halfway between the two.
+        (Side::Diagonal, false) => (2, 1),
+        (Side::Diagonal, true) => (1, 0),
+    };
+    let tile = hit.tile_id;
+    let patterned = is_patterned(tile) || texture.is_some();
+    let top = HORIZON - height_above_horizon(hit, eye_height);
+    let bottom = top + hit.height;
+
+    // walls in the fog and walls filling the whole screen are just one color.
+    if hit.distance > fog_distance {
+        stage.fill(lane, BACKGROUND);
+        return;
+    } else if top <= 0 && bottom >= SCREEN_SIZE as i32 && color == dither && !patterned {
+        stage.fill(lane, color);
+        return;
+    }
+
+    let column = stage.column(lane);
+    let texel_top = top;
+    let top = top.clamp(0, SCREEN_SIZE as i32) as usize;
+    let bottom = bottom.clamp(0, SCREEN_SIZE as i32) as usize;
+
+    column[..top].fill(BACKGROUND);
+    if color == dither {
+        column[top..bottom].fill(color);
+    } else {
+        for (y, pixel) in column.iter_mut().enumerate().take(bottom).skip(top) {
+            *pixel = if (x + y) % 2 == 0 { color } else { dither };
+        }
+    }
+    column[bottom..].fill(BACKGROUND);
+
+    if patterned {
+        // far walls only use every other texel, which keeps thin seams from
+        // flickering in and out as the player moves.
+        let step = if hit.height < DETAIL_HEIGHT { 2 } else { 1 };
+        let u = (hit.tex_u * WALL_TEXELS as f32) as i32 / step * step;
+
+        // runs of pixels share a texel, which only needs checking once.
+        let mut last = None;
+        for (y, pixel) in column.iter_mut().enumerate().take(bottom).skip(top) {
+            let v = (y as i32 - texel_top) * WALL_TEXELS / hit.height / step * step;
+            let ink = match last {
+                Some((last_v, ink)) if last_v == v => ink,
+                _ => match texture {
+                    // a texture's transparent pixels leave the wall showing.
+                    Some(texture) => {
+                        Some(texture.texel(u as usize, v as usize)).filter(|&c| c != 0)
+                    }
+                    None => is_seam(tile, u, v).then_some(SEAM),
+                },
+            };
+            last = Some((v, ink));
+
+            if let Some(ink) = ink {
+                *pixel = ink;
+            }
+        }
+    }
+}
+
+/// Draw a run of partial walls over screen column `x`: the face of the
+/// first, and the underside or top of the run behind it, which only shows
+/// when the camera is below or above the walls.
+fn draw_partial_wall(
+    column: &mut [u8],
+    x: usize,
+    span: &PartialWallSpan,
+    wall_height: f32,
+    eye_height: f32,
+) {
+    let color = match span.side {
+        Side::Horizontal => 2,
+        _ => 1,
+    };
+    // the screen row that a point `height` up from the floor and `depth`
+    // away is drawn on, which for a player already inside the run is off
+    // the top or bottom of the screen.
+    let row = |height: f32, depth: f32| {
+        let row = HORIZON as f32 - wall_height / depth * (height - eye_height);
+        row.clamp(0.0, SCREEN_SIZE as f32) as usize
+    };
+
+    let face_top = row(span.top, span.near);
+    let face_bottom = row(span.bottom, span.near);
+    let (cap_top, cap_bottom) = if eye_height < span.bottom {
+        (face_bottom, row(span.bottom, span.far))
+    } else if eye_height > span.top {
+        (row(span.top, span.far), face_top)
+    } else {
+        (0, 0)
+    };
+
+    for (y, pixel) in column.iter_mut().enumerate().take(cap_bottom).skip(cap_top) {
+        *pixel = if (x + y) % 2 == 0 { SEAM } else { BACKGROUND };
+    }
+    column[face_top..face_bottom].fill(color);
+}
+
+/// Lighten a sparse, slanted dither over the part of screen column `x` where
+/// a shaft of sunlight `height` pixels tall would be.
+fn draw_light_shaft(column: &mut [u8], x: usize, height: f32, eye_height: f32) {
+    let top = HORIZON as f32 - height * (1.0 - eye_height);
+    let bottom = (top + height).clamp(0.0, SCREEN_SIZE as f32) as usize;
+    let top = top.clamp(0.0, SCREEN_SIZE as f32) as usize;
+
+    for (y, pixel) in column.iter_mut().enumerate().take(bottom).skip(top) {
+        if y % 2 == 0 && (x + y / 2) % 4 == 0 {
+            *pixel = pixel.saturating_sub(1);
+        }
+    }
+}
+
+/// The color of a floor at screen pixel (x, y), if it's a floor that's drawn
+/// in its own colors, like one that hurts.
+fn marked_floor_color(tile: u8, x: usize, y: usize) -> Option<u8> {
+    match tile {
+        // a bright checkerboard, glowing up from below.
+        LAVA => Some(if (x + y) % 2 == 0 { 2 } else { 1 }),
+        // a sheen of stripes across the surface.
+        ACID => Some(if y % 2 == 0 { 1 } else { BACKGROUND }),
+        // ripples across the surface, in light and shade.
+        WATER => Some(if (x + y / 2 * 3) % 6 == 0 { 2 } else { 1 }),
+        _ => None,
+    }
+}
+
+/// Check if a kind of wall has a pattern drawn over it.
+const fn is_patterned(tile: u8) -> bool {
+    matches!(
+        tile,
+        BRICK
+            | METAL
+            | DOOR_FRAME
+            | RED_DOOR
+            | YELLOW_DOOR
+            | EXIT_SWITCH
+            | SWITCH
+            | SWITCH_THROWN
+            | GATE
+    )
+}
+
+/// Check if the texel at (u, v) of a patterned wall is part of its seams.
+fn is_seam(tile: u8, u: i32, v: i32) -> bool {
+    match tile {
+        // courses of bricks, with every other course offset by half a brick.
+        BRICK => v % 4 == 0 || (u + (v / 4 % 2) * 4) % 8 == 0,
+        // a panel with a rivet in each corner.
+        METAL => u == 0 || u == 15 || ((u == 2 || u == 13) && (v == 2 || v == 13)),
+        // thick posts down either side.
+        DOOR_FRAME => !(3..13).contains(&u),
+        // planks held together by a pair of crossbars.
+        RED_DOOR => u % 5 == 0 || v == 3 || v == 12,
+        // planks with a lock plate in the middle.
+        YELLOW_DOOR => u % 5 == 0 || ((6..10).contains(&u) && (6..10).contains(&v)),
+        // a lever in a slot, on a plate bolted to the wall.
+        EXIT_SWITCH => {
+            let plate = (4..12).contains(&u) && (2..14).contains(&v);
+            let slot = (7..9).contains(&u) && (4..12).contains(&v);
+            let handle = (5..11).contains(&u) && (4..6).contains(&v);
+            (plate && (u == 4 || u == 11 || v == 2 || v == 13)) || slot || handle
+        }
+        // a smaller lever, flipped up or down.
+        SWITCH | SWITCH_THROWN => {
+            let plate = (5..11).contains(&u) && (4..12).contains(&v);
+            let slot = (7..9).contains(&u) && (6..10).contains(&v);
+            let handle_row = if tile == SWITCH { 6 } else { 9 };
+            let handle = (6..10).contains(&u) && v == handle_row;
+            (plate && (u == 5 || u == 10 || v == 4 || v == 11)) || slot || handle
+        }
+        // bars held in a frame.
+        GATE => u % 3 == 0 || v == 0 || v == 15 || v == 3 || v == 12,
+        _ => false,
+    }
+}
+
+/// A sparse dither across the open sky, so it stands apart from indoor ceilings.
+fn sky_haze(sky_x: usize, y: usize) -> u8 {
+    if y % 2 == 0 && (sky_x + y) % 4 == 0 {
+        MOUNTAINS
+    } else {
+        BACKGROUND
+    }
+}
+
+/// How far above the horizon the skybox's mountains reach in a direction.
+fn skyline_height(angle: f32) -> f32 {
+    // wrap the angle around to a position along the skyline, then blend
+    // between the two nearest heights.
+    let position = angle::turns(angle) * SKYLINE.len() as f32;
+    let idx = position as usize % SKYLINE.len();
+    let next = (idx + 1) % SKYLINE.len();
+    let blend = position - floorf(position);
+
+    SKYLINE[idx] as f32 * (1.0 - blend) + SKYLINE[next] as f32 * blend
+}
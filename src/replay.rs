@@ -0,0 +1,50 @@
+//! A record of where the player has been over the last few seconds.
+
+/// How many updates are kept, which is about two seconds' worth.
+const CAPACITY: usize = 120;
+
+/// Where the player was on one update.
+#[derive(Clone, Copy)]
+pub struct Snapshot {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A ring buffer of snapshots, overwriting the oldest once it's full.
+pub struct Replay {
+    snapshots: [Snapshot; CAPACITY],
+    /// Where the next snapshot goes.
+    next: usize,
+    len: usize,
+}
+
+impl Replay {
+    pub const fn new() -> Self {
+        Self {
+            snapshots: [Snapshot { x: 0.0, y: 0.0 }; CAPACITY],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Remember where the player is on this update.
+    pub fn record(&mut self, snapshot: Snapshot) {
+        self.snapshots[self.next] = snapshot;
+        self.next = (self.next + 1) % CAPACITY;
+        self.len = (self.len + 1).min(CAPACITY);
+    }
+
+    /// Forget everything recorded so far.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Get a snapshot, counting from the oldest one still kept.
+    pub fn get(&self, idx: usize) -> Option<Snapshot> {
+        (idx < self.len).then(|| self.snapshots[(self.next + CAPACITY - self.len + idx) % CAPACITY])
+    }
+}
@@ -0,0 +1,110 @@
+//! Resetting the whole game, by holding X, Z, up and down together for a
+//! second, without waiting on the host to reload the cartridge. A kiosk or
+//! arcade cabinet left in a strange state can always be brought back this way,
+//! and a kiosk does it by itself when a game is left alone for too long.
+//!
+//! Everything the game keeps between updates is in one of a handful of
+//! statics, and every one of them is put back how it was at the start: the
+//! game's [`State`], the messages and toasts waiting to be shown, where the
+//! mouse was last seen, and whatever the sound channels are still playing.
+//! Anything saved to the disk is loaded again, just like on a real start.
+
+use crate::{
+    channels, controls::Controls, difficulty::Difficulty, footsteps::Footsteps, gallery::Gallery,
+    kiosk::Kiosk, messages, mouse, mutators::Mutators, noclip::Noclip, pause::Pause,
+    pools::PoolReport, start, texture::Textures, toasts, versus::Versus, Mode, RayHit, Resolution,
+    State, BUTTON_1, BUTTON_2, BUTTON_DOWN, BUTTON_UP, LEVELS, STATE,
+};
+
+/// The buttons to hold together to reset the game. Walking forward and back
+/// at once gets the player nowhere, so nothing while playing holds them all.
+const RESET_BUTTONS: u8 = BUTTON_1 | BUTTON_2 | BUTTON_UP | BUTTON_DOWN;
+
+/// How many updates the buttons have to be held for, so that a reset can't
+/// happen by accident.
+const HOLD_FRAMES: u16 = 60;
+
+/// How many updates in a row the buttons have been held for.
+static mut HELD_FRAMES: u16 = 0;
+
+/// Keep track of how long the buttons have been held for on the gamepad,
+/// resetting the game once they've been held long enough. Returns `true` if
+/// the game was reset.
+///
+/// The buttons have to be let go of before the game can be reset again.
+pub fn update(gamepad: u8) -> bool {
+    unsafe {
+        HELD_FRAMES = if gamepad & RESET_BUTTONS == RESET_BUTTONS {
+            HELD_FRAMES.saturating_add(1)
+        } else {
+            0
+        };
+
+        if HELD_FRAMES == HOLD_FRAMES {
+            soft_reset();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Put every static back how it was when the cartridge started.
+///
+/// The gamepad as it was last seen is kept, so that the buttons still held
+/// from the reset aren't taken as being pressed again on the title screen.
+pub unsafe fn soft_reset() {
+    STATE.reset();
+    messages::clear();
+    toasts::clear();
+    mouse::forget();
+    channels::silence();
+    start();
+}
+
+impl State {
+    /// Put everything back how [`State::new`] has it, in place.
+    ///
+    /// A whole new [`State`] wouldn't fit on the stack, and copying one in
+    /// from a constant would mean keeping a second copy of it in the
+    /// cartridge, so each part is put back where it is instead.
+    fn reset(&mut self) {
+        self.mode = Mode::Title;
+        self.transition = None;
+        self.menu_focus = 0;
+        self.title_frames = 0;
+        self.footsteps = Footsteps::new();
+        self.score = 0;
+        self.hurt_frames = 0;
+        self.invulnerable_frames = 0;
+        self.wading = false;
+        self.show_automap = false;
+        self.noclip = Noclip::new();
+        self.best_frames = [[None; LEVELS.len()]; 2];
+        self.resolution = Resolution::DEFAULT;
+        self.codex = 0;
+        self.show_damage_numbers = false;
+        self.show_inputs = false;
+        self.show_timer = false;
+        self.pool_report = PoolReport::new();
+        self.mouse_look = false;
+        self.controls = Controls::new();
+        self.reduce_motion = false;
+        self.mutators = Mutators::NONE;
+        self.difficulty = Difficulty::Normal;
+        self.unlocked = Mutators::UNLOCKED_AT_START;
+        self.shuffle_seed = 0;
+        self.gallery = Gallery::new();
+        self.versus = Versus::new();
+        self.textures = Textures::new();
+        self.time_attack = None;
+        self.best_time_attack = None;
+        self.kiosk = Kiosk::new();
+        self.pause = Pause::new();
+        self.view.fill(RayHit::NONE);
+
+        // everything that belongs to a level is put back by loading the
+        // first one again, now that the mutators and difficulty are.
+        self.load_level(0);
+    }
+}
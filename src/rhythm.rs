@@ -0,0 +1,99 @@
+//! A steady beat for the rhythm mutator to play along to.
+//!
+//! With the rhythm mutator on, the clock ticks along with every update of the
+//! level, and on every beat it calls each of the [`LISTENERS`], telling them
+//! whether the beat is the first of a bar. Gameplay keeps time by adding a
+//! listener there, rather than by counting updates for itself: a metronome
+//! clicks along, switches and doors the player used off the beat are worked
+//! on it, and bosses throw their shots at the start of every bar. Anything
+//! that has to wait for the beat instead, like an enemy's wound up swing,
+//! checks [`State::off_beat`].
+
+use crate::{sfx, State};
+
+/// How many updates there are in each beat, for 120 beats a minute.
+const BEAT_FRAMES: u32 = 30;
+/// How many beats there are in each bar.
+const BEATS_PER_BAR: u32 = 4;
+
+/// Something that keeps time with the beat, told whether the beat is the
+/// first of a bar.
+pub type Listener = fn(&mut State, bool);
+
+/// Everything that keeps time with the beat, called in this order on every
+/// beat.
+const LISTENERS: [Listener; 3] = [
+    State::click_metronome,
+    State::work_held_cell,
+    State::throw_boss_shots,
+];
+
+pub struct Rhythm {
+    /// How many updates the level has been keeping time for.
+    frames: u32,
+    /// The cell of a switch or door the player used off the beat, waiting
+    /// to be worked on the next one.
+    held: Option<(i32, i32)>,
+}
+
+impl Rhythm {
+    pub const fn new() -> Self {
+        Self {
+            frames: 0,
+            held: None,
+        }
+    }
+
+    /// Start keeping time over from the top of a bar.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Whether this update falls on a beat.
+    const fn on_beat(&self) -> bool {
+        self.frames % BEAT_FRAMES == 0
+    }
+
+    /// Hold the switch or door in `cell` until the next beat.
+    pub fn hold(&mut self, cell: (i32, i32)) {
+        self.held = Some(cell);
+    }
+}
+
+impl State {
+    /// Whether the rhythm mutator is holding things up until the next beat.
+    pub fn off_beat(&self) -> bool {
+        self.mutators.rhythm && !self.rhythm.on_beat()
+    }
+
+    /// Move the clock on by an update, calling every one of the
+    /// [`LISTENERS`] when a beat comes around.
+    pub fn update_rhythm(&mut self) {
+        if !self.mutators.rhythm {
+            return;
+        }
+
+        self.rhythm.frames = self.rhythm.frames.wrapping_add(1);
+        if self.rhythm.on_beat() {
+            let bar = self.rhythm.frames % (BEAT_FRAMES * BEATS_PER_BAR) == 0;
+            for listener in LISTENERS {
+                listener(self, bar);
+            }
+        }
+    }
+
+    /// Click the metronome, louder on the first beat of a bar.
+    fn click_metronome(&mut self, bar: bool) {
+        sfx::play(if bar { &sfx::BAR } else { &sfx::BEAT });
+    }
+
+    /// Work the switch or door the player used since the last beat, if they
+    /// used one.
+    fn work_held_cell(&mut self, _bar: bool) {
+        if let Some((x, y)) = self.rhythm.held.take() {
+            if !self.throw_switch(x, y) {
+                self.unlock_door(x, y);
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! A small random number generator that gives the same numbers every time
+//! it's started from the same seed.
+//!
+//! Nothing random is ever seeded from the clock, so anything built from a seed
+//! (like a random dungeon) can be made again exactly by reusing it.
+
+/// What a generator seeded with zero starts from instead, since xorshift
+/// gets stuck on zero.
+const ZERO_SEED_STATE: u32 = 0x9e37_79b9;
+
+/// A xorshift generator, which is tiny and fast but fine for games.
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    /// Start a generator from a seed. Every seed works, including zero, and
+    /// every one but zero gives its own numbers, so seeds next to each other
+    /// (like one random dungeon and the next) never turn out the same.
+    pub const fn new(seed: u32) -> Self {
+        let state = if seed == 0 { ZERO_SEED_STATE } else { seed };
+        Self { state }
+    }
+
+    /// Get the next number, anywhere in the range of a `u32`.
+    pub fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state
+    }
+
+    /// Get a number from 0 up to but not including `limit`, which must not be
+    /// zero.
+    pub fn below(&mut self, limit: usize) -> usize {
+        self.next_u32() as usize % limit
+    }
+}
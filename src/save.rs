@@ -0,0 +1,208 @@
+//! Saving the game to the disk: the options, the codex entries and mutators
+//! unlocked, and how far the player has got, to carry on from the title screen next time.
+//!
+//! Progress is saved each time a new level is started, so continuing starts
+//! that level over with what the player had going into it. The save starts
+//! with a version number and a checksum, so one from an older build, or one
+//! that's been mangled, is thrown away rather than read as nonsense.
+
+use crate::{difficulty::Difficulty, disk, health::MAX_HEALTH, levels::LEVELS, Resolution, State};
+
+/// The first byte of a save, so that a disk that's never been written isn't
+/// read as one.
+const MAGIC: u8 = 0x5a;
+/// Which layout the save is in, to be bumped whenever it changes. Saves in
+/// any other layout are ignored.
+const VERSION: u8 = 4;
+
+/// How many bytes the save takes up on the disk.
+pub const SAVED_SIZE: usize = 16;
+
+/// Where each part of the save goes. The checksum covers everything after
+/// it.
+const CHECKSUM: usize = 2;
+const OPTIONS: usize = 3;
+const HAS_PROGRESS: usize = 4;
+const LEVEL: usize = 5;
+const HEALTH: usize = 6;
+const KEYS: usize = 7;
+const AMMO: usize = 8;
+const SCORE: usize = 9;
+const DIFFICULTY: usize = 13;
+const CODEX: usize = 14;
+const UNLOCKED: usize = 15;
+
+/// Which bit of the options byte each option is kept in.
+const HALF_RES: u8 = 1 << 0;
+const DAMAGE_NUMBERS: u8 = 1 << 1;
+const REDUCE_MOTION: u8 = 1 << 2;
+const INPUTS: u8 = 1 << 3;
+const MOUSE_LOOK: u8 = 1 << 4;
+const TIMER: u8 = 1 << 5;
+const POOLS: u8 = 1 << 6;
+
+/// Which bit of the unlocked byte each mutator that has to be unlocked is
+/// kept in.
+const BIG_ENEMIES: u8 = 1 << 0;
+const PARTY_COLORS: u8 = 1 << 1;
+
+/// How far the player had got when the game was last saved.
+#[derive(Clone, Copy)]
+pub struct Progress {
+    pub level: usize,
+    pub health: u8,
+    pub keys: u8,
+    pub ammo: u8,
+    pub score: u32,
+    pub difficulty: Difficulty,
+}
+
+/// Mix every byte after the checksum together, so that changing any of
+/// them changes it.
+fn checksum(saved: &[u8; SAVED_SIZE]) -> u8 {
+    saved[CHECKSUM + 1..]
+        .iter()
+        .fold(0, |sum: u8, &byte| sum.rotate_left(1) ^ byte)
+}
+
+/// Read the save from the disk, if there's one there that this build can
+/// make sense of.
+fn read() -> Option<[u8; SAVED_SIZE]> {
+    let mut saved = [0; SAVED_SIZE];
+    disk::load(disk::SAVE, &mut saved);
+
+    let valid = saved[0] == MAGIC && saved[1] == VERSION && saved[CHECKSUM] == checksum(&saved);
+    valid.then_some(saved)
+}
+
+/// Write the save to the disk, stamped with the version and checksum.
+fn write(mut saved: [u8; SAVED_SIZE]) {
+    saved[0] = MAGIC;
+    saved[1] = VERSION;
+    saved[CHECKSUM] = checksum(&saved);
+    disk::save(disk::SAVE, &saved);
+}
+
+/// How far the player had got when the game was last saved, if they'd got
+/// anywhere.
+pub fn saved_progress() -> Option<Progress> {
+    let saved = read()?;
+    if saved[HAS_PROGRESS] == 0 {
+        return None;
+    }
+    let difficulty = Difficulty::from_byte(saved[DIFFICULTY])?;
+
+    let progress = Progress {
+        level: saved[LEVEL] as usize,
+        health: saved[HEALTH],
+        keys: saved[KEYS],
+        ammo: saved[AMMO],
+        score: u32::from_le_bytes([
+            saved[SCORE],
+            saved[SCORE + 1],
+            saved[SCORE + 2],
+            saved[SCORE + 3],
+        ]),
+        difficulty,
+    };
+    // a level that's since been taken out can't be carried on from.
+    let valid = progress.level < LEVELS.len() && (1..=MAX_HEALTH).contains(&progress.health);
+    valid.then_some(progress)
+}
+
+impl State {
+    /// Pack every option into a byte, one bit each.
+    fn options_byte(&self) -> u8 {
+        [
+            (matches!(self.resolution, Resolution::Half), HALF_RES),
+            (self.show_damage_numbers, DAMAGE_NUMBERS),
+            (self.reduce_motion, REDUCE_MOTION),
+            (self.show_inputs, INPUTS),
+            (self.mouse_look, MOUSE_LOOK),
+            (self.show_timer, TIMER),
+            (self.pool_report.enabled, POOLS),
+        ]
+        .iter()
+        .filter(|&&(on, _)| on)
+        .fold(0, |options, &(_, bit)| options | bit)
+    }
+
+    /// Pack the mutators that have been unlocked into a byte, one bit each.
+    fn unlocked_byte(&self) -> u8 {
+        [
+            (self.unlocked.big_enemies, BIG_ENEMIES),
+            (self.unlocked.party_colors, PARTY_COLORS),
+        ]
+        .iter()
+        .filter(|&&(on, _)| on)
+        .fold(0, |unlocked, &(_, bit)| unlocked | bit)
+    }
+
+    /// Read the options, codex entries and unlocked mutators saved on the
+    /// disk, if there are any.
+    pub fn load_options(&mut self) {
+        let Some(saved) = read() else {
+            return;
+        };
+        self.codex = saved[CODEX];
+        self.unlocked.big_enemies = saved[UNLOCKED] & BIG_ENEMIES != 0;
+        self.unlocked.party_colors = saved[UNLOCKED] & PARTY_COLORS != 0;
+
+        let options = saved[OPTIONS];
+        self.resolution = if options & HALF_RES != 0 {
+            Resolution::Half
+        } else {
+            Resolution::Full
+        };
+        self.show_damage_numbers = options & DAMAGE_NUMBERS != 0;
+        self.reduce_motion = options & REDUCE_MOTION != 0;
+        self.show_inputs = options & INPUTS != 0;
+        self.mouse_look = options & MOUSE_LOOK != 0;
+        self.show_timer = options & TIMER != 0;
+        self.pool_report.enabled = options & POOLS != 0;
+    }
+
+    /// Write the options, codex entries and unlocked mutators to the disk,
+    /// keeping any progress saved there.
+    pub fn save_options(&self) {
+        let mut saved = read().unwrap_or([0; SAVED_SIZE]);
+        saved[OPTIONS] = self.options_byte();
+        saved[CODEX] = self.codex;
+        saved[UNLOCKED] = self.unlocked_byte();
+        write(saved);
+    }
+
+    /// Write how far the player has got to the disk, along with the options.
+    /// Runs that aren't part of the normal game, like random dungeons and
+    /// time attacks, aren't saved.
+    pub fn save_progress(&self) {
+        if self.dungeon.is_some() || self.time_attack.is_some() {
+            return;
+        }
+
+        let mut saved = [0; SAVED_SIZE];
+        saved[OPTIONS] = self.options_byte();
+        saved[HAS_PROGRESS] = 1;
+        saved[LEVEL] = self.level as u8;
+        saved[HEALTH] = self.health;
+        saved[KEYS] = self.keys;
+        saved[AMMO] = self.ammo;
+        saved[SCORE..SCORE + 4].copy_from_slice(&self.score.to_le_bytes());
+        saved[DIFFICULTY] = self.difficulty as u8;
+        saved[CODEX] = self.codex;
+        saved[UNLOCKED] = self.unlocked_byte();
+        write(saved);
+    }
+
+    /// Carry on from the progress saved on the disk, starting the level it
+    /// was saved on over.
+    pub fn continue_game(&mut self, progress: Progress) {
+        self.time_attack = None;
+        self.difficulty = progress.difficulty;
+        self.load_level(progress.level);
+        self.health = progress.health;
+        self.keys = progress.keys;
+        self.ammo = progress.ammo;
+        self.score = progress.score;
+    }
+}
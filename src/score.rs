@@ -0,0 +1,32 @@
+//! Points for killing enemies, picking up treasure and finding secrets,
+//! added up over a whole run.
+
+use crate::{
+    framebuffer::SCREEN_SIZE,
+    text::{text, Line},
+    State, DRAW_COLORS,
+};
+
+/// How many points each thing is worth.
+pub const KILL_POINTS: u32 = 100;
+pub const BOSS_POINTS: u32 = 1000;
+pub const TREASURE_POINTS: u32 = 250;
+pub const SECRET_POINTS: u32 = 500;
+
+impl State {
+    /// Add `points` to the score, for the run and for the level being played.
+    pub fn award(&mut self, points: u32) {
+        self.score = self.score.saturating_add(points);
+        self.stats.points = self.stats.points.saturating_add(points);
+    }
+
+    /// Show the score in the bottom-right corner of the screen.
+    pub fn draw_score(&self) {
+        let mut line = Line::new();
+        line.push_number(self.score);
+        let x = SCREEN_SIZE as i32 - 4 - 8 * line.as_str().len() as i32;
+
+        unsafe { *DRAW_COLORS = 0x4 };
+        text(line.as_str(), x, SCREEN_SIZE as i32 - 12);
+    }
+}
@@ -0,0 +1,56 @@
+//! Scoreboards around the versus arena, showing the score as it stands.
+//!
+//! A scoreboard's face is a texture, redrawn whenever the score changes, with
+//! digits from a tiny font small enough to fit two of them across a wall.
+
+use crate::texture::{Texture, TEXTURE_SIZE};
+
+/// The colors the board's face, and the frame and digits on it, are drawn in.
+const FACE: u8 = 1;
+const INK: u8 = 3;
+
+/// The digits 0 to 9, three texels wide and five tall, with the leftmost
+/// texel of each row in the most significant of its three bits.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// The row of the scoreboard the digits start on.
+const DIGIT_TOP: usize = 6;
+/// How far in from the left edge each player's score goes, in texels.
+const DIGIT_LEFT: [usize; 2] = [3, 10];
+
+/// Draw a scoreboard showing each player's score into a texture, with a
+/// frame around the edge and a dash between the two. Scores past 9 are shown
+/// as 9.
+pub fn draw_scoreboard(texture: &mut Texture, scores: [u8; 2]) {
+    texture.clear(0);
+
+    // the face, with a frame around its edge, leaving a border of whatever
+    // it's hung on.
+    let edge = TEXTURE_SIZE - 2;
+    texture.fill_rect(1, 1, edge, edge, INK);
+    texture.fill_rect(2, 2, edge - 2, edge - 2, FACE);
+
+    texture.fill_rect(7, DIGIT_TOP + 2, 2, 1, INK);
+
+    for (score, left) in scores.into_iter().zip(DIGIT_LEFT) {
+        let digit = DIGITS[score.min(9) as usize];
+        for (row, bits) in digit.into_iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) != 0 {
+                    texture.set(left + col, DIGIT_TOP + row, INK);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Secret walls that slide out of the way when the player pushes on them.
+
+/// How many cells a secret wall slides back once pushed.
+const PUSH_CELLS: u8 = 2;
+
+/// How many updates it takes a secret wall to slide across one cell.
+const FRAMES_PER_CELL: u16 = 60;
+
+/// A secret wall that is currently sliding.
+pub struct PushWall {
+    /// The cell the wall currently occupies.
+    pub x: i32,
+    pub y: i32,
+    /// The direction the wall is sliding in, one cell at a time.
+    dx: i32,
+    dy: i32,
+    cells_left: u8,
+    timer: u16,
+}
+
+impl PushWall {
+    /// Start sliding the wall at (x, y) in the direction (dx, dy).
+    pub const fn new(x: i32, y: i32, dx: i32, dy: i32) -> Self {
+        Self {
+            x,
+            y,
+            dx,
+            dy,
+            cells_left: PUSH_CELLS,
+            timer: FRAMES_PER_CELL,
+        }
+    }
+
+    /// Advance the slide by one update.
+    ///
+    /// Returns the cell the wall should move into if it's time for it to move
+    /// on to the next one.
+    pub fn tick(&mut self) -> Option<(i32, i32)> {
+        self.timer -= 1;
+        if self.timer > 0 {
+            return None;
+        }
+
+        self.timer = FRAMES_PER_CELL;
+        Some((self.x + self.dx, self.y + self.dy))
+    }
+
+    /// Move the wall into the next cell. Returns `true` once it has stopped.
+    pub fn advance(&mut self) -> bool {
+        self.x += self.dx;
+        self.y += self.dy;
+        self.cells_left -= 1;
+        self.cells_left == 0
+    }
+}
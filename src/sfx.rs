@@ -0,0 +1,519 @@
+//! Sound effects, each described by name as data rather than built up from
+//! the bits of a [`tone`](crate::tone) call wherever it's played.
+//!
+//! Every sound says which channel it plays on and how much it matters, and
+//! goes through [`channels`] so that sounds the player mustn't miss aren't
+//! cut off by background noise, and the other way around. Every sound the
+//! game makes is defined here, along with the `tone` flags they're built
+//! from.
+
+use crate::{
+    channels::{self, Priority},
+    State,
+};
+
+/// Which channel a sound is played on.
+#[derive(Clone, Copy)]
+pub enum Channel {
+    Pulse1 = 0,
+    Pulse2 = 1,
+    Triangle = 2,
+    Noise = 3,
+}
+
+/// The bits of a tone's flags that pick its channel.
+pub const TONE_CHANNEL: u32 = 0b11;
+
+/// Which speakers a sound comes out of.
+#[derive(Clone, Copy)]
+pub enum Pan {
+    Both = 0,
+    Left = 16,
+    Right = 32,
+}
+
+/// How much of each wave the pulse channels spend high, which makes them
+/// sound thinner or fuller. Other channels ignore it.
+#[derive(Clone, Copy)]
+pub enum Duty {
+    Eighth = 0,
+    Quarter = 4,
+    Half = 8,
+}
+
+/// How many updates a sound takes to get loud, fall back, hold, and then
+/// fade away.
+#[derive(Clone, Copy)]
+pub struct Envelope {
+    pub attack: u8,
+    pub decay: u8,
+    pub sustain: u8,
+    pub release: u8,
+}
+
+/// A sound effect.
+#[derive(Clone, Copy)]
+pub struct Sound {
+    /// The frequencies the sound slides from and to, in hertz. They're the
+    /// same for a sound that holds its pitch.
+    pub sweep: (u16, u16),
+    pub envelope: Envelope,
+    /// How loud the sound is, from 0 to 100.
+    pub volume: u8,
+    pub channel: Channel,
+    pub duty: Duty,
+    pub priority: Priority,
+}
+
+impl Sound {
+    /// The sound's sweep as [`tone`](crate::tone) takes it.
+    const fn frequency(&self) -> u32 {
+        self.sweep.0 as u32 | (self.sweep.1 as u32) << 16
+    }
+
+    /// The sound's envelope as [`tone`](crate::tone) takes it.
+    const fn duration(&self) -> u32 {
+        let Envelope {
+            attack,
+            decay,
+            sustain,
+            release,
+        } = self.envelope;
+        (attack as u32) << 24 | (decay as u32) << 16 | (release as u32) << 8 | sustain as u32
+    }
+
+    /// The sound's channel and duty cycle as [`tone`](crate::tone) takes
+    /// them.
+    const fn flags(&self) -> u32 {
+        self.channel as u32 | self.duty as u32
+    }
+
+    /// The same sound, `hertz` higher all the way through its sweep.
+    pub const fn raised(self, hertz: u16) -> Self {
+        Self {
+            sweep: (self.sweep.0 + hertz, self.sweep.1 + hertz),
+            ..self
+        }
+    }
+}
+
+/// A door grinding open.
+pub const DOOR: Sound = Sound {
+    sweep: (120, 60),
+    envelope: Envelope {
+        attack: 2,
+        decay: 0,
+        sustain: 6,
+        release: 12,
+    },
+    volume: 60,
+    channel: Channel::Triangle,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A gunshot.
+pub const SHOT: Sound = Sound {
+    sweep: (300, 60),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 2,
+        release: 8,
+    },
+    volume: 60,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// The click of an empty gun.
+pub const EMPTY_GUN: Sound = Sound {
+    sweep: (1600, 1600),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 2,
+        release: 0,
+    },
+    volume: 20,
+    channel: Channel::Pulse1,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A shot landing on an enemy.
+pub const HIT: Sound = Sound {
+    sweep: (220, 110),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 2,
+        release: 6,
+    },
+    volume: 40,
+    channel: Channel::Pulse2,
+    duty: Duty::Quarter,
+    priority: Priority::Normal,
+};
+
+/// The chimes for picking things up, climbing higher for the things that
+/// matter more.
+const fn pickup(sweep: (u16, u16)) -> Sound {
+    Sound {
+        sweep,
+        envelope: Envelope {
+            attack: 0,
+            decay: 0,
+            sustain: 4,
+            release: 6,
+        },
+        volume: 40,
+        channel: Channel::Pulse1,
+        duty: Duty::Eighth,
+        priority: Priority::Important,
+    }
+}
+pub const PICKUP_AMMO: Sound = pickup((330, 440));
+pub const PICKUP_HEALTH: Sound = pickup((440, 880));
+pub const PICKUP_KEY: Sound = pickup((660, 1320));
+pub const PICKUP_TREASURE: Sound = pickup((990, 1980));
+
+/// The player dying, in a long slide down.
+pub const DEATH: Sound = Sound {
+    sweep: (440, 55),
+    envelope: Envelope {
+        attack: 0,
+        decay: 10,
+        sustain: 20,
+        release: 40,
+    },
+    volume: 70,
+    channel: Channel::Pulse2,
+    duty: Duty::Half,
+    priority: Priority::Important,
+};
+
+/// The click of the exit switch being thrown.
+pub const EXIT_SWITCH: Sound = Sound {
+    sweep: (1200, 1200),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 4,
+        release: 0,
+    },
+    volume: 40,
+    channel: Channel::Pulse1,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A heavy clunk from throwing a switch, lower than the exit switch's click.
+pub const SWITCH: Sound = Sound {
+    sweep: (400, 200),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 6,
+        release: 0,
+    },
+    volume: 50,
+    channel: Channel::Pulse1,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// The rumble of a gate or bridge worked by a switch.
+pub const MECHANISM: Sound = Sound {
+    sweep: (70, 50),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 6,
+        release: 20,
+    },
+    volume: 70,
+    channel: Channel::Triangle,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A gate slamming shut behind the player.
+pub const GATE_SLAM: Sound = Sound {
+    sweep: (140, 40),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 3,
+        release: 14,
+    },
+    volume: 80,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// The grinding scrape of a secret wall sliding along.
+pub const PUSH_WALL: Sound = Sound {
+    sweep: (60, 45),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 4,
+        release: 8,
+    },
+    volume: 50,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A quick sweep upwards, from stepping through a teleporter.
+pub const TELEPORT: Sound = Sound {
+    sweep: (220, 880),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 16,
+        release: 0,
+    },
+    volume: 60,
+    channel: Channel::Triangle,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A splash from stepping or landing in water.
+pub const SPLASH: Sound = Sound {
+    sweep: (400, 120),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 4,
+        release: 10,
+    },
+    volume: 45,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A whoosh from dashing.
+pub const DASH: Sound = Sound {
+    sweep: (900, 200),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 0,
+        release: 12,
+    },
+    volume: 30,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A low rumble of thunder that takes a while to die away.
+pub const THUNDER: Sound = Sound {
+    sweep: (90, 30),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 20,
+        release: 90,
+    },
+    volume: 80,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A rising whine once a fireball is charged up and ready to throw.
+pub const FIREBALL_READY: Sound = Sound {
+    sweep: (400, 800),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 10,
+        release: 0,
+    },
+    volume: 25,
+    channel: Channel::Pulse1,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A fireball being thrown.
+pub const FIREBALL: Sound = Sound {
+    sweep: (200, 80),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 4,
+        release: 20,
+    },
+    volume: 50,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A shot fizzling out against a wall.
+pub const FIZZLE: Sound = Sound {
+    sweep: (500, 100),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 6,
+        release: 0,
+    },
+    volume: 30,
+    channel: Channel::Noise,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A chime for knocking down a target in the shooting gallery.
+pub const TARGET_HIT: Sound = Sound {
+    sweep: (660, 990),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 6,
+        release: 0,
+    },
+    volume: 50,
+    channel: Channel::Pulse2,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A player's shot in a split-screen match.
+pub const VERSUS_SHOT: Sound = Sound {
+    sweep: (880, 220),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 5,
+        release: 0,
+    },
+    volume: 40,
+    channel: Channel::Pulse1,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// A player fragging the other in a split-screen match.
+pub const FRAG: Sound = Sound {
+    sweep: (200, 60),
+    envelope: Envelope {
+        attack: 0,
+        decay: 0,
+        sustain: 20,
+        release: 0,
+    },
+    volume: 70,
+    channel: Channel::Pulse2,
+    duty: Duty::Eighth,
+    priority: Priority::Normal,
+};
+
+/// The noises enemies make: barking when they spot the player, and gurgling
+/// to themselves otherwise. Bosses are bigger, and sound it.
+const fn enemy(sweep: (u16, u16), sustain: u8, volume: u8, channel: Channel) -> Sound {
+    Sound {
+        sweep,
+        envelope: Envelope {
+            attack: 0,
+            decay: 0,
+            sustain,
+            release: 0,
+        },
+        volume,
+        channel,
+        duty: Duty::Eighth,
+        priority: Priority::Normal,
+    }
+}
+pub const BARK: Sound = enemy((300, 520), 10, 60, Channel::Pulse2);
+pub const BOSS_BARK: Sound = enemy((160, 340), 14, 80, Channel::Pulse2);
+pub const GURGLE: Sound = enemy((150, 100), 12, 45, Channel::Pulse1);
+pub const BOSS_GURGLE: Sound = enemy((90, 55), 12, 70, Channel::Pulse1);
+
+/// Footsteps on each kind of floor. They're the least important sound there
+/// is, so they're skipped rather than cutting off anything else.
+const fn step(sweep: (u16, u16), sustain: u8, release: u8, volume: u8) -> Sound {
+    Sound {
+        sweep,
+        envelope: Envelope {
+            attack: 0,
+            decay: 0,
+            sustain,
+            release,
+        },
+        volume,
+        channel: Channel::Noise,
+        duty: Duty::Eighth,
+        priority: Priority::Ambient,
+    }
+}
+/// A dull thud.
+pub const STEP: Sound = step((110, 110), 0, 4, 14);
+/// A slosh through the water.
+pub const STEP_WATER: Sound = step((420, 160), 2, 8, 22);
+/// A hiss off whatever's burning the player's feet.
+pub const STEP_HOT: Sound = step((900, 1400), 0, 6, 16);
+
+/// The metronome's clicks for the rhythm mutator, louder on the first beat
+/// of each bar. They're background noise, so anything else cuts them off.
+const fn click(frequency: u16, volume: u8) -> Sound {
+    Sound {
+        sweep: (frequency, frequency),
+        envelope: Envelope {
+            attack: 0,
+            decay: 0,
+            sustain: 1,
+            release: 2,
+        },
+        volume,
+        channel: Channel::Noise,
+        duty: Duty::Eighth,
+        priority: Priority::Ambient,
+    }
+}
+pub const BEAT: Sound = click(1800, 15);
+pub const BAR: Sound = click(2400, 30);
+
+/// Play a sound effect.
+pub fn play(sound: &Sound) {
+    play_panned(sound, Pan::Both);
+}
+
+/// Play a sound effect out of only some of the speakers.
+pub fn play_panned(sound: &Sound, pan: Pan) {
+    channels::play_with_priority(
+        sound.frequency(),
+        sound.duration(),
+        sound.volume as u32,
+        sound.flags() | pan as u32,
+        sound.priority,
+    );
+}
+
+impl State {
+    /// Play a sound effect coming from the point (x, y) on the map, sounding
+    /// as far off as it is, as described for [`State::heard_from`].
+    pub fn play_sound_at(&self, x: f32, y: f32, sound: &Sound) {
+        let heard = self.heard_from(x, y, sound.frequency(), sound.volume as u32, sound.flags());
+        if let Some((frequency, volume, flags)) = heard {
+            channels::play_with_priority(
+                frequency,
+                sound.duration(),
+                volume,
+                flags,
+                sound.priority,
+            );
+        }
+    }
+}
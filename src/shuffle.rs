@@ -0,0 +1,133 @@
+//! Shuffling where items and enemies are placed, for the shuffle mutator.
+//!
+//! Items and enemies trade places with each other at random, so the campaign
+//! plays out differently every run without touching the hand-made maps. Each
+//! shuffle is checked to make sure every key can still be reached without
+//! going through the door it opens, and is thrown away for another if not.
+
+use crate::{
+    entities::MAX_ENTITIES,
+    keys::Key,
+    map::{is_wall, Map, WorldMap, MAX_CELLS},
+    math::floorf,
+    rng::Rng,
+    State,
+};
+
+/// How many shuffles to try before giving up and leaving everything where
+/// the map put it.
+const ATTEMPTS: usize = 32;
+
+/// Find every cell the player can walk to from `start`, holding the keys in
+/// `held`, as one bit per cell.
+fn reachable(map: &Map, start: (i32, i32), held: u8) -> [u8; MAX_CELLS / 8] {
+    let mut reached = [0; MAX_CELLS / 8];
+    let mut stack = [(0, 0); MAX_CELLS];
+    let mut len = 0;
+
+    if let Some(idx) = map.index(start.0, start.1) {
+        reached[idx / 8] |= 1 << (idx % 8);
+        stack[0] = start;
+        len = 1;
+    }
+
+    while len > 0 {
+        len -= 1;
+        let (x, y) = stack[len];
+
+        for (next_x, next_y) in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+            let Some(idx) = map.index(next_x, next_y) else {
+                continue;
+            };
+            if reached[idx / 8] & (1 << (idx % 8)) != 0 {
+                continue;
+            }
+
+            let tile = map.tile_at(next_x, next_y);
+            let unlocked = Key::for_door(tile).is_some_and(|key| held & key.bit() != 0);
+            if is_wall(tile) && !unlocked {
+                continue;
+            }
+
+            reached[idx / 8] |= 1 << (idx % 8);
+            stack[len] = (next_x, next_y);
+            len += 1;
+        }
+    }
+
+    reached
+}
+
+/// Check if the player can collect every key from `start`, picking up the
+/// ones they can get to and trying again with the doors those open.
+fn keys_are_reachable(map: &Map, start: (i32, i32), keys: &[(Key, (f32, f32))]) -> bool {
+    let mut held = 0;
+
+    loop {
+        let reached = reachable(map, start, held);
+
+        let mut found = held;
+        for &(key, (x, y)) in keys {
+            let Some(idx) = map.index(floorf(x) as i32, floorf(y) as i32) else {
+                continue;
+            };
+            if reached[idx / 8] & (1 << (idx % 8)) != 0 {
+                found |= key.bit();
+            }
+        }
+
+        if found == held {
+            return keys.iter().all(|(key, _)| held & key.bit() != 0);
+        }
+        held = found;
+    }
+}
+
+impl State {
+    /// Shuffle the items and enemies that were just placed around the level.
+    ///
+    /// The same run always shuffles a level the same way, so dying and
+    /// starting it over doesn't move everything around again.
+    pub fn shuffle_entities(&mut self) {
+        let mut rng = Rng::new(self.shuffle_seed ^ (self.level as u32).wrapping_mul(0x9e37_79b9));
+
+        // the slots holding something to shuffle, and where each one is.
+        let mut slots = [0; MAX_ENTITIES];
+        let mut spots = [(0.0, 0.0); MAX_ENTITIES];
+        let mut len = 0;
+        for (idx, entity) in self.entities.indexed() {
+            if entity.health.is_some() || entity.item.is_some() {
+                slots[len] = idx;
+                spots[len] = (entity.x, entity.y);
+                len += 1;
+            }
+        }
+
+        let (spawn_x, spawn_y, _) = self.level().map.spawn;
+        let start = (floorf(spawn_x) as i32, floorf(spawn_y) as i32);
+
+        for _ in 0..ATTEMPTS {
+            for idx in (1..len).rev() {
+                spots.swap(idx, rng.below(idx + 1));
+            }
+
+            let mut keys = [(Key::Red, (0.0, 0.0)); MAX_ENTITIES];
+            let mut key_count = 0;
+            for (&slot, &spot) in slots[..len].iter().zip(&spots) {
+                if let Some(key) = self.entities.get_mut(slot).and_then(|entity| entity.key()) {
+                    keys[key_count] = (key, spot);
+                    key_count += 1;
+                }
+            }
+
+            if keys_are_reachable(&self.map, start, &keys[..key_count]) {
+                for (&slot, &(x, y)) in slots[..len].iter().zip(&spots) {
+                    if let Some(entity) = self.entities.get_mut(slot) {
+                        (entity.x, entity.y) = (x, y);
+                    }
+                }
+                return;
+            }
+        }
+    }
+}
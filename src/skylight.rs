@@ -0,0 +1,35 @@
+//! Sunlight falling through cells that are open to the sky.
+//!
+//! Anything standing in the open is drawn a shade lighter, and from indoors
+//! the light coming down through an open roof shows as faint, slanted shafts
+//! over whatever is behind it, so stepping out of a building feels like it.
+
+use crate::{
+    map::{GridWalk, WorldMap, SKY},
+    math::{cosf, sinf},
+    State,
+};
+
+/// The most cells a ray is followed through while looking for sunlight.
+const MAX_STEPS: usize = 32;
+
+impl State {
+    /// How far the first cell open to the sky is from the camera plane,
+    /// looking along `angle`, if there's one closer than `limit`.
+    pub fn skylight_depth(&self, angle: f32, limit: f32) -> Option<f32> {
+        let (dir_x, dir_y) = (cosf(angle), -sinf(angle));
+        let correction = cosf(angle - self.player_angle);
+        let cells = GridWalk::new((self.player_x, self.player_y), (dir_x, dir_y));
+        for ((x, y), along, _) in cells.take(MAX_STEPS) {
+            let depth = along * correction;
+            if depth >= limit {
+                return None;
+            }
+            if self.map.tile_at(x, y) == SKY {
+                return Some(depth);
+            }
+        }
+
+        None
+    }
+}
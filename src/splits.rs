@@ -0,0 +1,108 @@
+//! A speedrun timer for each level, and the best time on each one kept on
+//! the disk for runners to chase.
+//!
+//! The timer counts the same updates as the level's stats, which only tick
+//! over while the level is actually being played, so it stops for menus,
+//! messages between levels, and dying.
+
+use crate::{
+    disk,
+    intermission::push_time,
+    levels::LEVELS,
+    text::{text, Line},
+    timestep::STEPS_PER_SECOND,
+    toasts, State, DRAW_COLORS,
+};
+
+/// How many bytes the best times take up on the disk: one `u32` for each
+/// level, with and without mutators changing how it plays.
+pub const SAVED_SIZE: usize = 2 * LEVELS.len() * 4;
+
+/// Where the timer goes on screen, in the top-left corner, or just below the
+/// time attack clock when there is one.
+const TIMER_X: i32 = 4;
+const TIMER_Y: i32 = 4;
+const TIME_ATTACK_TIMER_Y: i32 = 24;
+
+/// Write out a number of updates as minutes, seconds and hundredths.
+fn push_split(line: &mut Line, frames: u32) -> &mut Line {
+    push_time(line, frames)
+        .push_str(".")
+        .push_two_digits(frames % STEPS_PER_SECOND * 100 / STEPS_PER_SECOND)
+}
+
+impl State {
+    /// Read the best time on each level from the disk. Levels never finished
+    /// are saved as zero.
+    pub fn load_best_frames(&mut self) {
+        let mut saved = [0; SAVED_SIZE];
+        disk::load(disk::SPLITS, &mut saved);
+
+        let best = self.best_frames.iter_mut().flatten();
+        for (best, bytes) in best.zip(saved.chunks_exact(4)) {
+            let frames = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            *best = (frames > 0).then_some(frames);
+        }
+    }
+
+    /// Write the best time on each level to the disk.
+    fn save_best_frames(&self) {
+        let mut saved = [0; SAVED_SIZE];
+        let best = self.best_frames.iter().flatten();
+        for (best, bytes) in best.zip(saved.chunks_exact_mut(4)) {
+            bytes.copy_from_slice(&best.unwrap_or(0).to_le_bytes());
+        }
+        disk::save(disk::SPLITS, &saved);
+    }
+
+    /// Keep the time the level that was just finished took, if it's the
+    /// best anyone has done on it.
+    pub fn record_split(&mut self) {
+        // random dungeons are all different, so there's no record to beat.
+        if self.dungeon.is_some() {
+            return;
+        }
+
+        let best = &mut self.best_frames[self.mutators.change_play() as usize][self.level];
+        if best.map_or(true, |best| self.stats.frames < best) {
+            *best = Some(self.stats.frames);
+            toasts::push(&toasts::CLOCK, "NEW RECORD!");
+            self.save_best_frames();
+        }
+    }
+
+    /// Show how long the level has taken so far, and the best time on it to
+    /// beat, if the timer is switched on.
+    pub fn draw_timer(&self) {
+        if !self.show_timer {
+            return;
+        }
+
+        let y = if self.time_attack.is_some() {
+            TIME_ATTACK_TIMER_Y
+        } else {
+            TIMER_Y
+        };
+
+        unsafe { *DRAW_COLORS = 0x4 };
+        text(
+            push_split(&mut Line::new(), self.stats.frames).as_str(),
+            TIMER_X,
+            y,
+        );
+
+        if self.dungeon.is_some() {
+            return;
+        }
+        let best = self.best_frames[self.mutators.change_play() as usize][self.level];
+        if let Some(best) = best {
+            // the best time goes dark once it's out of reach.
+            unsafe { *DRAW_COLORS = if self.stats.frames > best { 0x2 } else { 0x3 } };
+            text(
+                push_split(Line::new().push_str("PB "), best).as_str(),
+                TIMER_X,
+                y + 10,
+            );
+        }
+    }
+}
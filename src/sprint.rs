@@ -0,0 +1,79 @@
+//! Sprinting, by holding Z while walking.
+//!
+//! Sprinting wears the player out, and once they run out of stamina they
+//! have to get all of it back before they can sprint again. Turning doesn't
+//! speed up along with walking, so aiming is no harder on the run.
+
+use crate::{framebuffer::SCREEN_SIZE, rect, State, DRAW_COLORS};
+
+/// How many times faster than walking the player sprints.
+pub const SPRINT_SPEED: f32 = 1.6;
+
+/// How much stamina the player has when they're fully rested.
+const MAX_STAMINA: u16 = 180;
+/// How much stamina each update of sprinting uses up.
+const DRAIN: u16 = 2;
+/// How much stamina comes back on each update spent not sprinting.
+const RECOVERY: u16 = 1;
+
+/// The size of the stamina meter, and where it goes along the bottom of the
+/// screen.
+const METER_WIDTH: u32 = 42;
+const METER_HEIGHT: u32 = 4;
+const METER_Y: i32 = 150;
+
+/// How much longer the player can keep sprinting.
+pub struct Stamina {
+    left: u16,
+    /// Whether the player ran out, and has to get all of it back before
+    /// sprinting again.
+    winded: bool,
+}
+
+impl Stamina {
+    pub const fn new() -> Self {
+        Self {
+            left: MAX_STAMINA,
+            winded: false,
+        }
+    }
+
+    /// Check if the player gets to sprint this update, using up stamina if
+    /// they do and getting some back if they don't.
+    pub fn sprint(&mut self, wants_to: bool) -> bool {
+        let sprinting = wants_to && !self.winded;
+
+        if sprinting {
+            self.left = self.left.saturating_sub(DRAIN);
+            self.winded = self.left == 0;
+        } else {
+            self.left = (self.left + RECOVERY).min(MAX_STAMINA);
+            self.winded &= self.left < MAX_STAMINA;
+        }
+
+        sprinting
+    }
+}
+
+impl State {
+    /// Draw how much stamina the player has along the bottom of the screen,
+    /// unless they've got all of it.
+    pub fn draw_stamina(&self) {
+        let stamina = &self.stamina;
+        if stamina.left == MAX_STAMINA {
+            return;
+        }
+
+        let left = (SCREEN_SIZE as u32 - METER_WIDTH) as i32 / 2;
+        let filled = (METER_WIDTH - 2) * stamina.left as u32 / MAX_STAMINA as u32;
+
+        unsafe {
+            *DRAW_COLORS = 0x41;
+            rect(left, METER_Y, METER_WIDTH, METER_HEIGHT);
+
+            // the meter fades while the player gets their breath back.
+            *DRAW_COLORS = if stamina.winded { 0x33 } else { 0x44 };
+            rect(left + 1, METER_Y + 1, filled, METER_HEIGHT - 2);
+        }
+    }
+}
@@ -0,0 +1,558 @@
+//! Billboarded sprites, drawn over the walls one screen column at a time.
+
+use core::{f32::consts::TAU, ops::Range};
+
+use crate::{
+    angle,
+    framebuffer::{ColumnStage, SCREEN_SIZE},
+    math::tanf,
+    render::HORIZON,
+    texture::{Slot, Textures, TEXTURE_SIZE},
+    DRAW_COLORS, FOV,
+};
+
+/// A 2bpp image, packed four pixels per byte with the leftmost pixel in the
+/// most significant bits (the same layout `w4 png2src` produces).
+///
+/// Pixels with a value of 0 are transparent.
+pub struct Sprite {
+    pub width: usize,
+    pub height: usize,
+    pub data: &'static [u8],
+}
+
+impl Sprite {
+    /// Get the value of the pixel at (u, v).
+    fn texel(&self, u: usize, v: usize) -> u8 {
+        let idx = v * self.width + u;
+        (self.data[idx / 4] >> (6 - (idx % 4) * 2)) & 0b11
+    }
+}
+
+/// A large image made from a grid of same-sized sprites, drawn as a single
+/// billboard so that it can be bigger than any one sprite could reasonably be.
+pub struct Composite {
+    /// How many sprites wide the grid is.
+    pub columns: usize,
+    /// The sprites making up the grid, row by row.
+    pub tiles: &'static [&'static Sprite],
+}
+
+/// Something that looks different depending on which side it's seen from,
+/// with a same-sized sprite for each of eight directions. The first is what
+/// its front looks like, and the rest go on around it anticlockwise, so the
+/// third is what it looks like with the viewer off to its left.
+pub struct Directional {
+    pub frames: [&'static Sprite; 8],
+}
+
+/// How many sprite pixels span one map unit in the world.
+pub const TEXELS_PER_UNIT: f32 = 16.0;
+
+/// What something looks like in the world.
+#[derive(Clone, Copy)]
+pub enum Graphic {
+    Sprite(&'static Sprite),
+    Composite(&'static Composite),
+    /// A texture drawn at runtime, which can change from frame to frame.
+    Texture(Slot),
+    /// A sprite that depends on which side it's seen from, picked by
+    /// [`Graphic::seen_from`] before it's drawn.
+    Directional(&'static Directional),
+}
+
+impl Graphic {
+    /// The width of the whole image, in pixels.
+    pub fn width(self) -> usize {
+        match self {
+            Self::Sprite(sprite) => sprite.width,
+            Self::Composite(composite) => composite.columns * composite.tiles[0].width,
+            Self::Texture(_) => TEXTURE_SIZE,
+            Self::Directional(directional) => directional.frames[0].width,
+        }
+    }
+
+    /// The height of the whole image, in pixels.
+    pub fn height(self) -> usize {
+        match self {
+            Self::Sprite(sprite) => sprite.height,
+            Self::Composite(composite) => {
+                (composite.tiles.len() / composite.columns) * composite.tiles[0].height
+            }
+            Self::Texture(_) => TEXTURE_SIZE,
+            Self::Directional(directional) => directional.frames[0].height,
+        }
+    }
+
+    /// Get the value of the pixel at (u, v) of the whole image, looking up
+    /// textures drawn at runtime in `textures`.
+    fn texel(self, u: usize, v: usize, textures: &Textures) -> u8 {
+        match self {
+            Self::Sprite(sprite) => sprite.texel(u, v),
+            Self::Composite(composite) => {
+                let (tile_width, tile_height) =
+                    (composite.tiles[0].width, composite.tiles[0].height);
+                let tile =
+                    composite.tiles[(v / tile_height) * composite.columns + (u / tile_width)];
+                tile.texel(u % tile_width, v % tile_height)
+            }
+            Self::Texture(slot) => textures.get(slot).texel(u, v),
+            Self::Directional(directional) => directional.frames[0].texel(u, v),
+        }
+    }
+
+    /// What the graphic looks like from `angle` radians anticlockwise around
+    /// from its front, picking out the nearest of a directional graphic's
+    /// sprites. Anything else looks the same from every side.
+    pub fn seen_from(self, angle: f32) -> Self {
+        match self {
+            Self::Directional(directional) => {
+                let turn = angle::turns(angle + TAU / 16.0);
+                Self::Sprite(directional.frames[(turn * 8.0) as usize % 8])
+            }
+            _ => self,
+        }
+    }
+}
+
+pub const PILLAR: Sprite = Sprite {
+    width: 16,
+    height: 16,
+    data: &[
+        0x00, 0x0f, 0xf0, 0x00, 0x00, 0x3a, 0xac, 0x00, 0x00, 0x3f, 0xfc, 0x00, 0x00, 0x0e, 0xb0,
+        0x00, 0x00, 0x0e, 0x70, 0x00, 0x00, 0x0e, 0x70, 0x00, 0x00, 0x0e, 0x70, 0x00, 0x00, 0x0e,
+        0x70, 0x00, 0x00, 0x0e, 0x70, 0x00, 0x00, 0x0e, 0x70, 0x00, 0x00, 0x0e, 0x70, 0x00, 0x00,
+        0x0e, 0x70, 0x00, 0x00, 0x0e, 0x70, 0x00, 0x00, 0x3f, 0xfc, 0x00, 0x00, 0xea, 0xab, 0x00,
+        0x00, 0xff, 0xff, 0x00,
+    ],
+};
+
+pub const CRATE: Sprite = Sprite {
+    width: 16,
+    height: 16,
+    data: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x03, 0xff,
+        0xff, 0xc0, 0x03, 0x95, 0x56, 0xc0, 0x03, 0x95, 0x56, 0xc0, 0x03, 0xff, 0xff, 0xc0, 0x03,
+        0x95, 0x56, 0xc0, 0x03, 0x95, 0x56, 0xc0, 0x03, 0xff, 0xff, 0xc0, 0x03, 0xaa, 0xaa, 0xc0,
+        0x00, 0xff, 0xff, 0x00,
+    ],
+};
+
+pub const EXIT_DOOR: Sprite = Sprite {
+    width: 16,
+    height: 16,
+    data: &[
+        0x00, 0xff, 0xff, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0x95, 0x56, 0xb0, 0x0e, 0x55, 0x55,
+        0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x0e, 0x55,
+        0x55, 0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x0e,
+        0x55, 0x55, 0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x0e, 0x55, 0x55, 0xb0, 0x3f, 0xff, 0xff, 0xfc,
+        0xea, 0xaa, 0xaa, 0xab,
+    ],
+};
+
+pub const SPIKES: Sprite = Sprite {
+    width: 16,
+    height: 8,
+    data: &[
+        0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0xec, 0xec, 0xec,
+        0xec, 0xec, 0xec, 0xec, 0xec, 0xeb, 0xeb, 0xeb, 0xeb, 0xeb, 0xeb, 0xeb, 0xeb, 0xff, 0xff,
+        0xff, 0xff,
+    ],
+};
+
+pub const SLIME: Sprite = Sprite {
+    width: 16,
+    height: 16,
+    data: &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0xf0,
+        0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0xaa, 0xaa, 0xb0, 0x0e, 0x5a,
+        0xa5, 0xb0, 0x0e, 0x7a, 0xad, 0xb0, 0x3a, 0xaa, 0xaa, 0xac, 0x3a, 0xbf, 0xfe, 0xac, 0x3a,
+        0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab, 0xee, 0xeb, 0xee, 0xeb,
+        0x33, 0x3c, 0x3c, 0xcc,
+    ],
+};
+
+pub const PLAYER: Sprite = Sprite {
+    width: 16,
+    height: 16,
+    data: &[
+        0x00, 0x0f, 0xf0, 0x00, 0x00, 0x3a, 0xac, 0x00, 0x00, 0x39, 0x6c, 0x00, 0x00, 0x3a, 0xac,
+        0x00, 0x00, 0x0f, 0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x03, 0xba,
+        0xae, 0xc0, 0x03, 0xba, 0xae, 0xc0, 0x03, 0xba, 0xae, 0xc0, 0x00, 0xfa, 0xaf, 0x00, 0x00,
+        0x3a, 0xac, 0x00, 0x00, 0x38, 0x2c, 0x00, 0x00, 0x38, 0x2c, 0x00, 0x00, 0x38, 0x2c, 0x00,
+        0x00, 0xfc, 0x3f, 0x00,
+    ],
+};
+
+pub const STATUE: Composite = Composite {
+    columns: 2,
+    tiles: &[
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x3f, 0x00, 0x00, 0x03, 0xea, 0x00, 0x00, 0x0e, 0xaa, 0x00, 0x00,
+                0x3a, 0xaa, 0x00, 0x00, 0x3a, 0xfa, 0x00, 0x00, 0x3b, 0xfe, 0x00, 0x00, 0x3b, 0xde,
+                0x00, 0x00, 0x3a, 0xfa, 0x00, 0x00, 0x3a, 0xab, 0x00, 0x00, 0x0e, 0xab, 0x00, 0x00,
+                0x0e, 0xaa, 0x00, 0x00, 0x03, 0xbb, 0x00, 0x00, 0x03, 0xee, 0x00, 0x00, 0x00, 0xff,
+                0x00, 0x00, 0x3f, 0xaa, 0x00, 0x03, 0xea, 0xaa,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0xfc, 0x00, 0x00, 0x00, 0xab, 0xc0, 0x00, 0x00, 0xaa, 0xb0, 0x00, 0x00, 0xaa, 0xac,
+                0x00, 0x00, 0xaf, 0xac, 0x00, 0x00, 0xbf, 0xec, 0x00, 0x00, 0xb7, 0xec, 0x00, 0x00,
+                0xaf, 0xac, 0x00, 0x00, 0xea, 0xac, 0x00, 0x00, 0xea, 0xb0, 0x00, 0x00, 0xaa, 0xb0,
+                0x00, 0x00, 0xba, 0xc0, 0x00, 0x00, 0xef, 0xc0, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00,
+                0xaa, 0xfc, 0x00, 0x00, 0xaa, 0xab, 0xc0, 0x00,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x0e, 0xaa, 0xaa, 0x00, 0x0e, 0xae, 0xaa, 0x00, 0x0e, 0xae, 0xaa, 0x00, 0x0e,
+                0xae, 0xaa, 0x00, 0x0e, 0xb3, 0xaa, 0x00, 0x0e, 0xb3, 0xaa, 0x00, 0x0f, 0xc3, 0xa9,
+                0x00, 0x00, 0x03, 0xa9, 0x00, 0x00, 0x03, 0xa9, 0x00, 0x00, 0x03, 0xab, 0x00, 0x00,
+                0x0f, 0xff, 0x00, 0x00, 0x3a, 0xaa, 0x00, 0x00, 0xff, 0xff, 0x00, 0x00, 0xe5, 0x55,
+                0x00, 0x00, 0xea, 0xaa, 0x00, 0x00, 0xff, 0xff,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0xaa, 0xaa, 0xb0, 0x00, 0xaa, 0xba, 0xb0, 0x00, 0xaa, 0xba, 0xb0, 0x00, 0xaa, 0xba,
+                0xb0, 0x00, 0xaa, 0xb3, 0xac, 0x00, 0xaa, 0xb3, 0xac, 0x00, 0x6a, 0xb0, 0xfc, 0x00,
+                0x6a, 0xb0, 0x00, 0x00, 0x6a, 0xb0, 0x00, 0x00, 0xea, 0xb0, 0x00, 0x00, 0xff, 0xfc,
+                0x00, 0x00, 0xaa, 0xab, 0x00, 0x00, 0xff, 0xff, 0xc0, 0x00, 0x55, 0x56, 0xc0, 0x00,
+                0xaa, 0xaa, 0xc0, 0x00, 0xff, 0xff, 0xc0, 0x00,
+            ],
+        },
+    ],
+};
+
+/// A slime seen from each of eight directions, for [`Graphic::Directional`].
+pub const SLIME_VIEWS: Directional = Directional {
+    frames: [
+        &SLIME,
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+                0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0xaa, 0xaa, 0xb0,
+                0x0d, 0xaa, 0x5a, 0xb0, 0x0f, 0xaa, 0x7a, 0xb0, 0x3a, 0xaa, 0xaa, 0xac, 0x3a, 0xff,
+                0xfa, 0xac, 0x3a, 0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab,
+                0xee, 0xeb, 0xee, 0xeb, 0x33, 0x3c, 0x3c, 0xcc,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+                0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0xaa, 0xaa, 0xb0,
+                0x0d, 0xaa, 0xaa, 0xb0, 0x0f, 0xaa, 0xaa, 0xb0, 0x3a, 0xaa, 0xaa, 0xac, 0x3f, 0xea,
+                0xaa, 0xac, 0x3a, 0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab,
+                0xee, 0xeb, 0xee, 0xeb, 0x33, 0x3c, 0x3c, 0xcc,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+                0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0xaa, 0xaa, 0xb0,
+                0x0d, 0xaa, 0xaa, 0xb0, 0x0e, 0xaa, 0xaa, 0xb0, 0x3a, 0xaa, 0xaa, 0xac, 0x3a, 0xaa,
+                0xaa, 0xac, 0x3a, 0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab,
+                0xee, 0xeb, 0xee, 0xeb, 0x33, 0x3c, 0x3c, 0xcc,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+                0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xa5, 0xaa, 0xc0, 0x0e, 0x9a, 0xaa, 0xb0,
+                0x0e, 0xaa, 0xaa, 0xb0, 0x0e, 0xaa, 0xaa, 0xb0, 0x3a, 0xaa, 0xaa, 0xac, 0x3a, 0xaa,
+                0xaa, 0xac, 0x3a, 0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab,
+                0xee, 0xeb, 0xee, 0xeb, 0x33, 0x3c, 0x3c, 0xcc,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+                0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0xaa, 0xaa, 0xb0,
+                0x0e, 0xaa, 0xaa, 0x70, 0x0e, 0xaa, 0xaa, 0xb0, 0x3a, 0xaa, 0xaa, 0xac, 0x3a, 0xaa,
+                0xaa, 0xac, 0x3a, 0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab,
+                0xeb, 0xbb, 0xeb, 0xbb, 0x33, 0x3c, 0x3c, 0xcc,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+                0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0xaa, 0xaa, 0xb0,
+                0x0e, 0xaa, 0xaa, 0x70, 0x0e, 0xaa, 0xaa, 0xf0, 0x3a, 0xaa, 0xaa, 0xac, 0x3a, 0xaa,
+                0xab, 0xfc, 0x3a, 0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab,
+                0xeb, 0xbb, 0xeb, 0xbb, 0x33, 0x3c, 0x3c, 0xcc,
+            ],
+        },
+        &Sprite {
+            width: 16,
+            height: 16,
+            data: &[
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+                0xf0, 0x00, 0x00, 0xfa, 0xaf, 0x00, 0x03, 0xaa, 0xaa, 0xc0, 0x0e, 0xaa, 0xaa, 0xb0,
+                0x0e, 0xa5, 0xaa, 0x70, 0x0e, 0xad, 0xaa, 0xf0, 0x3a, 0xaa, 0xaa, 0xac, 0x3a, 0xaf,
+                0xff, 0xac, 0x3a, 0xaa, 0xaa, 0xac, 0xea, 0xaa, 0xaa, 0xab, 0xea, 0xaa, 0xaa, 0xab,
+                0xeb, 0xbb, 0xeb, 0xbb, 0x33, 0x3c, 0x3c, 0xcc,
+            ],
+        },
+    ],
+};
+
+/// A recoloring applied to a sprite as it's drawn.
+///
+/// Sprite pixel values are mapped to palette colors through the
+/// `DRAW_COLORS` register, the same way WASM-4's own `blit` does, so a single
+/// sprite can be drawn in several different color schemes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Tint {
+    /// The sprite's own colors.
+    Normal,
+    /// Every pixel at the brightest color, for a frame after taking a hit.
+    Flash,
+    /// Washed out towards the lighter colors.
+    Frozen,
+    /// Pulled down towards the darker colors.
+    Poisoned,
+    /// Every color a shade lighter, for standing out in the sun.
+    Sunlit,
+    /// The sprite's colors, swapped around to pick out a team.
+    Team(u8),
+}
+
+impl Tint {
+    /// The value to put in `DRAW_COLORS` when drawing with this tint.
+    pub const fn draw_colors(self) -> u16 {
+        match self {
+            Self::Normal => 0x4320,
+            Self::Flash => 0x1110,
+            Self::Frozen => 0x4210,
+            Self::Poisoned => 0x4330,
+            Self::Sunlit => 0x3210,
+            Self::Team(team) if team % 2 == 0 => 0x4320,
+            Self::Team(_) => 0x4230,
+        }
+    }
+
+    /// This tint, in the light of a cell open to the sky. Sprites already
+    /// tinted some other way keep it, so it can still be seen.
+    pub const fn sunlit(self) -> Self {
+        match self {
+            Self::Normal => Self::Sunlit,
+            other => other,
+        }
+    }
+}
+
+/// How many fractional bits are used when stepping through a graphic's rows.
+const FIXED_SHIFT: u32 = 16;
+const FIXED_ONE: u32 = 1 << FIXED_SHIFT;
+
+/// The most sprites that can be drawn in a single frame.
+pub const MAX_VISIBLE: usize = 16;
+
+/// A graphic that has been placed on screen.
+#[derive(Clone, Copy)]
+pub struct Projected {
+    pub graphic: Graphic,
+    /// The distance from the camera plane to the graphic, in map units.
+    pub depth: f32,
+    /// The screen column the graphic is centered on.
+    pub screen_x: f32,
+    /// How many pixels on screen one map unit covers at this depth.
+    pub scale: f32,
+    /// How many times bigger than normal the graphic is drawn. It still stands
+    /// on the floor either way.
+    pub size: f32,
+    pub tint: Tint,
+    /// Whether the graphic is drawn lying flat on the floor, rather than
+    /// standing up.
+    pub flat: bool,
+}
+
+impl Projected {
+    /// Draw the part of a graphic lying flat on the floor that covers screen
+    /// column `x`. It's turned to face the camera, with its top edge furthest
+    /// away.
+    fn draw_flat(
+        &self,
+        column: &mut [u8],
+        x: usize,
+        draw_colors: u16,
+        eye_height: f32,
+        textures: &Textures,
+    ) {
+        let graphic = self.graphic;
+        let width = graphic.width() as f32 / TEXELS_PER_UNIT * self.size;
+        let length = graphic.height() as f32 / TEXELS_PER_UNIT * self.size;
+        let (near, far) = (self.depth - length / 2.0, self.depth + length / 2.0);
+
+        // how far off to the side the graphic's center and this column are,
+        // for every unit away from the camera plane.
+        let pixels_per_radian = SCREEN_SIZE as f32 / FOV;
+        let center = SCREEN_SIZE as f32 / 2.0;
+        let graphic_slope = tanf((self.screen_x - center) / pixels_per_radian);
+        let column_slope = tanf((x as f32 + 0.5 - center) / pixels_per_radian);
+        let lateral = graphic_slope * self.depth;
+
+        // the floor at each row is as far away as something standing on it
+        // with its bottom edge drawn on that row would be.
+        let focal = self.scale * self.depth * eye_height;
+        let first_row = (HORIZON as f32 + focal / far).clamp(0.0, SCREEN_SIZE as f32) as usize;
+        let last_row = if near > 0.0 {
+            (HORIZON as f32 + focal / near).clamp(0.0, SCREEN_SIZE as f32) as usize
+        } else {
+            SCREEN_SIZE
+        };
+
+        for (y, pixel) in column.iter_mut().enumerate().take(last_row).skip(first_row) {
+            let depth = focal / (y as f32 + 0.5 - HORIZON as f32);
+            let u = (column_slope * depth - lateral) / width + 0.5;
+            let v = (far - depth) / length;
+            if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v) {
+                continue;
+            }
+
+            let texel = graphic.texel(
+                (u * graphic.width() as f32) as usize,
+                (v * graphic.height() as f32) as usize,
+                textures,
+            );
+            let color = (draw_colors >> (texel * 4)) & 0xf;
+            if color != 0 {
+                *pixel = (color - 1) as u8;
+            }
+        }
+    }
+}
+
+/// Every sprite visible this frame, sorted from farthest to nearest.
+pub struct SpriteList {
+    sprites: [Option<Projected>; MAX_VISIBLE],
+    len: usize,
+}
+
+impl SpriteList {
+    pub const fn new() -> Self {
+        Self {
+            sprites: [None; MAX_VISIBLE],
+            len: 0,
+        }
+    }
+
+    /// How many sprites are in the list, and how many there's room for.
+    pub fn usage(&self) -> (usize, usize) {
+        (self.len, MAX_VISIBLE)
+    }
+
+    /// Add a sprite to the list, keeping it sorted. Sprites past
+    /// [`MAX_VISIBLE`] are dropped.
+    pub fn push(&mut self, projected: Projected) {
+        if self.len == MAX_VISIBLE {
+            return;
+        }
+
+        // insertion sort, since there are only ever a handful of sprites.
+        let mut idx = self.len;
+        while idx > 0 && self.sprites[idx - 1].is_some_and(|s| s.depth < projected.depth) {
+            self.sprites[idx] = self.sprites[idx - 1];
+            idx -= 1;
+        }
+
+        self.sprites[idx] = Some(projected);
+        self.len += 1;
+    }
+
+    /// Draw every sprite that covers screen column `x` into one of the staged
+    /// columns, skipping any that aren't within `depths` of the camera plane,
+    /// like those hidden behind a wall.
+    ///
+    /// `eye_height` is how far off the floor the camera is, as a fraction of a
+    /// wall's height, and `textures` has any textures drawn at runtime.
+    pub fn draw_column(
+        &self,
+        stage: &mut ColumnStage,
+        lane: usize,
+        x: usize,
+        depths: Range<f32>,
+        eye_height: f32,
+        textures: &Textures,
+    ) {
+        for projected in self.sprites[..self.len].iter().flatten() {
+            if !depths.contains(&projected.depth) {
+                continue;
+            }
+
+            let draw_colors = unsafe {
+                *DRAW_COLORS = projected.tint.draw_colors();
+                *DRAW_COLORS
+            };
+
+            if projected.flat {
+                let column = stage.column(lane);
+                projected.draw_flat(column, x, draw_colors, eye_height, textures);
+                continue;
+            }
+
+            let graphic = projected.graphic;
+            let size = projected.scale * projected.size;
+            let width = graphic.width() as f32 / TEXELS_PER_UNIT * size;
+            let height = graphic.height() as f32 / TEXELS_PER_UNIT * size;
+
+            let left = projected.screen_x - (width / 2.0);
+            let u = (x as f32 + 0.5 - left) / width;
+            if !(0.0..1.0).contains(&u) {
+                continue;
+            }
+
+            let u = (u * graphic.width() as f32) as usize;
+
+            // stand the graphic on the floor, however tall it is.
+            let top = HORIZON as f32 + (projected.scale * eye_height) - height;
+
+            // only walk the rows that are actually on screen, stepping through
+            // the graphic's rows in fixed point rather than dividing per pixel.
+            let first_row = (top + 0.5).clamp(0.0, SCREEN_SIZE as f32) as usize;
+            let last_row = (top + height + 0.5).clamp(0.0, SCREEN_SIZE as f32) as usize;
+
+            let step = (graphic.height() as f32 / height * FIXED_ONE as f32) as u32;
+            let mut v = ((first_row as f32 + 0.5 - top) / height
+                * graphic.height() as f32
+                * FIXED_ONE as f32) as u32;
+
+            let column = stage.column(lane);
+            for pixel in &mut column[first_row..last_row] {
+                let row = ((v >> FIXED_SHIFT) as usize).min(graphic.height() - 1);
+                v += step;
+
+                // each pixel value picks one of the four draw colors, where 0
+                // is transparent and anything else is a palette index plus one.
+                let texel = graphic.texel(u, row, textures);
+                let color = (draw_colors >> (texel * 4)) & 0xf;
+                if color != 0 {
+                    *pixel = (color - 1) as u8;
+                }
+            }
+        }
+    }
+}
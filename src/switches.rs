@@ -0,0 +1,75 @@
+//! Switches on the walls that open and close gates, and raise and lower
+//! bridges, somewhere else on the map.
+//!
+//! A switch and the cells it works are linked by sharing a tag number.
+//! Throwing a switch flips every cell with its tag, including any other
+//! switches, so they all show which way things are set.
+
+use crate::{
+    map::{WorldMap, BRIDGE, FLOOR, GATE, LAVA, SWITCH, SWITCH_THROWN},
+    math::{distance, floorf},
+    sfx, State,
+};
+
+/// A cell with the tag linking it with a switch.
+pub type Tag = ((i32, i32), u8);
+
+/// What a tagged cell turns into when its switch is thrown, if anything.
+fn flipped(tile: u8) -> Option<u8> {
+    match tile {
+        SWITCH => Some(SWITCH_THROWN),
+        SWITCH_THROWN => Some(SWITCH),
+        GATE => Some(FLOOR),
+        FLOOR => Some(GATE),
+        LAVA => Some(BRIDGE),
+        BRIDGE => Some(LAVA),
+        _ => None,
+    }
+}
+
+impl State {
+    /// Throw the switch at (x, y), returning `true` if there was one there.
+    pub fn throw_switch(&mut self, x: i32, y: i32) -> bool {
+        if !matches!(self.map.tile_at(x, y), SWITCH | SWITCH_THROWN) {
+            return false;
+        }
+
+        let tags = self.level().map.tags;
+        let Some(&(_, tag)) = tags.iter().find(|&&(cell, _)| cell == (x, y)) else {
+            return false;
+        };
+        if self.off_beat() {
+            self.rhythm.hold((x, y));
+            return true;
+        }
+
+        // nothing opens or closes underneath the player.
+        let player_cell = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+
+        // the gate or bridge nearest the player is the one they hear move.
+        let mut nearest: Option<(f32, f32)> = None;
+        for &((cell_x, cell_y), _) in tags.iter().filter(|&&(_, other)| other == tag) {
+            if (cell_x, cell_y) == player_cell {
+                continue;
+            }
+            let tile = self.map.tile_at(cell_x, cell_y);
+            if let Some(flipped) = flipped(tile) {
+                self.map.set_tile(cell_x, cell_y, flipped);
+            }
+
+            let cell = (cell_x as f32 + 0.5, cell_y as f32 + 0.5);
+            let away = |(x, y): (f32, f32)| distance(x - self.player_x, y - self.player_y);
+            let moved = !matches!(tile, SWITCH | SWITCH_THROWN);
+            if moved && nearest.map_or(true, |nearest| away(cell) < away(nearest)) {
+                nearest = Some(cell);
+            }
+        }
+
+        // a clunk, and then a rumble from whatever it worked.
+        sfx::play(&sfx::SWITCH);
+        if let Some((x, y)) = nearest {
+            self.play_sound_at(x, y, &sfx::MECHANISM);
+        }
+        true
+    }
+}
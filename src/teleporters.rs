@@ -0,0 +1,93 @@
+//! Pads that send the player across the map to their partner.
+
+use crate::{map::is_wall, math::floorf, sfx, sprites::Sprite, State};
+
+/// How many updates the screen stays lit up after teleporting.
+const FLASH_FRAMES: u8 = 20;
+
+/// Marks a teleporter pad on the floor.
+pub const PAD: Sprite = Sprite {
+    width: 16,
+    height: 6,
+    data: &[
+        0x00, 0xaa, 0xaa, 0x00, 0x0a, 0xff, 0xff, 0xa0, 0x2f, 0x55, 0x55, 0xf8, 0x2f, 0x55, 0x55,
+        0xf8, 0x0a, 0xff, 0xff, 0xa0, 0x00, 0xaa, 0xaa, 0x00,
+    ],
+};
+
+/// What the player's been doing with the teleporters.
+pub struct Teleporter {
+    /// Whether stepping onto a pad sends the player away. It's cleared when
+    /// they arrive so they aren't sent straight back, and set again once
+    /// they step off.
+    armed: bool,
+    /// How many more updates the flash from the last teleport lasts.
+    flash: u8,
+}
+
+impl Teleporter {
+    pub const fn new() -> Self {
+        Self {
+            armed: true,
+            flash: 0,
+        }
+    }
+}
+
+impl State {
+    /// Send the player to the partner of the pad they're standing on, if
+    /// they've just stepped onto one.
+    pub fn update_teleporter(&mut self) {
+        self.teleporter.flash = self.teleporter.flash.saturating_sub(1);
+
+        let cell = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        let partner = self.level().map.teleporters.iter().find_map(|&(a, b)| {
+            if a == cell {
+                Some(b)
+            } else if b == cell {
+                Some(a)
+            } else {
+                None
+            }
+        });
+
+        let Some((to_x, to_y)) = partner else {
+            self.teleporter.armed = true;
+            return;
+        };
+
+        // wait on the pad until there's room at the other end.
+        if !self.teleporter.armed || self.cell_is_occupied(to_x, to_y) {
+            return;
+        }
+
+        self.player_x = to_x as f32 + 0.5;
+        self.player_y = to_y as f32 + 0.5;
+        self.teleporter = Teleporter {
+            armed: false,
+            flash: FLASH_FRAMES,
+        };
+
+        sfx::play(&sfx::TELEPORT);
+    }
+
+    /// Check if something is in the way of teleporting into a cell, which
+    /// on a streamed map may well be outside the window.
+    fn cell_is_occupied(&self, x: i32, y: i32) -> bool {
+        is_wall(self.map.tile_anywhere(x, y))
+            || self.entities.indexed().any(|(_, entity)| {
+                entity.health.is_some()
+                    && (floorf(entity.x) as i32, floorf(entity.y) as i32) == (x, y)
+            })
+    }
+
+    /// How brightly the screen is lit up from teleporting, from 0.0 to 1.0.
+    pub fn teleport_flash(&self) -> f32 {
+        // a flash is a lot to take in, so it's left out along with the rest.
+        if self.reduce_motion {
+            return 0.0;
+        }
+
+        self.teleporter.flash as f32 / FLASH_FRAMES as f32
+    }
+}
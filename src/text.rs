@@ -0,0 +1,89 @@
+//! Drawing text with WASM-4's built-in font.
+
+extern "C" {
+    #[link_name = "textUtf8"]
+    fn extern_text(text: *const u8, length: usize, x: i32, y: i32);
+    #[link_name = "traceUtf8"]
+    fn extern_trace(text: *const u8, length: usize);
+}
+
+/// How many characters of the built-in font fit across the screen.
+pub const LINE_LENGTH: usize = 20;
+
+/// Draw text with its top-left corner at (x, y), using the first draw color
+/// for the text and the second for the background behind it.
+pub fn text(text: &str, x: i32, y: i32) {
+    unsafe { extern_text(text.as_ptr(), text.len(), x, y) }
+}
+
+/// Write text out to the console, for debugging.
+pub fn trace(text: &str) {
+    unsafe { extern_trace(text.as_ptr(), text.len()) }
+}
+
+/// A single line of text, built up piece by piece without needing an allocator.
+///
+/// Anything past [`LINE_LENGTH`] characters is dropped.
+pub struct Line {
+    buf: [u8; LINE_LENGTH],
+    len: usize,
+}
+
+impl Line {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; LINE_LENGTH],
+            len: 0,
+        }
+    }
+
+    /// Add some text to the end of the line.
+    pub fn push_str(&mut self, s: &str) -> &mut Self {
+        for &byte in s.as_bytes() {
+            self.push_byte(byte);
+        }
+        self
+    }
+
+    /// Add a number to the end of the line, in decimal.
+    pub fn push_number(&mut self, mut n: u32) -> &mut Self {
+        let mut digits = [0; 10];
+        let mut count = 0;
+
+        loop {
+            digits[count] = b'0' + (n % 10) as u8;
+            count += 1;
+            n /= 10;
+
+            if n == 0 {
+                break;
+            }
+        }
+
+        for &digit in digits[..count].iter().rev() {
+            self.push_byte(digit);
+        }
+        self
+    }
+
+    /// Add a number to the end of the line, padded with zeroes to two digits.
+    pub fn push_two_digits(&mut self, n: u32) -> &mut Self {
+        if n < 10 {
+            self.push_byte(b'0');
+        }
+        self.push_number(n)
+    }
+
+    fn push_byte(&mut self, byte: u8) {
+        if self.len < LINE_LENGTH {
+            self.buf[self.len] = byte;
+            self.len += 1;
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        // only whole strings and ascii digits are ever pushed, but a string
+        // cut off part of the way through a character could still be invalid.
+        core::str::from_utf8(&self.buf[..self.len]).unwrap_or("")
+    }
+}
@@ -0,0 +1,93 @@
+//! Small textures kept in RAM, so that they can be drawn into while the game
+//! runs rather than being baked into the cartridge.
+//!
+//! Each texture is in one of a handful of slots held by [`Textures`], and
+//! anything showing one refers to it by its [`Slot`]. Walls draw a texture's
+//! pixels over their own shading, and sprites draw them like any other
+//! graphic's, so the same texture can hang on a wall or stand in the world.
+
+/// How many pixels wide and tall every texture is.
+pub const TEXTURE_SIZE: usize = 16;
+
+/// How many bytes a texture's pixels take up, at four pixels per byte.
+const TEXTURE_BYTES: usize = TEXTURE_SIZE * TEXTURE_SIZE / 4;
+
+/// Each of the textures there's room for.
+#[derive(Clone, Copy)]
+pub enum Slot {
+    /// The score of a versus match.
+    Scoreboard,
+    /// What the level's security camera sees.
+    Monitor,
+}
+
+/// How many slots there are, one for each variant of [`Slot`].
+const SLOTS: usize = 2;
+
+/// A 2bpp image, packed the same way as a [`Sprite`](crate::sprites::Sprite)'s
+/// pixels, where 0 is transparent.
+pub struct Texture {
+    data: [u8; TEXTURE_BYTES],
+}
+
+impl Texture {
+    /// A texture with every pixel transparent.
+    pub const fn new() -> Self {
+        Self {
+            data: [0; TEXTURE_BYTES],
+        }
+    }
+
+    /// Get the value of the pixel at (u, v).
+    pub fn texel(&self, u: usize, v: usize) -> u8 {
+        let idx = v * TEXTURE_SIZE + u;
+        (self.data[idx / 4] >> (6 - (idx % 4) * 2)) & 0b11
+    }
+
+    /// Set the pixel at (u, v) to `color`, which is masked to two bits.
+    pub fn set(&mut self, u: usize, v: usize, color: u8) {
+        let idx = v * TEXTURE_SIZE + u;
+        let shift = 6 - (idx % 4) * 2;
+        let byte = &mut self.data[idx / 4];
+        *byte = (*byte & !(0b11 << shift)) | ((color & 0b11) << shift);
+    }
+
+    /// Set every pixel in the `width` by `height` rectangle with its top left
+    /// corner at (u, v) to `color`, clipped to the edges of the texture.
+    pub fn fill_rect(&mut self, u: usize, v: usize, width: usize, height: usize, color: u8) {
+        for v in v..(v + height).min(TEXTURE_SIZE) {
+            for u in u..(u + width).min(TEXTURE_SIZE) {
+                self.set(u, v, color);
+            }
+        }
+    }
+
+    /// Set every pixel to `color`.
+    pub fn clear(&mut self, color: u8) {
+        self.fill_rect(0, 0, TEXTURE_SIZE, TEXTURE_SIZE, color);
+    }
+}
+
+/// Every texture there's room for, one in each [`Slot`].
+pub struct Textures {
+    slots: [Texture; SLOTS],
+}
+
+impl Textures {
+    pub const fn new() -> Self {
+        const EMPTY: Texture = Texture::new();
+        Self {
+            slots: [EMPTY; SLOTS],
+        }
+    }
+
+    /// The texture in a slot.
+    pub fn get(&self, slot: Slot) -> &Texture {
+        &self.slots[slot as usize]
+    }
+
+    /// The texture in a slot, to draw into.
+    pub fn get_mut(&mut self, slot: Slot) -> &mut Texture {
+        &mut self.slots[slot as usize]
+    }
+}
@@ -0,0 +1,50 @@
+//! Bending time for effect, by skipping whole updates of the game.
+//!
+//! Everything driven by the game's updates (movement, timers, animations)
+//! skips together, so none of it drifts out of step with the rest.
+
+/// How many updates a heavy hit freezes the action for.
+const HIT_STOP_FRAMES: u8 = 2;
+
+/// How many updates slow motion lasts for after killing a boss.
+const SLOW_MOTION_FRAMES: u16 = 90;
+
+pub struct TimeScale {
+    /// How many more updates the action is frozen for.
+    hit_stop: u8,
+    /// How many more updates the action runs at half speed for.
+    slow_motion: u16,
+}
+
+impl TimeScale {
+    pub const fn new() -> Self {
+        Self {
+            hit_stop: 0,
+            slow_motion: 0,
+        }
+    }
+
+    /// Freeze the action for a moment, to sell the weight of a hit.
+    pub fn hit_stop(&mut self) {
+        self.hit_stop = HIT_STOP_FRAMES;
+    }
+
+    /// Run the action at half speed for a while.
+    pub fn slow_motion(&mut self) {
+        self.slow_motion = SLOW_MOTION_FRAMES;
+    }
+
+    /// Advance by one update, returning whether the game should be simulated
+    /// on it.
+    pub fn tick(&mut self) -> bool {
+        if self.hit_stop > 0 {
+            self.hit_stop -= 1;
+            false
+        } else if self.slow_motion > 0 {
+            self.slow_motion -= 1;
+            self.slow_motion % 2 == 0
+        } else {
+            true
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Time attack, where every level is played back to back against one clock.
+//!
+//! The clock only runs while a level is being played, and the run is ranked
+//! at the end against the par times of every level added together. Dying
+//! uses up one of a few continues, and running out of them ends the run.
+
+use crate::{
+    intermission::push_time,
+    levels::LEVELS,
+    text::{text, Line},
+    toasts,
+    ui::Ui,
+    Mode, State, DRAW_COLORS,
+};
+
+/// How many times the player can die before the run is over.
+const CONTINUES: u8 = 3;
+
+/// A time attack run in progress.
+pub struct TimeAttack {
+    /// How many updates were spent on the levels that are already finished.
+    frames: u32,
+    /// How many more times the player can die.
+    continues: u8,
+    /// Whether the player made it through every level.
+    finished: bool,
+}
+
+/// Every level's par time added together, to rank whole runs against.
+const fn total_par_frames() -> u32 {
+    let mut total = 0;
+
+    let mut idx = 0;
+    while idx < LEVELS.len() {
+        total += LEVELS[idx].par_frames;
+        idx += 1;
+    }
+
+    total
+}
+
+/// The rank for finishing every level in `frames` updates.
+const fn rank(frames: u32) -> &'static str {
+    let par = total_par_frames();
+
+    if frames * 4 <= par * 3 {
+        "S"
+    } else if frames <= par {
+        "A"
+    } else if frames * 4 <= par * 5 {
+        "B"
+    } else {
+        "C"
+    }
+}
+
+impl State {
+    /// Start a time attack run from the first level.
+    pub fn start_time_attack(&mut self) {
+        self.time_attack = Some(TimeAttack {
+            frames: 0,
+            continues: CONTINUES,
+            finished: false,
+        });
+        self.score = 0;
+        self.load_level(0);
+    }
+
+    /// How long the current run has taken so far, counting the level being
+    /// played.
+    fn time_attack_frames(&self) -> u32 {
+        self.time_attack
+            .as_ref()
+            .map_or(0, |run| run.frames + self.stats.frames)
+    }
+
+    /// Stop the clock on a finished level, ending the run if it was the last.
+    pub fn finish_time_attack_level(&mut self) {
+        let is_last = self.level == LEVELS.len() - 1;
+        if let Some(run) = &mut self.time_attack {
+            run.frames += self.stats.frames;
+            run.finished = is_last;
+        }
+    }
+
+    /// Check if the player has died one time too many.
+    pub fn out_of_continues(&self) -> bool {
+        self.time_attack
+            .as_ref()
+            .is_some_and(|run| run.continues == 0)
+    }
+
+    /// Use up one of the run's continues, if there's a run going.
+    pub fn use_continue(&mut self) {
+        if let Some(run) = &mut self.time_attack {
+            run.continues = run.continues.saturating_sub(1);
+        }
+    }
+
+    /// Charge for starting the level over part way through a run, just as
+    /// dying would: the time spent on it stays on the clock, and it uses up
+    /// a continue.
+    pub fn charge_time_attack_restart(&mut self) {
+        if let Some(run) = &mut self.time_attack {
+            run.frames += self.stats.frames;
+        }
+        self.use_continue();
+    }
+
+    /// Draw the run's clock and the continues left in the corner of the screen.
+    pub fn draw_time_attack(&self) {
+        let Some(run) = &self.time_attack else {
+            return;
+        };
+
+        unsafe { *DRAW_COLORS = 0x4 };
+        text(
+            push_time(&mut Line::new(), self.time_attack_frames()).as_str(),
+            4,
+            4,
+        );
+        text(
+            Line::new()
+                .push_str("\u{85}")
+                .push_number(run.continues as u32)
+                .as_str(),
+            4,
+            14,
+        );
+    }
+
+    /// Show how the run went, going back to the title screen once the player
+    /// is done looking.
+    pub fn update_ranking(&mut self, pressed: u8) {
+        let Some(run) = &self.time_attack else {
+            return;
+        };
+
+        let mut ui = Ui::new(16, 16, pressed);
+        ui.label("TIME ATTACK");
+        ui.space(8);
+
+        ui.line(push_time(Line::new().push_str("TIME    "), run.frames));
+        ui.line(push_time(
+            Line::new().push_str("PAR     "),
+            total_par_frames(),
+        ));
+        // the death that ended a run didn't get to use up a continue.
+        let deaths = (CONTINUES - run.continues) as u32 + !run.finished as u32;
+        ui.line(Line::new().push_str("DEATHS  ").push_number(deaths));
+        ui.space(8);
+
+        if run.finished {
+            ui.line(Line::new().push_str("RANK    ").push_str(rank(run.frames)));
+        } else {
+            ui.label("OUT OF CONTINUES");
+        }
+        ui.space(8);
+
+        let mut focus = 0;
+        if ui.menu(&["TITLE"], &mut focus).is_some() {
+            self.transition_to(Mode::Title);
+        }
+    }
+
+    /// Move on to the ranking screen, keeping track of the best run.
+    pub fn end_time_attack(&mut self) {
+        // the player might still be dying while the screen fades out.
+        if self.in_transition() {
+            return;
+        }
+
+        let Some(run) = &mut self.time_attack else {
+            return;
+        };
+
+        // a run that ended early still counts the level it ended on.
+        if !run.finished {
+            run.frames += self.stats.frames;
+        }
+
+        if run.finished && self.best_time_attack.map_or(true, |best| run.frames < best) {
+            self.best_time_attack = Some(run.frames);
+            toasts::push(&toasts::CLOCK, "NEW RECORD!");
+        }
+
+        self.transition_to(Mode::Ranking);
+    }
+}
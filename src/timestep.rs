@@ -0,0 +1,12 @@
+//! The fixed timestep the game is simulated at.
+//!
+//! WASM-4 calls `update` sixty times a second, and each call simulates
+//! exactly one step of the game. Speeds and rates are written per second,
+//! and multiplied by [`DT`] to get how much they change things by on each
+//! step, so tuning them doesn't mean thinking in frames.
+
+/// How many steps of the game are simulated each second.
+pub const STEPS_PER_SECOND: u32 = 60;
+
+/// How many seconds each step of the game covers.
+pub const DT: f32 = 1.0 / STEPS_PER_SECOND as f32;
@@ -0,0 +1,92 @@
+//! The title screen shown when the game starts.
+
+use crate::{save, sprites::EXIT_DOOR, ui::Ui, Mode, State};
+
+/// Where the title screen's menu has its "VERSUS" item.
+pub const VERSUS_ITEM: usize = 4;
+/// Where the title screen's menu has its "OPTIONS" item.
+pub const OPTIONS_ITEM: usize = 5;
+/// Where the title screen's menu has its "MUTATORS" item.
+pub const MUTATORS_ITEM: usize = 6;
+/// Where the title screen's menu has its "CODEX" item.
+pub const CODEX_ITEM: usize = 7;
+/// Where the title screen's menu has its item for picking the difficulty.
+const DIFFICULTY_ITEM: usize = 8;
+
+impl State {
+    /// Show the title screen's menu, starting the game when the player picks it.
+    pub fn update_title(&mut self, pressed: u8) {
+        self.title_frames = self.title_frames.wrapping_add(1);
+
+        let mut ui = Ui::new(24, 4, pressed);
+
+        ui.icon(&EXIT_DOOR);
+        ui.label("W4 RAYCASTER");
+        ui.space(4);
+
+        let items = [
+            "CONTINUE",
+            "START",
+            "RANDOM DUNGEON",
+            "TIME ATTACK",
+            "VERSUS",
+            "OPTIONS",
+            "MUTATORS",
+            "CODEX",
+            self.difficulty.menu_item(),
+        ];
+        // "CONTINUE" is only there once there's been progress saved, with
+        // the rest of the menu numbered as if it always was.
+        let progress = save::saved_progress();
+        let first = if progress.is_some() { 0 } else { 1 };
+        let mut focus = self.menu_focus.saturating_sub(first);
+        let picked = ui
+            .menu(&items[first..], &mut focus)
+            .map(|item| item + first);
+        self.menu_focus = focus + first;
+
+        match picked {
+            Some(0) => {
+                if let Some(progress) = progress {
+                    self.shuffle_seed = self.title_frames;
+                    self.continue_game(progress);
+                    self.transition_to(Mode::Playing);
+                }
+            }
+            Some(1) => {
+                self.time_attack = None;
+                self.shuffle_seed = self.title_frames;
+                self.score = 0;
+                self.load_level(0);
+                self.transition_to(Mode::Playing);
+            }
+            Some(2) => {
+                self.time_attack = None;
+                self.score = 0;
+                self.load_dungeon(self.title_frames);
+                self.transition_to(Mode::Playing);
+            }
+            Some(3) => {
+                self.shuffle_seed = self.title_frames;
+                self.start_time_attack();
+                self.transition_to(Mode::Playing);
+            }
+            Some(VERSUS_ITEM) => self.transition_to(Mode::Versus),
+            Some(OPTIONS_ITEM) => {
+                self.pause.in_options = false;
+                self.mode = Mode::Options;
+                self.menu_focus = 0;
+            }
+            Some(MUTATORS_ITEM) => {
+                self.mode = Mode::Mutators;
+                self.menu_focus = 0;
+            }
+            Some(CODEX_ITEM) => self.mode = Mode::Codex { page: 0 },
+            Some(DIFFICULTY_ITEM) => self.difficulty = self.difficulty.next(),
+            _ => {}
+        }
+
+        ui.space(4);
+        ui.hint("\u{86}\u{87} CHOOSE  \u{80} PICK");
+    }
+}
@@ -0,0 +1,124 @@
+//! Small notices that slide in from the corner of the screen.
+//!
+//! Unlike [`messages`](crate::messages), toasts never wait for the player:
+//! each slides in, sits there for a moment, and slides back out on its own.
+
+use crate::{framebuffer::SCREEN_SIZE, math::ease_in_out, sprites::Sprite, ui::Ui};
+
+/// The most toasts that can be waiting to be shown at once.
+const CAPACITY: usize = 4;
+
+/// How many updates it takes a toast to slide in or out.
+const SLIDE_FRAMES: u16 = 15;
+/// How many updates a toast stays on screen between sliding in and out.
+const SHOW_FRAMES: u16 = 120;
+
+/// How big the box around a toast is, in pixels.
+const WIDTH: i32 = 112;
+const HEIGHT: i32 = 20;
+
+/// The gap between a toast and the edges of the screen.
+const MARGIN: i32 = 4;
+
+pub const TROPHY: Sprite = Sprite {
+    width: 8,
+    height: 8,
+    data: &[
+        0xff, 0xff, 0xea, 0xab, 0x3a, 0xac, 0x0e, 0xb0, 0x03, 0xc0, 0x03, 0xc0, 0x0f, 0xf0, 0x3f,
+        0xfc,
+    ],
+};
+
+pub const CLOCK: Sprite = Sprite {
+    width: 8,
+    height: 8,
+    data: &[
+        0x0f, 0xf0, 0x35, 0x5c, 0xd5, 0xd7, 0xd5, 0xd7, 0xd7, 0xd7, 0xd5, 0x57, 0x35, 0x5c, 0x0f,
+        0xf0,
+    ],
+};
+
+/// A notice waiting to be shown, with an icon beside its text.
+#[derive(Clone, Copy)]
+struct Toast {
+    icon: &'static Sprite,
+    text: &'static str,
+}
+
+/// Toasts waiting to be shown, with the one on screen at the front.
+struct Toasts {
+    queue: [Option<Toast>; CAPACITY],
+    len: usize,
+    /// How many updates the front toast has been on screen for.
+    frames: u16,
+}
+
+static mut TOASTS: Toasts = Toasts {
+    queue: [None; CAPACITY],
+    len: 0,
+    frames: 0,
+};
+
+/// Queue up a toast. Its text should fit on one line of 11 characters.
+///
+/// The toast is dropped if too many are already waiting.
+pub fn push(icon: &'static Sprite, text: &'static str) {
+    unsafe {
+        if TOASTS.len < CAPACITY {
+            TOASTS.queue[TOASTS.len] = Some(Toast { icon, text });
+            TOASTS.len += 1;
+        }
+    }
+}
+
+/// How many toasts are waiting, including the one on screen, and how many
+/// there's room for.
+pub fn usage() -> (usize, usize) {
+    unsafe { (TOASTS.len, CAPACITY) }
+}
+
+/// Throw away every toast, including the one on screen.
+pub fn clear() {
+    unsafe {
+        TOASTS = Toasts {
+            queue: [None; CAPACITY],
+            len: 0,
+            frames: 0,
+        }
+    }
+}
+
+/// Draw the toast on screen, if there is one, and move it along.
+pub fn update() {
+    unsafe { TOASTS.update() }
+}
+
+impl Toasts {
+    fn update(&mut self) {
+        let Some(toast) = self.queue[0] else {
+            return;
+        };
+
+        // how far the toast has slid in, from 0.0 (off screen) to 1.0.
+        let shown = if self.frames < SLIDE_FRAMES {
+            self.frames as f32 / SLIDE_FRAMES as f32
+        } else if self.frames < SLIDE_FRAMES + SHOW_FRAMES {
+            1.0
+        } else {
+            (2 * SLIDE_FRAMES + SHOW_FRAMES - self.frames) as f32 / SLIDE_FRAMES as f32
+        };
+
+        let x = SCREEN_SIZE as i32 - ((WIDTH + MARGIN) as f32 * ease_in_out(shown)) as i32;
+        Ui::new(x, MARGIN, 0)
+            .dialog(WIDTH as u32, HEIGHT as u32)
+            .icon_label(toast.icon, toast.text);
+
+        self.frames += 1;
+        if self.frames == 2 * SLIDE_FRAMES + SHOW_FRAMES {
+            self.queue.rotate_left(1);
+            self.queue[CAPACITY - 1] = None;
+            self.len -= 1;
+            self.frames = 0;
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Fades between screens.
+//!
+//! A transition fades every color out to the lightest one, switches screens
+//! while nothing can be seen, then fades the new screen back in.
+
+use crate::{math::ease_in_out, title, Mode, State};
+
+/// How many updates it takes to fade out, and then again to fade back in.
+const FADE_FRAMES: u16 = 20;
+
+/// A change of screen that is in progress.
+pub struct Transition {
+    /// The screen being switched to.
+    to: Mode,
+    /// How many updates the transition has been running for.
+    frames: u16,
+}
+
+impl State {
+    /// Fade over to another screen, unless a transition is already running.
+    pub fn transition_to(&mut self, to: Mode) {
+        if self.transition.is_none() {
+            self.transition = Some(Transition { to, frames: 0 });
+        }
+    }
+
+    /// Whether a transition is running, during which the player can't do anything.
+    pub fn in_transition(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Advance the current transition, switching screens halfway through.
+    pub fn update_transition(&mut self) {
+        let Some(transition) = &mut self.transition else {
+            return;
+        };
+
+        transition.frames += 1;
+
+        if transition.frames == FADE_FRAMES {
+            let to = transition.to;
+
+            match (self.mode, to) {
+                (Mode::Intermission, Mode::Gallery) => self.start_gallery(),
+                (_, Mode::Versus) => self.start_versus(),
+                (Mode::Versus, _) => self.menu_focus = title::VERSUS_ITEM,
+                (_, Mode::Ranking) => {}
+                // a run is over once its ranking has been seen.
+                (Mode::Ranking, _) => self.time_attack = None,
+                // otherwise, leaving the stats screen or the shooting gallery
+                // means going on to the next level.
+                (Mode::Intermission | Mode::Gallery, _) => {
+                    self.load_next_level();
+                    self.save_progress();
+                }
+                (Mode::Dying { .. }, Mode::Playing) => self.respawn(),
+                (Mode::Dying { .. }, Mode::GameOver) => self.menu_focus = 0,
+                (Mode::GameOver, Mode::Playing) => self.retry_level(),
+                (Mode::Paused, Mode::Playing) => {
+                    self.charge_time_attack_restart();
+                    self.retry_level();
+                }
+                // giving up part way through a time attack ends the run.
+                (Mode::Paused, _) => {
+                    self.time_attack = None;
+                    self.menu_focus = 0;
+                }
+                (Mode::GameOver, _) => self.menu_focus = 0,
+                _ => {}
+            }
+            self.mode = to;
+        } else if transition.frames == 2 * FADE_FRAMES {
+            self.transition = None;
+        }
+    }
+
+    /// How far the screen has faded out, from 0.0 (not at all) to 1.0
+    /// (completely).
+    pub fn fade(&self) -> f32 {
+        let Some(transition) = &self.transition else {
+            return 0.0;
+        };
+
+        let fade = if transition.frames < FADE_FRAMES {
+            transition.frames as f32 / FADE_FRAMES as f32
+        } else {
+            (2 * FADE_FRAMES - transition.frames) as f32 / FADE_FRAMES as f32
+        };
+
+        ease_in_out(fade)
+    }
+}
@@ -0,0 +1,55 @@
+//! Invisible triggers that set off scripted events when the player walks
+//! into them, like an ambush springing or a gate slamming shut behind them.
+//!
+//! Each trigger only goes off once per attempt at the level, and a level
+//! can have up to 32 of them.
+
+use crate::{entities::Entity, map::GATE, math::floorf, messages, sfx, State};
+
+/// Something that happens when a trigger goes off.
+pub enum Event {
+    /// Show the player a message.
+    Message(&'static str),
+    /// Put a new entity on the map.
+    Spawn(Entity),
+    /// Close a gate across a cell.
+    Close(i32, i32),
+}
+
+/// A cell that sets off an event when the player walks into it.
+pub struct Trigger {
+    pub cell: (i32, i32),
+    pub event: Event,
+}
+
+impl State {
+    /// Set off every trigger in the cell the player is standing in that
+    /// hasn't gone off yet.
+    pub fn update_triggers(&mut self) {
+        let player_cell = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+
+        for (idx, trigger) in self.level().map.triggers.iter().enumerate() {
+            if trigger.cell != player_cell || self.triggered & (1 << idx) != 0 {
+                continue;
+            }
+            self.triggered |= 1 << idx;
+
+            match trigger.event {
+                Event::Message(message) => messages::push(message),
+                Event::Spawn(entity) => {
+                    if self.entities.insert(entity).is_some() && entity.health.is_some() {
+                        self.stats.kills_total += 1;
+                    }
+                }
+                // a gate can't close on the player.
+                Event::Close(x, y) if (x, y) != player_cell => {
+                    self.map.set_tile(x, y, GATE);
+                    // heard from wherever the gate is.
+                    let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+                    self.play_sound_at(x, y, &sfx::GATE_SLAM);
+                }
+                Event::Close(..) => {}
+            }
+        }
+    }
+}
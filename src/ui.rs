@@ -0,0 +1,231 @@
+//! A tiny immediate-mode UI for menus and overlays.
+//!
+//! Every screen builds its UI from scratch each update by calling widget
+//! methods on a [`Ui`], which lays them out one under another and hands the
+//! d-pad and buttons to whichever widget can use them.
+
+use crate::{
+    blit, blit_sub, rect,
+    sprites::Sprite,
+    text::{text, Line},
+    BUTTON_1, BUTTON_DOWN, BUTTON_UP, DRAW_COLORS,
+};
+
+/// The width and height of a character in the built-in font.
+const CHAR_SIZE: i32 = 8;
+
+/// The space left between widgets, in pixels.
+const SPACING: i32 = 4;
+
+/// How far inside a dialog's frame its contents start.
+const PADDING: i32 = 6;
+
+/// The `DRAW_COLORS` for text.
+const TEXT_COLORS: u16 = 0x4;
+/// The `DRAW_COLORS` for dimmed text.
+const DIM_TEXT_COLORS: u16 = 0x3;
+/// The `DRAW_COLORS` for filled boxes with an outline.
+const BOX_COLORS: u16 = 0x41;
+/// The `DRAW_COLORS` for [`PANEL`], drawing its insides in the lightest color.
+const PANEL_COLORS: u16 = 0x4310;
+
+/// The draw flag telling `blit` that a sprite has two bits per pixel.
+const BLIT_2BPP: u32 = 1;
+
+/// The frame drawn around dialogs, cut into a 3x3 grid of [`SLICE`]-sized
+/// pieces. The corners are drawn as-is, the edges are repeated to fit, and the
+/// middle is filled with the lightest color.
+const PANEL: Sprite = Sprite {
+    width: 12,
+    height: 12,
+    data: &[
+        0x3f, 0xff, 0xfc, 0xd5, 0x55, 0x57, 0xda, 0xaa, 0xa7, 0xd9, 0x55, 0x67, 0xd9, 0x55, 0x67,
+        0xd9, 0x55, 0x67, 0xd9, 0x55, 0x67, 0xd9, 0x55, 0x67, 0xd9, 0x55, 0x67, 0xda, 0xaa, 0xa7,
+        0xd5, 0x55, 0x57, 0x3f, 0xff, 0xfc,
+    ],
+};
+
+/// The width and height of each piece of [`PANEL`].
+const SLICE: i32 = 4;
+
+/// Draw a [`PANEL`] frame `width` by `height` pixels in size, which should
+/// both be at least two slices.
+fn panel(x: i32, y: i32, width: i32, height: i32) {
+    let (right, bottom) = (x + width - SLICE, y + height - SLICE);
+
+    // draw one piece of the panel from the slice at (col, row), cut down to
+    // `width` by `height` pixels.
+    let piece = |x, y, col, row, width, height| unsafe {
+        blit_sub(
+            PANEL.data.as_ptr(),
+            x,
+            y,
+            width as u32,
+            height as u32,
+            (col * SLICE) as u32,
+            (row * SLICE) as u32,
+            PANEL.width as u32,
+            BLIT_2BPP,
+        );
+    };
+
+    unsafe {
+        *DRAW_COLORS = PANEL_COLORS;
+    }
+
+    for edge_x in (x + SLICE..right).step_by(SLICE as usize) {
+        let width = SLICE.min(right - edge_x);
+        piece(edge_x, y, 1, 0, width, SLICE);
+        piece(edge_x, bottom, 1, 2, width, SLICE);
+    }
+
+    for edge_y in (y + SLICE..bottom).step_by(SLICE as usize) {
+        let height = SLICE.min(bottom - edge_y);
+        piece(x, edge_y, 0, 1, SLICE, height);
+        piece(right, edge_y, 2, 1, SLICE, height);
+    }
+
+    piece(x, y, 0, 0, SLICE, SLICE);
+    piece(right, y, 2, 0, SLICE, SLICE);
+    piece(x, bottom, 0, 2, SLICE, SLICE);
+    piece(right, bottom, 2, 2, SLICE, SLICE);
+
+    unsafe {
+        *DRAW_COLORS = 0x11;
+        rect(
+            x + SLICE,
+            y + SLICE,
+            (width - 2 * SLICE) as u32,
+            (height - 2 * SLICE) as u32,
+        );
+    }
+}
+
+/// Lays out widgets from top to bottom, starting at a point on screen.
+pub struct Ui {
+    x: i32,
+    y: i32,
+    /// The buttons that were first pressed on this update.
+    pressed: u8,
+}
+
+impl Ui {
+    /// Start laying out widgets with the top-left corner at (x, y).
+    pub fn new(x: i32, y: i32, pressed: u8) -> Self {
+        Self { x, y, pressed }
+    }
+
+    /// Leave some empty space before the next widget.
+    pub fn space(&mut self, pixels: i32) {
+        self.y += pixels;
+    }
+
+    /// Draw a line of text.
+    pub fn label(&mut self, label: &str) {
+        self.text(label, TEXT_COLORS);
+    }
+
+    /// Draw a line of text that's less important than the rest.
+    pub fn hint(&mut self, hint: &str) {
+        self.text(hint, DIM_TEXT_COLORS);
+    }
+
+    /// Draw a line of text built up from several pieces.
+    pub fn line(&mut self, line: &Line) {
+        self.label(line.as_str());
+    }
+
+    fn text(&mut self, label: &str, colors: u16) {
+        unsafe { *DRAW_COLORS = colors };
+        text(label, self.x, self.y);
+
+        let lines = label.bytes().filter(|&byte| byte == b'\n').count() as i32 + 1;
+        self.y += lines * CHAR_SIZE + SPACING;
+    }
+
+    /// Draw a bar `width` pixels wide, filled in proportion to `value / max`.
+    pub fn bar(&mut self, value: u32, max: u32, width: u32) {
+        let filled = if max == 0 {
+            width
+        } else {
+            width * value.min(max) / max
+        };
+
+        unsafe {
+            *DRAW_COLORS = BOX_COLORS;
+            rect(self.x, self.y, width, 6);
+
+            *DRAW_COLORS = 0x33;
+            rect(self.x + 1, self.y + 1, filled.saturating_sub(2), 4);
+        }
+
+        self.y += 6 + SPACING;
+    }
+
+    /// Draw a sprite at its actual size.
+    pub fn icon(&mut self, sprite: &Sprite) {
+        unsafe {
+            *DRAW_COLORS = 0x4320;
+            blit(
+                sprite.data.as_ptr(),
+                self.x,
+                self.y,
+                sprite.width as u32,
+                sprite.height as u32,
+                BLIT_2BPP,
+            );
+        }
+
+        self.y += sprite.height as i32 + SPACING;
+    }
+
+    /// Draw a sprite with a line of text beside it.
+    pub fn icon_label(&mut self, sprite: &Sprite, label: &str) {
+        let y = self.y;
+        self.icon(sprite);
+        let below = self.y;
+
+        self.x += sprite.width as i32 + SPACING;
+        self.y = y;
+        self.label(label);
+        self.x -= sprite.width as i32 + SPACING;
+
+        self.y = self.y.max(below);
+    }
+
+    /// Draw a list of choices, moving `focus` up and down it with the d-pad.
+    ///
+    /// Returns the index of the focused item if the player picked it this update.
+    pub fn menu(&mut self, items: &[&str], focus: &mut usize) -> Option<usize> {
+        if self.pressed & BUTTON_UP != 0 {
+            *focus = focus.checked_sub(1).unwrap_or(items.len() - 1);
+        }
+        if self.pressed & BUTTON_DOWN != 0 {
+            *focus = (*focus + 1) % items.len();
+        }
+
+        for (idx, item) in items.iter().enumerate() {
+            let mut line = Line::new();
+            line.push_str(if idx == *focus { "\u{85}" } else { " " })
+                .push_str(item);
+
+            if idx == *focus {
+                self.text(line.as_str(), TEXT_COLORS);
+            } else {
+                self.text(line.as_str(), DIM_TEXT_COLORS);
+            }
+        }
+
+        (self.pressed & BUTTON_1 != 0).then_some(*focus)
+    }
+
+    /// Draw a framed box `width` by `height` pixels in size, returning a `Ui`
+    /// for laying out its contents.
+    pub fn dialog(&mut self, width: u32, height: u32) -> Ui {
+        panel(self.x, self.y, width as i32, height as i32);
+
+        let inner = Ui::new(self.x + PADDING, self.y + PADDING, self.pressed);
+        self.y += height as i32 + SPACING;
+        inner
+    }
+}
@@ -0,0 +1,364 @@
+//! Split-screen deathmatch for two players on one console, as an experiment.
+//!
+//! The first gamepad plays on the top half of the screen and the second on
+//! the bottom. Each half is as wide as the normal view but only half as
+//! tall, so the world is drawn full size from each player's point of view
+//! and only the band of rows around the horizon is kept.
+//!
+//! Scoreboards hung on the arena's outer walls, and standing between its
+//! middle pillars, keep both players up to date with the score without
+//! taking their eyes off the fight.
+//!
+//! For anyone watching rather than playing, the first gamepad's Z button
+//! swaps the split views for an overview of the whole arena from above.
+
+use core::f32::consts::FRAC_PI_4;
+
+use crate::{
+    audio::walls_between,
+    auto_walk::AutoWalk,
+    crouch::Crouch,
+    dash::Dash,
+    entities::{Entities, Entity, BODY_RADIUS},
+    framebuffer::{ColumnStage, SCREEN_SIZE},
+    jump::Jump,
+    map::{Map, BRICK, FLOOR, SCOREBOARD, STONE},
+    math::{cosf, fabsf, sinf},
+    rect,
+    scoreboard::draw_scoreboard,
+    sfx::{self, Pan},
+    sprites::{Graphic, Tint, PLAYER},
+    text::{text, Line},
+    texture::Slot,
+    ui::Ui,
+    Mode, State, BUTTON_1, BUTTON_2, BUTTON_DOWN, BUTTON_LEFT, BUTTON_RIGHT, BUTTON_UP,
+    DRAW_COLORS, NEAR_DISTANCE,
+};
+
+const GAMEPAD2: *const u8 = 0x17 as *const u8;
+
+/// How many frags it takes to win a match.
+const FRAG_LIMIT: u8 = 5;
+/// How far a shot reaches, in map units.
+const SHOT_RANGE: f32 = 8.0;
+
+/// The size of the arena, in cells.
+const ARENA_SIZE: i32 = 12;
+/// Pillars around the arena, for hiding behind.
+const PILLARS: [(i32, i32); 6] = [(3, 3), (8, 3), (5, 5), (6, 6), (3, 8), (8, 8)];
+/// Where the scoreboards hang, one in the middle of each outer wall.
+const SCOREBOARDS: [(i32, i32); 4] = [(6, 0), (5, 11), (0, 5), (11, 6)];
+/// Where the freestanding scoreboards are, in the gaps between the middle
+/// pillars.
+const STANDING_SCOREBOARDS: [(f32, f32); 2] = [(6.5, 5.5), (5.5, 6.5)];
+/// Where each player starts, and goes back to after being fragged, facing
+/// across the arena towards the other.
+const SPAWNS: [(f32, f32, f32); 2] = [(1.5, 1.5, -FRAC_PI_4), (10.5, 10.5, 3.0 * FRAC_PI_4)];
+
+/// How many rows of the screen each player's view takes up.
+const VIEW_ROWS: usize = SCREEN_SIZE / 2;
+/// The first row of the full-size view that's kept, so that the horizon
+/// stays in the middle of each half.
+const BAND_TOP: usize = (SCREEN_SIZE - VIEW_ROWS) / 2;
+
+/// How many pixels apart the dots showing which way each player is facing
+/// are on the overview, and how many of them there are.
+const FACING_SPACING: f32 = 3.0;
+const FACING_DOTS: i32 = 3;
+
+/// Everything about one of the players that isn't shared with the other.
+#[derive(Clone, Copy)]
+struct Fighter {
+    x: f32,
+    y: f32,
+    angle: f32,
+    velocity: (f32, f32),
+    turn_velocity: f32,
+    frags: u8,
+}
+
+impl Fighter {
+    /// A player standing at one of the [`SPAWNS`].
+    const fn spawn(idx: usize) -> Self {
+        let (x, y, angle) = SPAWNS[idx];
+        Self {
+            x,
+            y,
+            angle,
+            velocity: (0.0, 0.0),
+            turn_velocity: 0.0,
+            frags: 0,
+        }
+    }
+}
+
+/// How the current match is going.
+pub struct Versus {
+    fighters: [Fighter; 2],
+    /// The second gamepad's state on the previous update, to tell when its
+    /// buttons are first pressed.
+    previous_gamepad: u8,
+    /// Whether the whole arena is being shown from above, rather than from
+    /// each player's point of view.
+    overview: bool,
+}
+
+impl Versus {
+    pub const fn new() -> Self {
+        Self {
+            fighters: [Fighter::spawn(0), Fighter::spawn(1)],
+            previous_gamepad: 0,
+            overview: false,
+        }
+    }
+
+    /// The player who has won the match, if either has yet.
+    fn winner(&self) -> Option<usize> {
+        self.fighters
+            .iter()
+            .position(|fighter| fighter.frags >= FRAG_LIMIT)
+    }
+}
+
+/// Build the arena the match is played in.
+fn arena() -> Map {
+    let mut map = Map::filled(ARENA_SIZE as usize, ARENA_SIZE as usize, STONE);
+
+    for y in 1..ARENA_SIZE - 1 {
+        for x in 1..ARENA_SIZE - 1 {
+            map.set_tile(x, y, FLOOR);
+        }
+    }
+    for (x, y) in PILLARS {
+        map.set_tile(x, y, BRICK);
+    }
+    for (x, y) in SCOREBOARDS {
+        map.set_tile(x, y, SCOREBOARD);
+    }
+
+    map
+}
+
+impl State {
+    /// Set up a new match, with the players in opposite corners of the arena.
+    pub fn start_versus(&mut self) {
+        self.map = arena();
+        // the arena is drawn in the first level's colors.
+        (self.level, self.dungeon) = (0, None);
+        self.versus = Versus::new();
+        self.crouch = Crouch::new();
+        self.jump = Jump::new();
+        self.dash = Dash::new();
+        self.auto_walk = AutoWalk::new();
+        draw_scoreboard(self.textures.get_mut(Slot::Scoreboard), [0, 0]);
+        self.menu_focus = 0;
+    }
+
+    /// Move both players and let them shoot at each other, then draw the
+    /// screen split between them.
+    pub fn update_versus(&mut self, gamepad: u8, pressed: u8) {
+        let gamepad2 = if self.in_transition() {
+            0
+        } else {
+            unsafe { *GAMEPAD2 }
+        };
+        let pressed2 = gamepad2 & !self.versus.previous_gamepad;
+        self.versus.previous_gamepad = gamepad2;
+
+        if pressed & BUTTON_2 != 0 {
+            self.versus.overview = !self.versus.overview;
+        }
+
+        if let Some(winner) = self.versus.winner() {
+            self.draw_match();
+            self.draw_versus_results(winner, pressed);
+            return;
+        }
+
+        for (idx, (gamepad, pressed)) in [(gamepad, pressed), (gamepad2, pressed2)]
+            .into_iter()
+            .enumerate()
+        {
+            self.take_fighter(idx);
+            self.steer(
+                gamepad & BUTTON_UP != 0,
+                gamepad & BUTTON_DOWN != 0,
+                gamepad & BUTTON_LEFT != 0,
+                gamepad & BUTTON_RIGHT != 0,
+                0.0,
+                false,
+                false,
+            );
+            self.put_fighter(idx);
+
+            if pressed & BUTTON_1 != 0 {
+                self.shoot(idx);
+            }
+        }
+
+        self.draw_match();
+    }
+
+    /// Draw the match however it's being shown.
+    fn draw_match(&mut self) {
+        if self.versus.overview {
+            self.draw_overview();
+        } else {
+            self.draw_versus();
+        }
+    }
+
+    /// Stand the camera where one of the players is, with the other player
+    /// and the freestanding scoreboards as the only things in the world to see.
+    fn take_fighter(&mut self, idx: usize) {
+        let fighter = self.versus.fighters[idx];
+        (self.player_x, self.player_y, self.player_angle) = (fighter.x, fighter.y, fighter.angle);
+        self.player_velocity = fighter.velocity;
+        self.player_turn_velocity = fighter.turn_velocity;
+
+        let other = 1 - idx;
+        let (x, y) = (self.versus.fighters[other].x, self.versus.fighters[other].y);
+        self.entities = Entities::new(&[]);
+        self.entities.insert(Entity {
+            tint: Tint::Team(other as u8),
+            ..Entity::new(x, y, Graphic::Sprite(&PLAYER))
+        });
+        for (x, y) in STANDING_SCOREBOARDS {
+            self.entities
+                .insert(Entity::new(x, y, Graphic::Texture(Slot::Scoreboard)));
+        }
+    }
+
+    /// Keep wherever the camera has moved to as one of the players.
+    fn put_fighter(&mut self, idx: usize) {
+        let fighter = &mut self.versus.fighters[idx];
+        (fighter.x, fighter.y, fighter.angle) = (self.player_x, self.player_y, self.player_angle);
+        fighter.velocity = self.player_velocity;
+        fighter.turn_velocity = self.player_turn_velocity;
+    }
+
+    /// Fire a shot straight ahead of one of the players, fragging the other
+    /// if it hits them and sending them back to where they started.
+    fn shoot(&mut self, idx: usize) {
+        self.take_fighter(idx);
+
+        let other = 1 - idx;
+        let target = self.versus.fighters[other];
+        let (depth, lateral) = self.to_camera(target.x, target.y);
+
+        // the top player's sounds come out of the left speaker, and the
+        // bottom player's out of the right.
+        let pan = if idx == 0 { Pan::Left } else { Pan::Right };
+        sfx::play_panned(&sfx::VERSUS_SHOT, pan);
+
+        let hit = (NEAR_DISTANCE..SHOT_RANGE).contains(&depth)
+            && fabsf(lateral) < 2.0 * BODY_RADIUS
+            && walls_between(
+                &self.map,
+                (self.player_x, self.player_y),
+                (target.x, target.y),
+            ) == 0;
+        if !hit {
+            return;
+        }
+
+        self.versus.fighters[idx].frags += 1;
+        self.versus.fighters[other] = Fighter {
+            frags: target.frags,
+            ..Fighter::spawn(other)
+        };
+        let [first, second] = self.versus.fighters;
+        draw_scoreboard(
+            self.textures.get_mut(Slot::Scoreboard),
+            [first.frags, second.frags],
+        );
+        sfx::play(&sfx::FRAG);
+    }
+
+    /// Draw each player's view on their half of the screen, with their frags
+    /// in its corner.
+    fn draw_versus(&mut self) {
+        // the bottom player's view goes first, so the camera is left with
+        // the top player like everywhere else.
+        let band = BAND_TOP..BAND_TOP + VIEW_ROWS;
+        self.take_fighter(1);
+        self.cast_view();
+        self.draw_view_on(ColumnStage::band(band.clone(), VIEW_ROWS));
+
+        self.take_fighter(0);
+        self.cast_view();
+        self.draw_view_on(ColumnStage::band(band, 0));
+
+        unsafe { *DRAW_COLORS = 0x4 };
+        unsafe { rect(0, VIEW_ROWS as i32 - 1, SCREEN_SIZE as u32, 1) };
+
+        for (idx, fighter) in self.versus.fighters.iter().enumerate() {
+            let mut line = Line::new();
+            line.push_str(if idx == 0 { "P1 " } else { "P2 " })
+                .push_number(fighter.frags as u32);
+            text(line.as_str(), 4, (idx * VIEW_ROWS) as i32 + 4);
+        }
+    }
+
+    /// Draw the whole arena from above, scaled up to fill the screen, with
+    /// each player's position, which way they're facing, and their frags.
+    fn draw_overview(&self) {
+        let cell_size = SCREEN_SIZE as i32 / ARENA_SIZE;
+        let origin = (SCREEN_SIZE as i32 - cell_size * ARENA_SIZE) / 2;
+
+        unsafe {
+            *DRAW_COLORS = 0x11;
+            rect(0, 0, SCREEN_SIZE as u32, SCREEN_SIZE as u32);
+
+            *DRAW_COLORS = 0x22;
+        }
+        self.draw_walls((origin, origin), cell_size, false);
+
+        unsafe {
+            // the first player is filled in, and the second is an outline.
+            for (fighter, colors) in self.versus.fighters.iter().zip([0x44, 0x41]) {
+                let x = origin + (fighter.x * cell_size as f32) as i32;
+                let y = origin + (fighter.y * cell_size as f32) as i32;
+                *DRAW_COLORS = colors;
+                rect(x - 2, y - 2, 4, 4);
+
+                *DRAW_COLORS = 0x44;
+                let (dir_x, dir_y) = (cosf(fighter.angle), -sinf(fighter.angle));
+                for dot in 1..=FACING_DOTS {
+                    let distance = 2.0 + dot as f32 * FACING_SPACING;
+                    let dot_x = x + (dir_x * distance) as i32;
+                    let dot_y = y + (dir_y * distance) as i32;
+                    rect(dot_x, dot_y, 1, 1);
+                }
+            }
+
+            *DRAW_COLORS = 0x4;
+        }
+
+        let [first, second] = self.versus.fighters;
+        let mut line = Line::new();
+        line.push_str("P1 ")
+            .push_number(first.frags as u32)
+            .push_str("  P2 ")
+            .push_number(second.frags as u32);
+        text(line.as_str(), origin + 2, origin + 2);
+    }
+
+    /// Show who won, letting the first player pick whether to go again.
+    fn draw_versus_results(&mut self, winner: usize, pressed: u8) {
+        let mut ui = Ui::new(16, 48, pressed);
+        let mut dialog = ui.dialog(128, 60);
+        dialog.label(if winner == 0 {
+            "PLAYER 1 WINS!"
+        } else {
+            "PLAYER 2 WINS!"
+        });
+        dialog.space(4);
+
+        match dialog.menu(&["REMATCH", "QUIT"], &mut self.menu_focus) {
+            Some(0) => self.transition_to(Mode::Versus),
+            Some(_) => self.transition_to(Mode::Title),
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Water on the floor, which the player has to wade through.
+//!
+//! Wading is half as fast as walking, which comes from the water's
+//! [`FloorAttributes`](crate::map::FloorAttributes) like any other floor's.
+//! While the player's in it, the whole screen is tinted blue, and stepping
+//! or landing in it makes a splash.
+
+use crate::{
+    map::{WorldMap, WATER},
+    math::floorf,
+    sfx, State,
+};
+
+/// The colors the screen is tinted towards while the player's in water,
+/// from lightest to darkest.
+pub const TINT: [u32; 4] = [0xc8e0f0, 0x6c98c0, 0x2c4870, 0x081428];
+/// How far the screen is tinted towards [`TINT`].
+const TINT_AMOUNT: f32 = 0.35;
+
+impl State {
+    /// Check if the player is standing in water, rather than jumping over it.
+    pub fn in_water(&self) -> bool {
+        let (x, y) = (floorf(self.player_x) as i32, floorf(self.player_y) as i32);
+        self.map.tile_at(x, y) == WATER && self.jump.height <= 0.0
+    }
+
+    /// Make a splash if the player has just stepped or landed in water.
+    pub fn update_water(&mut self) {
+        let wading = self.in_water();
+        if wading && !self.wading {
+            sfx::play(&sfx::SPLASH);
+        }
+        self.wading = wading;
+    }
+
+    /// How far the screen is tinted towards [`TINT`], from 0.0 to 1.0.
+    pub fn water_tint(&self) -> f32 {
+        if self.wading {
+            TINT_AMOUNT
+        } else {
+            0.0
+        }
+    }
+}
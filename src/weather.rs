@@ -0,0 +1,194 @@
+//! Rain and snow falling over the open parts of a level, and thunder in storms.
+//!
+//! Drops live in screen space rather than in the world, and are only drawn in
+//! the columns that look out under the sky, or everywhere while the player
+//! is out under it. Moving and turning push them
+//! around the screen so they still feel like they're out there.
+
+use crate::{
+    framebuffer::SCREEN_SIZE,
+    math::{cosf, sinf},
+    rect,
+    rng::Rng,
+    sfx, State, DRAW_COLORS, FOV,
+};
+
+/// What's falling from the sky over a level.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Weather {
+    Clear,
+    /// Rain, with the odd flash of lightning and roll of thunder if `thunder`
+    /// is set.
+    Rain {
+        thunder: bool,
+    },
+    Snow,
+}
+
+/// How many drops are on screen at once.
+const DROPS: usize = 48;
+
+/// How far a raindrop falls each update, in pixels.
+const RAIN_SPEED: f32 = 5.0;
+/// How long the streak left by a raindrop is, in pixels.
+const RAIN_LENGTH: u32 = 4;
+/// How far a snowflake falls each update, in pixels.
+const SNOW_SPEED: f32 = 0.7;
+
+/// How far drops are pushed out from the middle of the screen for each map
+/// unit the player walks forward.
+const FORWARD_SPREAD: f32 = 1.5;
+
+/// The fewest updates between thunderclaps.
+const THUNDER_GAP: u32 = 400;
+/// How many more updates there might randomly be between thunderclaps.
+const THUNDER_JITTER: usize = 600;
+/// How many updates the screen stays lit up after lightning.
+const LIGHTNING_FRAMES: u8 = 24;
+
+/// A drop of rain or flake of snow, somewhere on screen.
+#[derive(Clone, Copy)]
+struct Drop {
+    x: f32,
+    y: f32,
+}
+
+/// Everything falling on screen right now.
+pub struct Precipitation {
+    drops: [Drop; DROPS],
+    rng: Rng,
+    /// Where the player was and which way they were facing on the last
+    /// update, to tell how far they've moved since.
+    last_view: (f32, f32, f32),
+    /// How many updates until the next thunderclap.
+    thunder_in: u32,
+    /// How many more updates the last flash of lightning lasts.
+    lightning: u8,
+}
+
+impl Precipitation {
+    pub const fn new() -> Self {
+        Self {
+            drops: [Drop { x: 0.0, y: 0.0 }; DROPS],
+            rng: Rng::new(0),
+            last_view: (0.0, 0.0, 0.0),
+            thunder_in: THUNDER_GAP,
+            lightning: 0,
+        }
+    }
+
+    /// Start over with the drops scattered all over the screen, and the
+    /// player at `view`.
+    fn scatter(&mut self, view: (f32, f32, f32)) {
+        for drop in &mut self.drops {
+            drop.x = self.rng.below(SCREEN_SIZE) as f32;
+            drop.y = self.rng.below(SCREEN_SIZE) as f32;
+        }
+
+        self.last_view = view;
+        self.thunder_in = THUNDER_GAP;
+        self.lightning = 0;
+    }
+}
+
+impl State {
+    /// Scatter fresh drops over the screen, for the start of a level.
+    pub fn reset_weather(&mut self) {
+        let view = (self.player_x, self.player_y, self.player_angle);
+        self.precipitation.scatter(view);
+    }
+
+    /// Let everything fall a little further, and maybe strike some lightning.
+    pub fn update_weather(&mut self) {
+        let weather = self.level().weather;
+        let (x, y, angle) = (self.player_x, self.player_y, self.player_angle);
+        let precipitation = &mut self.precipitation;
+
+        let (last_x, last_y, last_angle) = precipitation.last_view;
+        precipitation.last_view = (x, y, angle);
+
+        if weather == Weather::Clear || self.reduce_motion {
+            return;
+        }
+
+        // turning sweeps the drops across the screen the other way, and
+        // walking forward spreads them out like they're rushing past.
+        let mut sweep = (angle - last_angle) / FOV * SCREEN_SIZE as f32;
+        if self.mutators.mirror {
+            sweep = -sweep;
+        }
+        let forward = (x - last_x) * cosf(angle) - (y - last_y) * sinf(angle);
+        let center = SCREEN_SIZE as f32 / 2.0;
+
+        for (idx, drop) in precipitation.drops.iter_mut().enumerate() {
+            drop.x += sweep + (drop.x - center) * forward * FORWARD_SPREAD;
+            drop.y += match weather {
+                Weather::Snow => {
+                    // flakes drift from side to side as they fall.
+                    drop.x += sinf(drop.y / 16.0 + idx as f32) * 0.3;
+                    SNOW_SPEED
+                }
+                _ => RAIN_SPEED,
+            };
+
+            // start drops that have left the screen over again at the top.
+            let size = SCREEN_SIZE as f32;
+            if drop.y >= size || drop.x < 0.0 || drop.x >= size {
+                drop.x = precipitation.rng.below(SCREEN_SIZE) as f32;
+                drop.y = 0.0;
+            }
+        }
+
+        precipitation.lightning = precipitation.lightning.saturating_sub(1);
+        if weather == (Weather::Rain { thunder: true }) {
+            precipitation.thunder_in = precipitation.thunder_in.saturating_sub(1);
+
+            if precipitation.thunder_in == 0 {
+                precipitation.lightning = LIGHTNING_FRAMES;
+                precipitation.thunder_in =
+                    THUNDER_GAP + precipitation.rng.below(THUNDER_JITTER) as u32;
+
+                sfx::play(&sfx::THUNDER);
+            }
+        }
+    }
+
+    /// Draw everything that's falling, wherever the sky can be seen.
+    pub fn draw_weather(&self) {
+        let weather = self.level().weather;
+        if weather == Weather::Clear || self.reduce_motion {
+            return;
+        }
+
+        let player_outdoor = self.point_is_outdoor(self.player_x, self.player_y);
+
+        for drop in &self.precipitation.drops {
+            let (x, y) = (drop.x as i32, drop.y as i32);
+            if !player_outdoor && !self.view[self.ray_at(x as usize)].outdoor {
+                continue;
+            }
+
+            unsafe {
+                match weather {
+                    // a shadow under each flake keeps it from vanishing into
+                    // light floors.
+                    Weather::Snow => {
+                        *DRAW_COLORS = 0x3;
+                        rect(x, y + 1, 1, 1);
+                        *DRAW_COLORS = 0x1;
+                        rect(x, y, 1, 1);
+                    }
+                    _ => {
+                        *DRAW_COLORS = 0x2;
+                        rect(x, y, 1, RAIN_LENGTH);
+                    }
+                }
+            }
+        }
+    }
+
+    /// How brightly the screen is lit up by lightning, from 0.0 to 1.0.
+    pub fn lightning(&self) -> f32 {
+        self.precipitation.lightning as f32 / LIGHTNING_FRAMES as f32
+    }
+}